@@ -15,6 +15,10 @@ import SettingsApplicator from '@/components/global/SettingsApplicator';
 import DailyTaskRefresh from '@/components/global/DailyTaskRefresh';
 import GlobalStatusDisplay from '@/components/global/GlobalStatusDisplay';
 import ScheduledReportGenerator from '@/components/global/ScheduledReportGenerator';
+import TrayEventListener from '@/components/global/TrayEventListener';
+import CommandLineTaskListener from '@/components/global/CommandLineTaskListener';
+import RecurringTaskListener from '@/components/global/RecurringTaskListener';
+import QuitConfirmation from '@/components/global/QuitConfirmation';
 import ScheduledReportModal from '@/components/global/ScheduledReportModal';
 import LoadingSpinner from '@/components/ui/LoadingSpinner';
 import { OnboardingScreen } from '@/components/features/onboarding/OnboardingScreen';
@@ -81,10 +85,14 @@ const App: React.FC = () => {
             <SettingsApplicator />
             <DailyTaskRefresh />
             <ScheduledReportGenerator />
+            <TrayEventListener />
+            <CommandLineTaskListener />
+            <RecurringTaskListener />
 
             {/* Global UI components */}
             <GlobalStatusDisplay />
             <ScheduledReportModal />
+            <QuitConfirmation />
 
             {/* Main application content with routing */}
             <Suspense fallback={<LoadingSpinner />}>
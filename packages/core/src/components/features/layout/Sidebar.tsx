@@ -282,7 +282,7 @@ const Sidebar: React.FC = () => {
         }
     }, [listToDelete, currentFilter, navigate, deleteList]);
 
-    const myListsToDisplay = useMemo(() => userLists?.filter(list => list.name !== 'Inbox') ?? [], [userLists]);
+    const myListsToDisplay = useMemo(() => userLists?.filter(list => list.name !== 'Inbox' && !list.archived) ?? [], [userLists]);
     const inboxList = useMemo(() => userLists?.find(list => list.name === 'Inbox'), [userLists]);
     const tagsToDisplay = useMemo(() => userTags, [userTags]);
 
@@ -11,6 +11,7 @@ import {
     isSettingsOpenAtom,
     preferencesSettingsAtom,
     preferencesSettingsLoadingAtom,
+    quickAddRequestAtom,
     rawSearchResultsAtom,
     searchTermAtom,
     selectedTaskIdAtom,
@@ -141,6 +142,7 @@ const TaskList: React.FC<{ title: string }> = ({ title: pageTitle }) => {
     const aiSettings = useAtomValue(aiSettingsAtom);
     const userProfile = useAtomValue(userProfileAtom);
     const isLoadingPreferences = useAtomValue(preferencesSettingsLoadingAtom);
+    const quickAddRequest = useAtomValue(quickAddRequestAtom);
     const preferences = useMemo(() => preferencesData ?? defaultPreferencesSettingsForApi(), [preferencesData]);
     const addNotification = useSetAtom(addNotificationAtom);
 
@@ -242,6 +244,18 @@ const TaskList: React.FC<{ title: string }> = ({ title: pageTitle }) => {
 
     }, [preferences, availableListsForNewTask, isLoadingPreferences, currentFilterGlobal, todayKey]);
 
+    // Quick add (e.g. from the tray menu): capture into the default list and focus the input
+    useEffect(() => {
+        if (quickAddRequest === 0 || isLoadingPreferences) return;
+        if (availableListsForNewTask.includes(preferences.defaultNewTaskList)) {
+            setNewTaskListState(preferences.defaultNewTaskList);
+        } else {
+            setNewTaskListState('Inbox');
+        }
+        setTimeout(() => newTaskTitleInputRef.current?.focus(), 0);
+        // eslint-disable-next-line react-hooks/exhaustive-deps
+    }, [quickAddRequest, isLoadingPreferences]);
+
     // Auto-enable AI task input when alwaysUseAITask is enabled AND config is valid
     useEffect(() => {
         if (isLoadingPreferences) return;
@@ -293,7 +307,7 @@ const TaskList: React.FC<{ title: string }> = ({ title: pageTitle }) => {
                 break;
         }
         if (currentFilterGlobal !== 'completed' && currentFilterGlobal !== 'trash') {
-            filtered.sort((a, b) => (a.order ?? 0) - (b.order ?? 0));
+            filtered.sort((a, b) => Number(b.pinned ?? false) - Number(a.pinned ?? false) || (a.order ?? 0) - (b.order ?? 0));
         }
         return { tasksToDisplay: filtered, isGroupedView: false, isSearching: false };
     }, [searchTerm, currentFilterGlobal, groupedTasks, rawSearchResults, allTasks]);
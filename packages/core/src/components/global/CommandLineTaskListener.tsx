@@ -0,0 +1,78 @@
+import React, { useEffect, useRef } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { useAtomValue } from 'jotai';
+import { tasksAtom, userListsAtom } from '@/store/jotai';
+import { useTaskOperations } from '@/hooks/useTaskOperations';
+import { isTauri } from '@/utils/networkUtils';
+
+interface CliAddTaskPayload {
+    title: string;
+    listName: string | null;
+}
+
+/**
+ * A global, non-visual component that adds tasks captured with `tada --add "..." --list ...`
+ * while the app is already running.
+ *
+ * On a cold start the Rust backend writes the task to the database itself; a second launch
+ * is forwarded here as `cli-add-task`, since this window owns the task cache.
+ */
+const CommandLineTaskListener: React.FC = () => {
+    const allTasks = useAtomValue(tasksAtom);
+    const allUserLists = useAtomValue(userListsAtom);
+    const { createTask } = useTaskOperations();
+
+    // The listener is registered once, so it reads the latest data through refs
+    const tasksRef = useRef(allTasks);
+    const listsRef = useRef(allUserLists);
+    tasksRef.current = allTasks;
+    listsRef.current = allUserLists;
+
+    useEffect(() => {
+        if (!isTauri()) return;
+
+        let unlisten: (() => void) | undefined;
+        listen<CliAddTaskPayload>('cli-add-task', (event) => {
+            const { title, listName } = event.payload;
+            const lists = listsRef.current ?? [];
+            const targetList = lists.find(l => l.name === (listName ?? 'Inbox')) ?? lists.find(l => l.name === 'Inbox');
+            if (!targetList) {
+                console.error('[CommandLineTaskListener] Could not find a list for the new task.');
+                return;
+            }
+
+            const topTask = (tasksRef.current ?? [])
+                .filter(t => !t.completed && t.listName !== 'Trash')
+                .sort((a, b) => (a.order ?? 0) - (b.order ?? 0))[0];
+
+            createTask({
+                title,
+                completed: false,
+                completedAt: null,
+                listName: targetList.name,
+                listId: targetList.id,
+                completePercentage: null,
+                startDate: null,
+                dueDate: null,
+                priority: null,
+                order: topTask?.order ? topTask.order - 1000 : Date.now(),
+                content: '',
+                tags: [],
+                subtasks: []
+            });
+        }).then(cleanup => {
+            unlisten = cleanup;
+        }).catch(error => {
+            console.error('[CommandLineTaskListener] Failed to listen for command-line tasks:', error);
+        });
+
+        return () => {
+            unlisten?.();
+        };
+    }, [createTask]);
+
+    return null;
+};
+
+CommandLineTaskListener.displayName = 'CommandLineTaskListener';
+export default CommandLineTaskListener;
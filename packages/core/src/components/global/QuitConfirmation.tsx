@@ -0,0 +1,54 @@
+import React, { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { useTranslation } from 'react-i18next';
+import ConfirmDeleteModalRadix from '@/components/ui/ConfirmDeleteModal';
+import { isTauri } from '@/utils/networkUtils';
+
+/**
+ * Asks before quitting while a scheduled report is still being generated.
+ *
+ * The Rust backend emits `confirm-quit` instead of exiting when "Quit" is picked from
+ * the tray mid-generation; confirming calls `confirm_quit` to exit for real.
+ */
+const QuitConfirmation: React.FC = () => {
+    const { t } = useTranslation();
+    const [isOpen, setIsOpen] = useState(false);
+
+    useEffect(() => {
+        if (!isTauri()) return;
+
+        let unlisten: (() => void) | undefined;
+        listen('confirm-quit', () => setIsOpen(true)).then(cleanup => {
+            unlisten = cleanup;
+        }).catch(error => {
+            console.error('[QuitConfirmation] Failed to listen for quit requests:', error);
+        });
+
+        return () => {
+            unlisten?.();
+        };
+    }, []);
+
+    const handleClose = useCallback(() => setIsOpen(false), []);
+    const handleConfirm = useCallback(() => {
+        setIsOpen(false);
+        invoke('confirm_quit').catch(error => console.error('[QuitConfirmation] Failed to quit:', error));
+    }, []);
+
+    return (
+        <ConfirmDeleteModalRadix
+            isOpen={isOpen}
+            onClose={handleClose}
+            onConfirm={handleConfirm}
+            itemTitle=""
+            title={t('scheduledReport.quitWhileGenerating.title')}
+            description={t('scheduledReport.quitWhileGenerating.description')}
+            confirmText={t('scheduledReport.quitWhileGenerating.confirmText')}
+            confirmVariant="danger"
+        />
+    );
+};
+
+QuitConfirmation.displayName = 'QuitConfirmation';
+export default QuitConfirmation;
@@ -0,0 +1,39 @@
+import React, { useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { useSetAtom } from 'jotai';
+import { RESET } from 'jotai/utils';
+import { tasksAtom } from '@/store/jotai';
+import { isTauri } from '@/utils/networkUtils';
+
+/**
+ * A global, non-visual component that reloads the task list once the desktop backend has
+ * created the next instance of a completed recurring task.
+ *
+ * The storage service adds the new instances to its cache before broadcasting
+ * `recurring-tasks-created`, so resetting the atom picks them up.
+ */
+const RecurringTaskListener: React.FC = () => {
+    const setTasks = useSetAtom(tasksAtom);
+
+    useEffect(() => {
+        if (!isTauri()) return;
+
+        let unlisten: (() => void) | undefined;
+        listen<string[]>('recurring-tasks-created', () => {
+            setTasks(RESET);
+        }).then(cleanup => {
+            unlisten = cleanup;
+        }).catch(error => {
+            console.error('[RecurringTaskListener] Failed to listen for recurring tasks:', error);
+        });
+
+        return () => {
+            unlisten?.();
+        };
+    }, [setTasks]);
+
+    return null;
+};
+
+RecurringTaskListener.displayName = 'RecurringTaskListener';
+export default RecurringTaskListener;
@@ -1,7 +1,7 @@
 import React, { useEffect, useRef, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import { useAtomValue, useSetAtom } from 'jotai';
+import { useAtom, useAtomValue, useSetAtom } from 'jotai';
 import {
     aiSettingsAtom,
     preferencesSettingsAtom,
@@ -27,7 +27,7 @@ import { isTauri } from '@/utils/networkUtils';
 const ScheduledReportGenerator: React.FC = () => {
     const { t } = useTranslation();
     const aiSettings = useAtomValue(aiSettingsAtom);
-    const preferences = useAtomValue(preferencesSettingsAtom);
+    const [preferences, setPreferences] = useAtom(preferencesSettingsAtom);
     const userProfile = useAtomValue(userProfileAtom);
     const tasksData = useAtomValue(tasksAtom);
     const setScheduledReportModal = useSetAtom(scheduledReportModalAtom);
@@ -66,6 +66,29 @@ const ScheduledReportGenerator: React.FC = () => {
         syncSettingsToBackend();
     }, [preferences?.scheduleSettings]);
 
+    /**
+     * Mirror schedule changes made outside Settings (e.g. the tray toggle) into preferences
+     */
+    useEffect(() => {
+        if (!isTauri()) return;
+
+        let unlisten: (() => void) | undefined;
+        listen<{ enabled: boolean }>('schedule-settings-changed', (event) => {
+            setPreferences(prev => prev.scheduleSettings.enabled === event.payload.enabled ? prev : {
+                ...prev,
+                scheduleSettings: { ...prev.scheduleSettings, enabled: event.payload.enabled },
+            });
+        }).then(cleanup => {
+            unlisten = cleanup;
+        }).catch(error => {
+            console.error('[ScheduledReportGenerator] Failed to listen for schedule changes:', error);
+        });
+
+        return () => {
+            unlisten?.();
+        };
+    }, [setPreferences]);
+
     const generateReport = useCallback(async (triggerDate?: string) => {
         // Skip if already generating
         if (isGeneratingRef.current) {
@@ -101,6 +124,11 @@ const ScheduledReportGenerator: React.FC = () => {
         console.log('[ScheduledReportGenerator] ⏰ Triggering scheduled report generation...');
         console.log('[ScheduledReportGenerator] Date:', todayDateStr);
 
+        if (isTauri()) {
+            invoke('set_tray_status', { state: 'busy' }).catch(error => console.error('[ScheduledReportGenerator] Failed to set tray status:', error));
+            invoke('set_report_in_progress', { inProgress: true }).catch(error => console.error('[ScheduledReportGenerator] Failed to flag report generation:', error));
+        }
+
         try {
             // Get today's completed tasks
             const todayStart = startOfDay(now).getTime();
@@ -203,6 +231,10 @@ const ScheduledReportGenerator: React.FC = () => {
             lastGeneratedDateRef.current = null;
         } finally {
             isGeneratingRef.current = false;
+            if (isTauri()) {
+                invoke('set_tray_status', { state: 'idle' }).catch(error => console.error('[ScheduledReportGenerator] Failed to set tray status:', error));
+                invoke('set_report_in_progress', { inProgress: false }).catch(error => console.error('[ScheduledReportGenerator] Failed to flag report generation:', error));
+            }
         }
     }, [aiSettings, preferences, userProfile, tasksData, t, setScheduledReportModal, setStoredSummaries, setEchoReports]);
 
@@ -261,6 +293,9 @@ const ScheduledReportGenerator: React.FC = () => {
 
                     unlistenRef.current = unlisten;
                     console.log('[ScheduledReportGenerator] ✅ Tauri event listener registered');
+
+                    // Let the backend flush any triggers queued while the UI was booting
+                    await invoke('mark_frontend_ready');
                 } catch (error) {
                     console.error('[ScheduledReportGenerator] Failed to set up Tauri listener:', error);
                 }
@@ -0,0 +1,49 @@
+import React, { useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { useSetAtom } from 'jotai';
+import { useNavigate } from 'react-router-dom';
+import { quickAddRequestAtom, selectedTaskIdAtom } from '@/store/jotai';
+import { isTauri } from '@/utils/networkUtils';
+
+/**
+ * A global, non-visual component that handles actions picked from the tray menu.
+ *
+ * The Rust backend shows the main window and emits `tray-quick-add` (focus the new-task
+ * input) or `open-task` (select a task from the "Recent" submenu).
+ */
+const TrayEventListener: React.FC = () => {
+    const navigate = useNavigate();
+    const setQuickAddRequest = useSetAtom(quickAddRequestAtom);
+    const setSelectedTaskId = useSetAtom(selectedTaskIdAtom);
+
+    useEffect(() => {
+        if (!isTauri()) return;
+
+        const unlisteners: Array<() => void> = [];
+        const register = async () => {
+            try {
+                unlisteners.push(await listen('tray-quick-add', () => {
+                    navigate('/all');
+                    setQuickAddRequest(prev => prev + 1);
+                }));
+                unlisteners.push(await listen<string>('open-task', (event) => {
+                    navigate('/all');
+                    // Select after the route change, which clears the selection when the filter changes
+                    setTimeout(() => setSelectedTaskId(event.payload), 0);
+                }));
+            } catch (error) {
+                console.error('[TrayEventListener] Failed to set up listeners:', error);
+            }
+        };
+        register();
+
+        return () => {
+            unlisteners.forEach(unlisten => unlisten());
+        };
+    }, [navigate, setQuickAddRequest, setSelectedTaskId]);
+
+    return null;
+};
+
+TrayEventListener.displayName = 'TrayEventListener';
+export default TrayEventListener;
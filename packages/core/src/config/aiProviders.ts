@@ -5,7 +5,7 @@ export interface AIModel {
 }
 
 export interface AIProvider {
-    id: 'openai' | 'claude' | 'gemini' | 'xai' | 'cohere' | 'replicate' | 'together' | 'groq' | 'perplexity' | 'huggingface' | 'openrouter' | 'oneapi' | 'newapi' | 'moonshot' | 'deepseek' | 'qwen' | 'zhipu' | 'baidu' | 'tencent' | 'bytedance' | 'baichuan' | 'minimax' | 'sensetime' | 'iflytek' | '302' | 'siliconflow' | 'ollama' | 'custom';
+    id: 'openai' | 'claude' | 'gemini' | 'xai' | 'cohere' | 'replicate' | 'together' | 'groq' | 'perplexity' | 'huggingface' | 'openrouter' | 'oneapi' | 'newapi' | 'moonshot' | 'deepseek' | 'qwen' | 'zhipu' | 'baidu' | 'tencent' | 'bytedance' | 'baichuan' | 'minimax' | 'sensetime' | 'iflytek' | '302' | 'siliconflow' | 'ollama' | 'lmstudio' | 'custom';
     nameKey: string; // Translation key
     models: AIModel[]; // Default/recommended models
     requiresApiKey: boolean;
@@ -221,6 +221,18 @@ export const AI_PROVIDERS: AIProvider[] = [
         })),
         models: []
     },
+    {
+        id: 'lmstudio',
+        nameKey: 'aiProviders.lmstudio',
+        requiresApiKey: false,
+        requiresBaseUrl: true,
+        defaultBaseUrl: 'http://localhost:1234',
+        apiEndpoint: '/v1/chat/completions',
+        listModelsEndpoint: '/v1/models',
+        getHeaders: () => ({ 'Content-Type': 'application/json' }),
+        parseModels: (data) => data.data?.map((m: any) => ({ id: m.id, name: m.id })) || [],
+        models: []
+    },
     {
         id: 'custom',
         nameKey: 'aiProviders.custom',
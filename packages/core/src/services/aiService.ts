@@ -2,7 +2,8 @@ import { AISettings, StoredSummary, Task, EchoReport, ProxySettings, UserProfile
 import storageManager from './storageManager.ts';
 import { AI_PROVIDERS, AIModel, AIProvider } from "@/config/aiProviders";
 import { stripBase64Images } from "@/utils/markdownUtils";
-import { fetchWithProxy } from "@/utils/networkUtils";
+import { fetchWithProxy, isTauri } from "@/utils/networkUtils";
+import { invoke } from "@tauri-apps/api/core";
 import { aiCache, withCache } from './ai/cache';
 import { withRetry } from './ai/retry';
 
@@ -148,6 +149,12 @@ const getProxySettings = (): ProxySettings | undefined => {
     }
 };
 
+/** Result of the desktop backend's `list_available_models` command. */
+interface NativeModelList {
+    supported: boolean;
+    models: string[];
+}
+
 /**
  * Fetches the list of available models from the configured AI provider's API.
  * @param settings The current AI settings.
@@ -162,6 +169,14 @@ export const fetchProviderModels = async (settings: AISettings): Promise<AIModel
         throw new Error("API key is required to fetch models.");
     }
 
+    // The desktop backend lists models without CORS and through the saved proxy
+    if (isTauri()) {
+        const result = await invoke<NativeModelList>('list_available_models', { config: settings });
+        if (result.supported) {
+            return result.models.map(id => ({ id, name: id }));
+        }
+    }
+
     const endpoint = getApiEndpoint(settings, 'models');
     const headers = (provider.id === 'gemini') ? { 'Content-Type': 'application/json' } : provider.getHeaders(settings.apiKey);
     const proxySettings = getProxySettings();
@@ -188,6 +203,16 @@ export const fetchProviderModels = async (settings: AISettings): Promise<AIModel
     }
 };
 
+/** Result of the desktop backend's `test_ai_connection` command. */
+interface NativeTestResult {
+    ok: boolean;
+    status: number | null;
+    latencyMs: number;
+    preview: string;
+    message: string;
+    failingHop: 'proxy' | 'endpoint' | null;
+}
+
 /**
  * Tests the connection to the configured AI provider to verify settings.
  * @param settings The current AI settings.
@@ -205,6 +230,17 @@ export const testConnection = async (settings: AISettings): Promise<boolean> =>
         throw new Error("Model is required to test connection.");
     }
 
+    // The desktop backend tests without CORS, through the saved proxy, and explains common
+    // failures (bad key, wrong base URL, dead proxy)
+    if (isTauri()) {
+        const result = await invoke<NativeTestResult>('test_ai_connection', { config: settings });
+        if (!result.ok) {
+            console.error('Connection test failed:', result);
+            throw new Error(result.message);
+        }
+        return true;
+    }
+
     const proxySettings = getProxySettings();
 
     try {
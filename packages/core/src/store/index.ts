@@ -48,6 +48,7 @@ export {
     isZenFullScreenAtom,
     aiConnectionStatusAtom,
     aiListAnalyzingTaskIdsAtom,
+    quickAddRequestAtom,
     scheduledReportModalAtom,
     selectedEchoReportIdAtom,
     selectedSummaryIdAtom,
@@ -14,6 +14,9 @@ export const aiConnectionStatusAtom = atom<'idle' | 'success' | 'error'>('idle')
 
 export const aiListAnalyzingTaskIdsAtom = atom<Set<string>>(new Set<string>());
 
+// --- Quick Add Request Atom (bumped to focus the new-task input, e.g. from the tray) ---
+export const quickAddRequestAtom = atom<number>(0);
+
 // --- Scheduled Report Modal Atom ---
 export const scheduledReportModalAtom = atom<ScheduledReportData | null>(null);
 
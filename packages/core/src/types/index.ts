@@ -13,6 +13,7 @@ export interface List {
     icon?: string | null;
     color?: string | null;
     order?: number | null;
+    archived?: boolean; // Hidden from the sidebar; its tasks still show everywhere else
 }
 
 /**
@@ -21,6 +22,7 @@ export interface List {
 export interface Subtask {
     id: string;
     parentId: string;
+    parentSubtaskId?: string | null;
     title: string;
     completed: boolean;
     completedAt: number | null;
@@ -51,6 +53,10 @@ export interface Task {
     tags?: string[];
     priority?: number | null;
     groupCategory: TaskGroupCategory;
+    recurrence?: string | null; // RFC 5545 RRULE, e.g. 'FREQ=WEEKLY;BYDAY=MO,WE'
+    estimatedMinutes?: number | null;
+    actualMinutes?: number; // Added up from stopped timers
+    pinned?: boolean; // Sorted above unpinned tasks within its group
     subtasks?: Subtask[];
 }
 
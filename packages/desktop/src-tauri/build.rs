@@ -1,3 +1,44 @@
+/// Trimmed output of a git command, or `None` outside a checkout or without git
+fn git(args: &[&str]) -> Option<String> {
+    std::process::Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|output| output.trim().to_string())
+        .filter(|output| !output.is_empty())
+}
+
 fn main() {
+    // Shown by `get_app_info`; builds outside a git checkout report "unknown"
+    let commit = git(&["rev-parse", "--short", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    let built_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or(0);
+    println!("cargo:rustc-env=TADA_GIT_COMMIT={}", commit);
+    println!("cargo:rustc-env=TADA_BUILD_TIMESTAMP={}", built_at);
+
+    // HEAD only changes on a checkout; a commit moves the branch ref, which lives in its own
+    // file or, once git packs it, in packed-refs. Cargo reruns on every build for a missing
+    // path, so a ref that is only packed is watched through the folder its file would appear in
+    let mut watched = vec!["HEAD".to_string(), "packed-refs".to_string()];
+    if let Some(branch) = git(&["rev-parse", "--symbolic-full-name", "HEAD"]).filter(|name| name != "HEAD") {
+        watched.push(branch);
+    }
+    for name in watched {
+        let Some(path) = git(&["rev-parse", "--git-path", &name]).map(std::path::PathBuf::from) else {
+            continue;
+        };
+        let path = match path.parent() {
+            Some(folder) if !path.exists() && name != "packed-refs" => folder.to_path_buf(),
+            _ => path,
+        };
+        if path.exists() {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+    }
+
     tauri_build::build()
-}
\ No newline at end of file
+}
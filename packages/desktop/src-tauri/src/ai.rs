@@ -0,0 +1,1703 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_http::reqwest;
+
+use crate::error::CmdError;
+use crate::{db, AppState};
+
+/// Settings key of the provider/apiKey/model/baseUrl row the frontend edits
+pub(crate) const AI_SETTINGS_KEY: &str = "ai";
+/// Settings key of the network proxy row the frontend edits
+const PROXY_SETTINGS_KEY: &str = "proxy";
+/// Upper bound for a chat completion; reports over many tasks take a while
+const CHAT_TIMEOUT: Duration = Duration::from_secs(120);
+/// Longest silence tolerated between two chunks of a streamed reply
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Time allowed to reach the provider at all
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// Retries after the first attempt when the config doesn't say
+const DEFAULT_MAX_RETRIES: u32 = 2;
+/// First backoff delay; doubles with every retry
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+/// Longest wait between two attempts, also caps a provider's `Retry-After`
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+/// Anthropic requires `max_tokens`; matches the frontend adapter's default
+const CLAUDE_MAX_TOKENS: u32 = 4096;
+
+/// Upper bound for a connection test, long enough for a cold local model
+const TEST_TIMEOUT: Duration = Duration::from_secs(20);
+/// How much of the response body a connection test hands back
+const PREVIEW_CHARS: usize = 200;
+/// How long an identical chat completion is answered from the cache
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+/// Where Ollama listens when no base URL is configured
+const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+/// Where LM Studio's local server listens when no base URL is configured
+const LMSTUDIO_BASE_URL: &str = "http://localhost:1234";
+/// Root of Gemini's native API; each model has its own `:generateContent` endpoint
+const GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+/// How API keys of the common providers start, for spotting them in error text
+const KEY_PREFIXES: &[&str] = &["sk-", "AIza"];
+/// Words a secret follows in headers and JSON, e.g. `Bearer <key>` or `"api_key": "<key>"`
+const KEY_LABELS: &[&str] = &["bearer", "api_key", "apikey", "api-key", "x-api-key", "x-goog-api-key"];
+/// Gemini finish reasons that mean the reply was withheld rather than finished
+const GEMINI_BLOCK_REASONS: &[&str] = &["SAFETY", "BLOCKLIST", "PROHIBITED_CONTENT", "SPII", "RECITATION"];
+
+/// AI providers by the ids the frontend stores; the hosted OpenAI-compatible ones (groq,
+/// deepseek, ...) share one dialect and only differ in endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "String")]
+pub enum Provider {
+    OpenAi,
+    Claude,
+    Gemini,
+    Ollama,
+    LmStudio,
+    Custom,
+    Compatible(String),
+}
+
+impl From<String> for Provider {
+    fn from(id: String) -> Self {
+        match id.as_str() {
+            "openai" => Provider::OpenAi,
+            "claude" => Provider::Claude,
+            "gemini" => Provider::Gemini,
+            "ollama" => Provider::Ollama,
+            "lmstudio" => Provider::LmStudio,
+            "custom" => Provider::Custom,
+            _ => Provider::Compatible(id),
+        }
+    }
+}
+
+impl Provider {
+    pub fn id(&self) -> &str {
+        match self {
+            Provider::OpenAi => "openai",
+            Provider::Claude => "claude",
+            Provider::Gemini => "gemini",
+            Provider::Ollama => "ollama",
+            Provider::LmStudio => "lmstudio",
+            Provider::Custom => "custom",
+            Provider::Compatible(id) => id,
+        }
+    }
+
+    /// Local servers and custom endpoints often run without a key
+    fn requires_api_key(&self) -> bool {
+        !matches!(self, Provider::Ollama | Provider::LmStudio | Provider::Custom)
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// Provider settings, same shape as the `ai` settings row
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiConfig {
+    pub provider: Provider,
+    #[serde(default)]
+    pub api_key: String,
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// `http://`, `https://` or `socks5://` proxy; an empty string forces a direct connection
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    /// Whole-request timeout (for streams: longest silence between chunks)
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Retries on 429, 5xx and connection failures; other errors are never retried
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    /// Seconds an identical request is answered from the cache; 0 turns caching off
+    #[serde(default)]
+    pub cache_ttl_secs: Option<u64>,
+    /// Tried in order when this provider is unavailable
+    #[serde(default)]
+    pub fallbacks: Vec<AiConfig>,
+}
+
+/// Per-call overrides for `ai_chat_completion`; omitted fields come from the `ai` settings row
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiConfigOverrides {
+    pub provider: Option<Provider>,
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+    pub base_url: Option<String>,
+    pub proxy_url: Option<String>,
+    pub timeout_secs: Option<u64>,
+    pub max_retries: Option<u32>,
+    pub cache_ttl_secs: Option<u64>,
+    pub fallbacks: Option<Vec<AiConfig>>,
+}
+
+/// The `proxy` settings row as the frontend stores it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProxySettings {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    protocol: String,
+    #[serde(default)]
+    host: String,
+    port: Option<u16>,
+    #[serde(default)]
+    auth: bool,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+impl ProxySettings {
+    /// Proxy URL with percent-encoded credentials; `None` when the proxy is off or incomplete
+    fn url(&self) -> Option<String> {
+        let host = self.host.trim();
+        if !self.enabled || host.is_empty() {
+            return None;
+        }
+        let protocol = if self.protocol.is_empty() { "http" } else { &self.protocol };
+        let mut url = Url::parse(&format!("{}://{}", protocol, host)).ok()?;
+        if let Some(port) = self.port {
+            url.set_port(Some(port)).ok()?;
+        }
+        if self.auth {
+            url.set_username(self.username.as_deref().unwrap_or_default()).ok()?;
+            url.set_password(self.password.as_deref()).ok()?;
+        }
+        Some(url.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatOptions {
+    /// Ask for a JSON reply, in whatever form the provider supports
+    #[serde(default)]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// A row of the `ai_usage` table
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageRow {
+    pub id: i64,
+    pub created_at: i64,
+    pub provider: String,
+    pub model: String,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub total_tokens: Option<i64>,
+    pub purpose: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatResponse {
+    pub content: String,
+    /// Provider that answered, which differs from the primary after a fallback
+    pub provider: String,
+    pub model: String,
+    pub usage: Option<TokenUsage>,
+    /// Position in `fallbacks` of the config that answered; `None` for the primary
+    pub fallback_index: Option<usize>,
+}
+
+/// A reply kept for in-session regenerations, see `AppState::ai_cache`
+pub struct CachedResponse {
+    response: ChatResponse,
+    stored_at: Instant,
+}
+
+/// Failure of an AI request; serialized as `{ kind, message }` so the frontend can tell a
+/// blocked prompt apart from a broken setup
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum AiError {
+    Request(String),
+    /// The provider couldn't be reached, timed out or failed on its side (5xx); the only
+    /// failures worth trying a fallback for
+    Unavailable(String),
+    /// The provider refused to answer, e.g. Gemini's safety filters
+    SafetyBlocked(String),
+    /// The provider rejected the API key (401 or 403), so asking for it again is the fix
+    Unauthorized(String),
+    /// Stopped by `cancel_ai_request` before the reply arrived
+    Cancelled(String),
+}
+
+impl fmt::Display for AiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AiError::Request(message)
+            | AiError::Unavailable(message)
+            | AiError::Cancelled(message)
+            | AiError::Unauthorized(message) => f.write_str(message),
+            AiError::SafetyBlocked(reason) => {
+                write!(f, "Blocked by the provider's safety filters: {}", reason)
+            }
+        }
+    }
+}
+
+impl AiError {
+    /// The `kind` it's serialized with
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AiError::Request(_) => "request",
+            AiError::Unavailable(_) => "unavailable",
+            AiError::SafetyBlocked(_) => "safetyBlocked",
+            AiError::Cancelled(_) => "cancelled",
+            AiError::Unauthorized(_) => "unauthorized",
+        }
+    }
+}
+
+impl From<String> for AiError {
+    fn from(message: String) -> Self {
+        AiError::Request(message)
+    }
+}
+
+/// Models a provider reports; `supported` is false when it has no listing endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelList {
+    pub supported: bool,
+    pub models: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AiModel {
+    pub id: String,
+    pub name: String,
+}
+
+/// `ai-stream-chunk` payload: the next piece of a streamed reply
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamChunk {
+    request_id: String,
+    delta: String,
+}
+
+/// `ai-stream-done` payload, sent exactly once per stream whether it finished, failed or was cancelled
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamDone {
+    request_id: String,
+    content: String,
+    cancelled: bool,
+    error: Option<AiError>,
+}
+
+/// Outcome of `test_ai_connection`; HTTP failures come back here rather than as errors
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestResult {
+    pub ok: bool,
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub preview: String,
+    pub message: String,
+    /// `"proxy"` or `"endpoint"` when the request never got an HTTP response
+    pub failing_hop: Option<String>,
+}
+
+/// Chat endpoints of the hosted providers, as in the frontend's provider list
+fn default_chat_endpoint(provider: &Provider) -> Option<&'static str> {
+    let id = match provider {
+        Provider::OpenAi => return Some("https://api.openai.com/v1/chat/completions"),
+        Provider::Compatible(id) => id.as_str(),
+        Provider::Claude | Provider::Gemini | Provider::Ollama | Provider::LmStudio | Provider::Custom => {
+            return None;
+        }
+    };
+    Some(match id {
+        "xai" => "https://api.x.ai/v1/chat/completions",
+        "groq" => "https://api.groq.com/openai/v1/chat/completions",
+        "openrouter" => "https://openrouter.ai/api/v1/chat/completions",
+        "siliconflow" => "https://api.siliconflow.cn/v1/chat/completions",
+        "302" => "https://api.302.ai/v1/chat/completions",
+        "moonshot" => "https://api.moonshot.cn/v1/chat/completions",
+        "deepseek" => "https://api.deepseek.com/v1/chat/completions",
+        "zhipu" => "https://open.bigmodel.cn/api/paas/v4/chat/completions",
+        "bytedance" => "https://ark.cn-beijing.volces.com/api/v3/chat/completions",
+        "minimax" => "https://api.minimax.chat/v1/text/chatcompletion_v2",
+        _ => return None,
+    })
+}
+
+fn base_url(config: &AiConfig) -> Option<&str> {
+    config
+        .base_url
+        .as_deref()
+        .map(|url| url.trim().trim_end_matches('/'))
+        .filter(|url| !url.is_empty())
+}
+
+/// Models offered for providers whose API has no model listing
+pub fn available_models(provider: &Provider) -> Vec<AiModel> {
+    let models: &[(&str, &str)] = match provider {
+        Provider::Claude => &[
+            ("claude-3-5-sonnet-20241022", "Claude 3.5 Sonnet"),
+            ("claude-3-5-haiku-20241022", "Claude 3.5 Haiku"),
+            ("claude-3-opus-20240229", "Claude 3 Opus"),
+        ],
+        _ => &[],
+    };
+    models
+        .iter()
+        .map(|&(id, name)| AiModel { id: id.to_string(), name: name.to_string() })
+        .collect()
+}
+
+/// Command for the model picker's built-in suggestions of a provider
+#[tauri::command]
+pub fn get_available_ai_models(provider: Provider) -> Vec<AiModel> {
+    available_models(&provider)
+}
+
+/// Model listing URL: `/v1/models` for OpenAI-style servers, `/api/tags` for Ollama
+fn models_endpoint(config: &AiConfig) -> Option<String> {
+    let chat_url = match &config.provider {
+        Provider::Ollama => {
+            return Some(format!("{}/api/tags", base_url(config).unwrap_or(OLLAMA_BASE_URL)));
+        }
+        Provider::Claude | Provider::Gemini => return None,
+        Provider::LmStudio => openai_chat_url(base_url(config).unwrap_or(LMSTUDIO_BASE_URL)),
+        Provider::Custom => openai_chat_url(base_url(config)?),
+        provider => default_chat_endpoint(provider)?.to_string(),
+    };
+    // Hosted OpenAI-compatible APIs list models next to their chat endpoint
+    chat_url
+        .strip_suffix("/chat/completions")
+        .map(|root| format!("{}/models", root))
+}
+
+/// Command to fetch the model ids a provider currently serves, for the `availableModels` picker
+#[tauri::command]
+pub async fn list_available_models(app_handle: AppHandle, mut config: AiConfig) -> Result<ModelList, CmdError> {
+    let Some(url) = models_endpoint(&config) else {
+        return Ok(ModelList { supported: false, models: Vec::new() });
+    };
+    config.api_key = resolve_api_key(None, config.api_key)?;
+    if config.provider.requires_api_key() && config.api_key.trim().is_empty() {
+        return Err(CmdError::Validation("An API key is required to fetch models".to_string()));
+    }
+    if config.proxy_url.is_none() {
+        config.proxy_url = saved_proxy_url(&db::pool(&app_handle).await?).await?;
+    }
+
+    let client = http_client(&config, TEST_TIMEOUT)?;
+    let mut request = client.get(&url);
+    for (name, value) in headers(&config) {
+        request = request.header(name, value);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| CmdError::Ai(AiError::Unavailable(friendly_transport_message(&config, &url, &e))))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| CmdError::Io(format!("Failed to read models from {}: {}", url, e)))?;
+    if !status.is_success() {
+        let message = friendly_status_message(&config, status.as_u16(), &body);
+        return Err(CmdError::Ai(if matches!(status.as_u16(), 401 | 403) {
+            AiError::Unauthorized(message)
+        } else {
+            AiError::Request(message)
+        }));
+    }
+
+    let body: Value = serde_json::from_str(&body)
+        .map_err(|e| AiError::Request(format!("Failed to parse models from {}: {}", url, e)))?;
+    let (entries, field) = match config.provider {
+        Provider::Ollama => (&body["models"], "name"),
+        _ => (&body["data"], "id"),
+    };
+    let mut models: Vec<String> = entries
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry[field].as_str().map(str::to_string))
+        .collect();
+    models.sort();
+    log::info!("[AI] {} models available from {}", models.len(), config.provider);
+    Ok(ModelList { supported: true, models })
+}
+
+/// OpenAI-style chat URL under a server root, accepting both `http://host:port` and
+/// `http://host:port/v1`
+fn openai_chat_url(base: &str) -> String {
+    if base.ends_with("/v1") {
+        format!("{}/chat/completions", base)
+    } else {
+        format!("{}/v1/chat/completions", base)
+    }
+}
+
+/// Resolves the chat URL: local and custom servers hang off the base URL, hosted
+/// providers use their fixed endpoint
+fn chat_endpoint(config: &AiConfig) -> Result<String, String> {
+    match &config.provider {
+        Provider::Ollama => Ok(format!("{}/api/chat", base_url(config).unwrap_or(OLLAMA_BASE_URL))),
+        Provider::LmStudio => Ok(openai_chat_url(base_url(config).unwrap_or(LMSTUDIO_BASE_URL))),
+        // A base URL here points at an Anthropic-compatible proxy
+        Provider::Claude => Ok(match base_url(config) {
+            Some(base) if base.ends_with("/v1") => format!("{}/messages", base),
+            Some(base) => format!("{}/v1/messages", base),
+            None => "https://api.anthropic.com/v1/messages".to_string(),
+        }),
+        Provider::Gemini => Ok(format!(
+            "{}/models/{}:generateContent",
+            base_url(config).unwrap_or(GEMINI_BASE_URL),
+            config.model.trim_start_matches("models/")
+        )),
+        Provider::Custom => {
+            let base = base_url(config).ok_or("A base URL is required for a custom provider")?;
+            Ok(openai_chat_url(base))
+        }
+        provider => default_chat_endpoint(provider)
+            .map(str::to_string)
+            .ok_or_else(|| format!("Provider '{}' is not supported", provider)),
+    }
+}
+
+/// Streaming differs from a plain completion only in the URL for Gemini (a separate
+/// `:streamGenerateContent` method, as SSE) and in a `stream` flag for everyone else
+fn stream_endpoint(config: &AiConfig) -> Result<String, String> {
+    let url = chat_endpoint(config)?;
+    Ok(match config.provider {
+        Provider::Gemini => url.replacen(":generateContent", ":streamGenerateContent?alt=sse", 1),
+        _ => url,
+    })
+}
+
+fn stream_body(config: &AiConfig, messages: &[ChatMessage], options: &ChatOptions) -> Value {
+    let mut body = chat_body(config, messages, options);
+    if config.provider != Provider::Gemini {
+        body["stream"] = json!(true);
+    }
+    body
+}
+
+fn headers(config: &AiConfig) -> Vec<(&'static str, String)> {
+    let mut headers = vec![("Content-Type", "application/json".to_string())];
+    match config.provider {
+        Provider::Claude => {
+            headers.push(("x-api-key", config.api_key.clone()));
+            headers.push(("anthropic-version", "2023-06-01".to_string()));
+        }
+        // Sent as a header rather than `?key=` so it never shows up in logged URLs
+        Provider::Gemini => headers.push(("x-goog-api-key", config.api_key.clone())),
+        Provider::Ollama => {}
+        _ if config.api_key.is_empty() => {}
+        _ => headers.push(("Authorization", format!("Bearer {}", config.api_key))),
+    }
+    headers
+}
+
+/// Splits messages the way Anthropic and Gemini want them: system prompts go in a separate
+/// field, and the rest must alternate user/assistant, so consecutive turns of one role are merged
+fn split_turns(messages: &[ChatMessage]) -> (Option<String>, Vec<ChatMessage>) {
+    let mut system: Vec<&str> = Vec::new();
+    let mut turns: Vec<ChatMessage> = Vec::new();
+    for message in messages {
+        if message.role == "system" {
+            system.push(&message.content);
+            continue;
+        }
+        let role = if message.role == "assistant" { "assistant" } else { "user" };
+        match turns.last_mut() {
+            Some(last) if last.role == role => {
+                last.content.push_str("\n\n");
+                last.content.push_str(&message.content);
+            }
+            _ => turns.push(ChatMessage { role: role.to_string(), content: message.content.clone() }),
+        }
+    }
+    let system = (!system.is_empty()).then(|| system.join("\n\n"));
+    (system, turns)
+}
+
+/// Chat request body in each provider's dialect. JSON mode differs the most: OpenAI takes
+/// `json_object`, LM Studio only accepts `json_schema` or `text`, Ollama's native API wants
+/// `format: "json"`, and Anthropic has no switch at all (the prompt has to ask)
+fn chat_body(config: &AiConfig, messages: &[ChatMessage], options: &ChatOptions) -> Value {
+    match config.provider {
+        Provider::Claude => {
+            let (system, messages) = split_turns(messages);
+            let mut body = json!({
+                "model": config.model,
+                "messages": messages,
+                "max_tokens": CLAUDE_MAX_TOKENS,
+            });
+            if let Some(system) = system {
+                body["system"] = json!(system);
+            }
+            body
+        }
+        Provider::Gemini => {
+            let (system, turns) = split_turns(messages);
+            let contents: Vec<Value> = turns
+                .iter()
+                .map(|turn| {
+                    let role = if turn.role == "assistant" { "model" } else { "user" };
+                    json!({ "role": role, "parts": [{ "text": turn.content }] })
+                })
+                .collect();
+            let mut body = json!({ "contents": contents });
+            if let Some(system) = system {
+                body["systemInstruction"] = json!({ "parts": [{ "text": system }] });
+            }
+            if options.json {
+                body["generationConfig"] = json!({ "responseMimeType": "application/json" });
+            }
+            body
+        }
+        Provider::Ollama => {
+            let mut body = json!({ "model": config.model, "messages": messages, "stream": false });
+            if options.json {
+                body["format"] = json!("json");
+            }
+            body
+        }
+        Provider::LmStudio => {
+            let mut body = json!({ "model": config.model, "messages": messages });
+            if options.json {
+                body["response_format"] = json!({
+                    "type": "json_schema",
+                    "json_schema": { "name": "response", "schema": { "type": "object" } },
+                });
+            }
+            body
+        }
+        _ => {
+            let mut body = json!({ "model": config.model, "messages": messages });
+            if options.json {
+                body["response_format"] = json!({ "type": "json_object" });
+            }
+            body
+        }
+    }
+}
+
+/// Text of a Gemini response, or why it was blocked: a rejected prompt has no candidates
+/// and a `promptFeedback.blockReason`, a withheld reply has a blocking `finishReason`
+fn gemini_text(body: &Value) -> Result<Option<String>, AiError> {
+    if let Some(reason) = body.pointer("/promptFeedback/blockReason").and_then(Value::as_str) {
+        return Err(AiError::SafetyBlocked(reason.to_string()));
+    }
+    let text = body.pointer("/candidates/0/content/parts").and_then(Value::as_array).map(|parts| {
+        parts
+            .iter()
+            .filter_map(|part| part.get("text").and_then(Value::as_str))
+            .collect::<String>()
+    });
+    let finish_reason = body.pointer("/candidates/0/finishReason").and_then(Value::as_str);
+    if let Some(reason) = finish_reason
+        && GEMINI_BLOCK_REASONS.contains(&reason)
+        && text.as_deref().unwrap_or_default().is_empty()
+    {
+        return Err(AiError::SafetyBlocked(reason.to_string()));
+    }
+    Ok(text)
+}
+
+/// Pulls the reply text and token counts out of a provider response
+fn parse_chat_response(config: &AiConfig, body: &Value) -> Result<ChatResponse, AiError> {
+    let count = |pointer: &str| body.pointer(pointer).and_then(Value::as_u64);
+    let (content, usage) = match config.provider {
+        Provider::Gemini => (
+            gemini_text(body)?,
+            count("/usageMetadata/promptTokenCount").zip(count("/usageMetadata/candidatesTokenCount")),
+        ),
+        Provider::Claude => (
+            body.get("content").and_then(Value::as_array).map(|blocks| {
+                blocks
+                    .iter()
+                    .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+                    .filter_map(|block| block.get("text").and_then(Value::as_str))
+                    .collect::<String>()
+            }),
+            count("/usage/input_tokens").zip(count("/usage/output_tokens")),
+        ),
+        Provider::Ollama => (
+            body.pointer("/message/content").and_then(Value::as_str).map(str::to_string),
+            count("/prompt_eval_count").zip(count("/eval_count")),
+        ),
+        _ => (
+            body.pointer("/choices/0/message/content").and_then(Value::as_str).map(str::to_string),
+            count("/usage/prompt_tokens").zip(count("/usage/completion_tokens")),
+        ),
+    };
+
+    let content = content.ok_or_else(|| {
+        let body = redact_secrets(&body.to_string(), &config.api_key);
+        format!("Unexpected response from {}: {}", config.provider, preview(&body))
+    })?;
+    Ok(ChatResponse {
+        content,
+        provider: config.provider.id().to_string(),
+        model: body
+            .get("model")
+            .or_else(|| body.get("modelVersion"))
+            .and_then(Value::as_str)
+            .unwrap_or(&config.model)
+            .to_string(),
+        usage: usage.map(|(prompt_tokens, completion_tokens)| TokenUsage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: count("/usage/total_tokens")
+                .or_else(|| count("/usageMetadata/totalTokenCount"))
+                .unwrap_or(prompt_tokens + completion_tokens),
+        }),
+        fallback_index: None,
+    })
+}
+
+fn proxy_url(config: &AiConfig) -> Option<&str> {
+    config.proxy_url.as_deref().map(str::trim).filter(|url| !url.is_empty())
+}
+
+/// A proxy URL with the password masked, for logs and error messages
+fn redacted(url: &Url) -> String {
+    let mut url = url.clone();
+    if url.password().is_some() {
+        let _ = url.set_password(Some("***"));
+    }
+    url.to_string()
+}
+
+fn parse_proxy(proxy_url: &str) -> Result<Url, String> {
+    let url = Url::parse(proxy_url).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+    if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+        return Err(format!(
+            "Unsupported proxy scheme '{}', use http://, https:// or socks5://",
+            url.scheme()
+        ));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err(format!("Proxy URL {} has no host", redacted(&url)));
+    }
+    Ok(url)
+}
+
+fn client_builder(config: &AiConfig) -> Result<reqwest::ClientBuilder, String> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy_url) = proxy_url(config) {
+        let url = parse_proxy(proxy_url)?;
+        let proxy = reqwest::Proxy::all(url.as_str())
+            .map_err(|e| format!("Invalid proxy URL {}: {}", redacted(&url), e))?;
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder)
+}
+
+fn timeout(config: &AiConfig, default: Duration) -> Duration {
+    config.timeout_secs.filter(|&secs| secs > 0).map_or(default, Duration::from_secs)
+}
+
+fn http_client(config: &AiConfig, default_timeout: Duration) -> Result<reqwest::Client, String> {
+    client_builder(config)?
+        .timeout(timeout(config, default_timeout))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+fn streaming_client(config: &AiConfig) -> Result<reqwest::Client, String> {
+    client_builder(config)?
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(timeout(config, STREAM_IDLE_TIMEOUT))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+fn post(client: &reqwest::Client, config: &AiConfig, url: &str, body: &Value) -> reqwest::RequestBuilder {
+    let mut request = client.post(url).body(body.to_string());
+    for (name, value) in headers(config) {
+        request = request.header(name, value);
+    }
+    request
+}
+
+/// Why one attempt failed, and whether trying again could help
+struct AttemptFailure {
+    message: String,
+    retryable: bool,
+    unavailable: bool,
+    unauthorized: bool,
+    retry_after: Option<Duration>,
+}
+
+/// Sends `body`, returning the first successful response; 429, 5xx, timeouts and connection
+/// failures are retried with exponential backoff, everything else fails at once
+async fn send_with_retries(
+    client: &reqwest::Client,
+    config: &AiConfig,
+    url: &str,
+    body: &Value,
+) -> Result<reqwest::Response, AiError> {
+    let max_retries = config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let failure = match post(client, config, url, body).send().await {
+            Ok(response) if response.status().is_success() => return Ok(response),
+            Ok(response) => {
+                let status = response.status().as_u16();
+                let retry_after = response
+                    .headers()
+                    .get("retry-after")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.trim().parse::<u64>().ok())
+                    .map(Duration::from_secs);
+                let body = response.text().await.unwrap_or_default();
+                AttemptFailure {
+                    message: friendly_status_message(config, status, &body),
+                    retryable: status == 429 || (500..=599).contains(&status),
+                    unavailable: (500..=599).contains(&status),
+                    unauthorized: status == 401 || status == 403,
+                    retry_after,
+                }
+            }
+            Err(e) => AttemptFailure {
+                message: friendly_transport_message(config, url, &e),
+                retryable: e.is_connect() || e.is_timeout(),
+                unavailable: e.is_connect() || e.is_timeout(),
+                unauthorized: false,
+                retry_after: None,
+            },
+        };
+
+        if !failure.retryable || attempt > max_retries {
+            let message = if attempt > 1 {
+                format!("{} (after {} attempts)", failure.message, attempt)
+            } else {
+                failure.message
+            };
+            return Err(if failure.unavailable {
+                AiError::Unavailable(message)
+            } else if failure.unauthorized {
+                AiError::Unauthorized(message)
+            } else {
+                AiError::Request(message)
+            });
+        }
+        let backoff = RETRY_BASE_DELAY * 2u32.saturating_pow(attempt - 1);
+        let delay = failure.retry_after.unwrap_or(backoff).min(RETRY_MAX_DELAY);
+        log::warn!(
+            "[AI] Attempt {} of {} failed, retrying in {:?}: {}",
+            attempt,
+            max_retries + 1,
+            delay,
+            failure.message
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Sends a chat completion to the configured provider and returns its reply; when the
+/// provider is unavailable, each of `config.fallbacks` is tried in turn
+pub async fn chat_completion(
+    config: AiConfig,
+    messages: Vec<ChatMessage>,
+    options: ChatOptions,
+) -> Result<ChatResponse, AiError> {
+    // Fallbacks go through the same proxy and limits unless they set their own
+    let fallbacks: Vec<AiConfig> = config
+        .fallbacks
+        .iter()
+        .map(|fallback| AiConfig {
+            proxy_url: fallback.proxy_url.clone().or_else(|| config.proxy_url.clone()),
+            timeout_secs: fallback.timeout_secs.or(config.timeout_secs),
+            max_retries: fallback.max_retries.or(config.max_retries),
+            fallbacks: Vec::new(),
+            ..fallback.clone()
+        })
+        .collect();
+    let candidates: Vec<&AiConfig> = std::iter::once(&config).chain(&fallbacks).collect();
+    let mut last_error = None;
+    for (index, candidate) in candidates.iter().enumerate() {
+        match complete_once(candidate, &messages, &options).await {
+            Ok(mut response) => {
+                if index > 0 {
+                    log::info!("[AI] Fallback {} / {} answered", candidate.provider, candidate.model);
+                }
+                response.fallback_index = index.checked_sub(1);
+                return Ok(response);
+            }
+            Err(AiError::Unavailable(message)) => {
+                let next = if index + 1 < candidates.len() { ", trying the next fallback" } else { "" };
+                log::warn!(
+                    "[AI] {} / {} unavailable{}: {}",
+                    candidate.provider,
+                    candidate.model,
+                    next,
+                    message
+                );
+                last_error = Some(AiError::Unavailable(message));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| AiError::Request("No AI provider configured".to_string())))
+}
+
+async fn complete_once(
+    config: &AiConfig,
+    messages: &[ChatMessage],
+    options: &ChatOptions,
+) -> Result<ChatResponse, AiError> {
+    validate(config)?;
+    let url = chat_endpoint(config)?;
+    let client = http_client(config, CHAT_TIMEOUT)?;
+
+    let body = chat_body(config, messages, options);
+    let response = send_with_retries(&client, config, &url, &body).await?;
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response from {}: {}", url, e))?;
+
+    let body: Value = serde_json::from_str(&body)
+        .map_err(|e| format!("Invalid JSON from {}: {}", url, e))?;
+    parse_chat_response(config, &body)
+}
+
+/// Decodes one line of a streamed reply into its text delta and whether the stream is over.
+/// Ollama sends one JSON object per line; everyone else sends SSE `data:` lines, where event
+/// names, comments and keep-alives carry nothing for us
+fn stream_delta(provider: &Provider, line: &str) -> Result<(Option<String>, bool), AiError> {
+    let data = match provider {
+        Provider::Ollama => line,
+        _ => match line.strip_prefix("data:") {
+            Some(data) => data.trim(),
+            None => return Ok((None, false)),
+        },
+    };
+    if data == "[DONE]" {
+        return Ok((None, true));
+    }
+    let Ok(value) = serde_json::from_str::<Value>(data) else {
+        log::debug!("[AI] Skipping undecodable stream line: {}", preview(data));
+        return Ok((None, false));
+    };
+
+    let text = |pointer: &str| value.pointer(pointer).and_then(Value::as_str).map(str::to_string);
+    Ok(match provider {
+        Provider::Ollama => (text("/message/content"), value.get("done") == Some(&json!(true))),
+        Provider::Claude => match value.get("type").and_then(Value::as_str) {
+            Some("content_block_delta") => (text("/delta/text"), false),
+            Some("message_stop") => (None, true),
+            Some("error") => {
+                let message = text("/error/message").unwrap_or_else(|| "Stream failed".to_string());
+                return Err(AiError::Request(message));
+            }
+            _ => (None, false),
+        },
+        Provider::Gemini => (gemini_text(&value)?, false),
+        _ => (text("/choices/0/delta/content"), false),
+    })
+}
+
+/// Streams a chat completion, emitting `ai-stream-chunk` for each delta; returns the full text
+pub async fn chat_completion_stream(
+    app: &AppHandle,
+    config: AiConfig,
+    messages: Vec<ChatMessage>,
+    options: ChatOptions,
+    request_id: &str,
+) -> Result<String, AiError> {
+    validate(&config)?;
+    let url = stream_endpoint(&config)?;
+    let client = streaming_client(&config)?;
+
+    // Only the request is retried; once chunks have been emitted a retry would repeat them
+    let mut response =
+        send_with_retries(&client, &config, &url, &stream_body(&config, &messages, &options)).await?;
+
+    let mut content = String::new();
+    let mut handle_line = |line: &[u8]| -> Result<bool, AiError> {
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(false);
+        }
+        let (delta, done) = stream_delta(&config.provider, line)?;
+        if let Some(delta) = delta.filter(|delta| !delta.is_empty()) {
+            content.push_str(&delta);
+            let chunk = StreamChunk { request_id: request_id.to_string(), delta };
+            if let Err(e) = app.emit("ai-stream-chunk", chunk) {
+                log::warn!("[AI] Failed to emit ai-stream-chunk: {}", e);
+            }
+        }
+        Ok(done)
+    };
+
+    // Lines are split on raw bytes, so a multi-byte character cut between chunks survives
+    let mut buffer: Vec<u8> = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| friendly_transport_message(&config, &url, &e))?
+    {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            if handle_line(&line)? {
+                return Ok(content);
+            }
+        }
+    }
+    handle_line(&buffer)?;
+    Ok(content)
+}
+
+fn emit_stream_done(app: &AppHandle, done: StreamDone) {
+    if let Err(e) = app.emit("ai-stream-done", done) {
+        log::error!("[AI] Failed to emit ai-stream-done: {}", e);
+    }
+}
+
+/// The saved `ai` settings row with the caller's overrides applied
+async fn resolve_config(app: &AppHandle, overrides: AiConfigOverrides) -> Result<AiConfig, String> {
+    let pool = db::pool(app).await?;
+    let saved = match db::get_setting(&pool, AI_SETTINGS_KEY).await? {
+        Some(value) => serde_json::from_str::<AiConfig>(&value)
+            .map_err(|e| format!("Failed to parse AI settings: {}", e))?,
+        None => AiConfig {
+            provider: Provider::OpenAi,
+            api_key: String::new(),
+            model: String::new(),
+            base_url: None,
+            proxy_url: None,
+            timeout_secs: None,
+            max_retries: None,
+            cache_ttl_secs: None,
+            fallbacks: Vec::new(),
+        },
+    };
+    let proxy_url = match overrides.proxy_url {
+        Some(proxy_url) => Some(proxy_url),
+        None => saved_proxy_url(&pool).await?,
+    };
+    Ok(AiConfig {
+        provider: overrides.provider.unwrap_or(saved.provider),
+        api_key: resolve_api_key(None, overrides.api_key.unwrap_or(saved.api_key))?,
+        model: overrides.model.unwrap_or(saved.model),
+        base_url: overrides.base_url.or(saved.base_url),
+        proxy_url,
+        timeout_secs: overrides.timeout_secs.or(saved.timeout_secs),
+        max_retries: overrides.max_retries.or(saved.max_retries),
+        cache_ttl_secs: overrides.cache_ttl_secs.or(saved.cache_ttl_secs),
+        fallbacks: overrides
+            .fallbacks
+            .unwrap_or(saved.fallbacks)
+            .into_iter()
+            .enumerate()
+            .map(|(index, fallback)| {
+                let api_key = resolve_api_key(Some(index), fallback.api_key)?;
+                Ok::<_, String>(AiConfig { api_key, ..fallback })
+            })
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// The real key when `api_key` is the placeholder the `ai` row keeps for the keychain, for the
+/// primary provider or for `fallbacks[fallback]`
+fn resolve_api_key(fallback: Option<usize>, api_key: String) -> Result<String, String> {
+    #[cfg(desktop)]
+    return crate::keychain::resolve_api_key(fallback, api_key);
+    #[cfg(not(desktop))]
+    {
+        let _ = fallback;
+        Ok(api_key)
+    }
+}
+
+/// Proxy URL from the `proxy` settings row, if the proxy is switched on
+async fn saved_proxy_url(pool: &sqlx::SqlitePool) -> Result<Option<String>, String> {
+    let Some(value) = db::get_setting(pool, PROXY_SETTINGS_KEY).await? else {
+        return Ok(None);
+    };
+    let settings: ProxySettings =
+        serde_json::from_str(&value).map_err(|e| format!("Failed to parse proxy settings: {}", e))?;
+    Ok(settings.url())
+}
+
+/// Records one AI call in `ai_usage`; bookkeeping failures are only logged
+async fn record_usage(
+    app: &AppHandle,
+    provider: &str,
+    model: &str,
+    usage: Option<&TokenUsage>,
+    purpose: Option<&str>,
+) {
+    let result = async {
+        let pool = db::pool(app).await?;
+        sqlx::query(
+            "INSERT INTO ai_usage (created_at, provider, model, prompt_tokens, completion_tokens, total_tokens, purpose)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(chrono::Utc::now().timestamp_millis())
+        .bind(provider)
+        .bind(model)
+        .bind(usage.map(|usage| usage.prompt_tokens as i64))
+        .bind(usage.map(|usage| usage.completion_tokens as i64))
+        .bind(usage.map(|usage| usage.total_tokens as i64))
+        .bind(purpose)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to record AI usage: {}", e))
+    }
+    .await;
+    if let Err(e) = result {
+        log::warn!("[AI] {}", e);
+    }
+}
+
+/// Command for the usage chart: every AI call since `since` (epoch millis), oldest first
+#[tauri::command]
+pub async fn get_ai_usage(app_handle: AppHandle, since: i64) -> Result<Vec<UsageRow>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let rows = sqlx::query_as::<_, (i64, i64, String, String, Option<i64>, Option<i64>, Option<i64>, Option<String>)>(
+        "SELECT id, created_at, provider, model, prompt_tokens, completion_tokens, total_tokens, purpose
+         FROM ai_usage WHERE created_at >= ? ORDER BY created_at",
+    )
+    .bind(since)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read AI usage: {}", e)))?;
+    Ok(rows
+        .into_iter()
+        .map(
+            |(id, created_at, provider, model, prompt_tokens, completion_tokens, total_tokens, purpose)| UsageRow {
+                id,
+                created_at,
+                provider,
+                model,
+                prompt_tokens,
+                completion_tokens,
+                total_tokens,
+                purpose,
+            },
+        )
+        .collect())
+}
+
+/// Cache key for a request: provider, model and the full conversation
+fn cache_key(config: &AiConfig, messages: &[ChatMessage], options: &ChatOptions) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    config.provider.id().hash(&mut hasher);
+    config.model.hash(&mut hasher);
+    for message in messages {
+        message.role.hash(&mut hasher);
+        message.content.hash(&mut hasher);
+    }
+    options.json.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn cache_ttl(config: &AiConfig) -> Duration {
+    config.cache_ttl_secs.map_or(DEFAULT_CACHE_TTL, Duration::from_secs)
+}
+
+/// A reply stored less than `ttl` ago; a poisoned cache counts as a miss
+fn cached_response(app: &AppHandle, key: u64, ttl: Duration) -> Option<ChatResponse> {
+    let state = app.state::<AppState>();
+    let mut cache = state.ai_cache.lock().ok()?;
+    cache.retain(|_, entry| entry.stored_at.elapsed() < ttl);
+    cache.get(&key).map(|entry| entry.response.clone())
+}
+
+fn store_response(app: &AppHandle, key: u64, response: &ChatResponse) {
+    let state = app.state::<AppState>();
+    if let Ok(mut cache) = state.ai_cache.lock() {
+        cache.insert(
+            key,
+            CachedResponse {
+                response: response.clone(),
+                stored_at: Instant::now(),
+            },
+        );
+    }
+}
+
+/// Command to drop every cached reply, e.g. after switching models; returns how many were dropped
+#[tauri::command]
+pub fn clear_ai_cache(app_handle: AppHandle) -> Result<usize, CmdError> {
+    let state = app_handle.state::<AppState>();
+    let mut cache = state.ai_cache.lock().map_err(|e| format!("Failed to clear the AI cache: {}", e))?;
+    let cleared = cache.len();
+    cache.clear();
+    log::info!("[AI] Cleared {} cached replies", cleared);
+    Ok(cleared)
+}
+
+/// Runs one completion past the cache and records what it cost
+async fn complete_and_record(
+    app: &AppHandle,
+    config: AiConfig,
+    messages: Vec<ChatMessage>,
+    options: ChatOptions,
+    purpose: Option<String>,
+) -> Result<ChatResponse, AiError> {
+    // Regenerating a report over unchanged tasks sends the same prompt again
+    let ttl = cache_ttl(&config);
+    let key = cache_key(&config, &messages, &options);
+    if !ttl.is_zero()
+        && let Some(response) = cached_response(app, key, ttl)
+    {
+        log::info!("[AI] Reusing cached reply from {} / {}", response.provider, response.model);
+        return Ok(response);
+    }
+    log::info!("[AI] Chat completion with {} / {}", config.provider, config.model);
+    let response = chat_completion(config, messages, options).await?;
+    let usage = response.usage.as_ref();
+    record_usage(app, &response.provider, &response.model, usage, purpose.as_deref()).await;
+    if !ttl.is_zero() {
+        store_response(app, key, &response);
+    }
+    Ok(response)
+}
+
+/// Command for the frontend to run a chat completion in the backend, away from CORS and
+/// without handing the API key around; with a `request_id` it can be stopped by `cancel_ai_request`
+#[tauri::command]
+pub async fn ai_chat_completion(
+    app_handle: AppHandle,
+    request_id: Option<String>,
+    config: Option<AiConfigOverrides>,
+    messages: Vec<ChatMessage>,
+    options: Option<ChatOptions>,
+    purpose: Option<String>,
+) -> Result<ChatResponse, CmdError> {
+    let config = resolve_config(&app_handle, config.unwrap_or_default()).await?;
+    let options = options.unwrap_or_default();
+    let Some(request_id) = request_id else {
+        return complete_and_record(&app_handle, config, messages, options, purpose).await.map_err(CmdError::Ai);
+    };
+
+    let state = app_handle.state::<AppState>();
+    let task = {
+        // Held until the handle is stored, so a cancel can't slip in before it
+        let mut requests = state
+            .ai_requests
+            .lock()
+            .map_err(|e| format!("Failed to track AI request: {}", e))?;
+        if requests.contains_key(&request_id) {
+            return Err(format!("AI request '{}' is already running", request_id).into());
+        }
+        let app = app_handle.clone();
+        let task = tauri::async_runtime::spawn(async move {
+            complete_and_record(&app, config, messages, options, purpose).await
+        });
+        requests.insert(request_id.clone(), (task.inner().abort_handle(), false));
+        task
+    };
+    let result = task.await;
+    if let Ok(mut requests) = state.ai_requests.lock() {
+        requests.remove(&request_id);
+    }
+    result
+        .unwrap_or_else(|_| Err(AiError::Cancelled(format!("AI request '{}' was cancelled", request_id))))
+        .map_err(CmdError::Ai)
+}
+
+/// Command to start streaming a chat completion; the reply arrives as `ai-stream-chunk` events
+/// tagged with `request_id`, followed by one `ai-stream-done`
+#[tauri::command]
+pub async fn start_ai_stream(
+    app_handle: AppHandle,
+    request_id: String,
+    config: Option<AiConfigOverrides>,
+    messages: Vec<ChatMessage>,
+    options: Option<ChatOptions>,
+    purpose: Option<String>,
+) -> Result<(), CmdError> {
+    let config = resolve_config(&app_handle, config.unwrap_or_default()).await?;
+    log::info!("[AI] Streaming {} with {} / {}", request_id, config.provider, config.model);
+
+    let state = app_handle.state::<AppState>();
+    // Held until the handle is stored, so a stream that ends immediately can't be left behind
+    let mut requests = state
+        .ai_requests
+        .lock()
+        .map_err(|e| format!("Failed to track AI stream: {}", e))?;
+    if requests.contains_key(&request_id) {
+        return Err(format!("AI request '{}' is already running", request_id).into());
+    }
+
+    let app = app_handle.clone();
+    let id = request_id.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        let result =
+            chat_completion_stream(&app, config.clone(), messages, options.unwrap_or_default(), &id).await;
+        if result.is_ok() {
+            // Streams don't report token counts for every provider, so only the call is recorded
+            record_usage(&app, config.provider.id(), &config.model, None, purpose.as_deref()).await;
+        }
+        // Gone from the map means `cancel_ai_request` already reported it
+        let tracked = match app.state::<AppState>().ai_requests.lock() {
+            Ok(mut requests) => requests.remove(&id).is_some(),
+            Err(_) => true,
+        };
+        if !tracked {
+            return;
+        }
+        if let Err(e) = &result {
+            log::warn!("[AI] Stream {} failed: {}", id, e);
+        }
+        let (content, error) = match result {
+            Ok(content) => (content, None),
+            Err(e) => (String::new(), Some(e)),
+        };
+        emit_stream_done(&app, StreamDone { request_id: id, content, cancelled: false, error });
+    });
+    requests.insert(request_id, (task.inner().abort_handle(), true));
+    Ok(())
+}
+
+/// Aborts a tracked AI request, dropping its HTTP body. A stream is ended with a cancelled
+/// `ai-stream-done`, since its task no longer gets to send one
+fn abort_request(app: &AppHandle, request_id: &str) -> Result<(), String> {
+    let request = app
+        .state::<AppState>()
+        .ai_requests
+        .lock()
+        .map_err(|e| format!("Failed to cancel AI request: {}", e))?
+        .remove(request_id);
+    let Some((handle, stream)) = request else {
+        return Ok(());
+    };
+    handle.abort();
+    log::info!("[AI] Cancelled request {}", request_id);
+    if let Err(e) = app.emit("ai-stream-cancelled", json!({ "requestId": request_id })) {
+        log::error!("[AI] Failed to emit ai-stream-cancelled: {}", e);
+    }
+    if stream {
+        let request_id = request_id.to_string();
+        emit_stream_done(app, StreamDone { request_id, content: String::new(), cancelled: true, error: None });
+    }
+    Ok(())
+}
+
+/// Command to stop a running completion or stream; unknown ids (already finished) are ignored
+#[tauri::command]
+pub fn cancel_ai_request(app_handle: AppHandle, request_id: String) -> Result<(), CmdError> {
+    Ok(abort_request(&app_handle, &request_id)?)
+}
+
+/// Smallest request each provider answers with a real completion
+fn test_body(config: &AiConfig) -> Value {
+    let messages = json!([{ "role": "user", "content": "Hi" }]);
+    match config.provider {
+        Provider::Gemini => json!({
+            "contents": [{ "role": "user", "parts": [{ "text": "Hi" }] }],
+            "generationConfig": { "maxOutputTokens": 1 },
+        }),
+        Provider::Ollama => json!({
+            "model": config.model,
+            "messages": messages,
+            "stream": false,
+            "options": { "num_predict": 1 },
+        }),
+        _ => json!({ "model": config.model, "messages": messages, "max_tokens": 1 }),
+    }
+}
+
+fn validate(config: &AiConfig) -> Result<(), String> {
+    if config.model.trim().is_empty() {
+        return Err("A model is required".to_string());
+    }
+    if config.provider.requires_api_key() && config.api_key.trim().is_empty() {
+        return Err("An API key is required".to_string());
+    }
+    Ok(())
+}
+
+/// The provider's own explanation from an error body, if it sent one
+fn api_error_message(body: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(body).ok()?;
+    let message = value
+        .pointer("/error/message")
+        .or_else(|| value.get("error").filter(|error| error.is_string()))
+        .or_else(|| value.get("message"))?;
+    message.as_str().map(str::to_string)
+}
+
+/// Maps the statuses users trip over to what they should actually fix; the provider's detail
+/// is redacted since some echo the key back
+fn friendly_status_message(config: &AiConfig, status: u16, body: &str) -> String {
+    let hint = match status {
+        401 | 403 => "Invalid API key",
+        404 => "Wrong base URL or model path",
+        429 => "Rate limited by the provider, try again later",
+        500..=599 => "The provider had an internal error",
+        _ => "Request rejected by the provider",
+    };
+    match api_error_message(body) {
+        Some(detail) => format!("{} ({}): {}", hint, status, redact_secrets(&detail, &config.api_key)),
+        None => format!("{} ({})", hint, status),
+    }
+}
+
+fn friendly_transport_message(config: &AiConfig, url: &str, error: &reqwest::Error) -> String {
+    let message = if error.is_timeout() {
+        format!("Timed out waiting for {}", url)
+    } else if error.is_connect() {
+        format!("Could not connect to {}, check the base URL and that the server is running", url)
+    } else {
+        format!("Request to {} failed: {}", url, error)
+    };
+    redact_secrets(&message, &config.api_key)
+}
+
+/// Shortens a secret to its last 4 characters
+fn mask(secret: &str) -> String {
+    let tail: Vec<char> = secret.chars().rev().take(4).collect();
+    format!("****{}", tail.into_iter().rev().collect::<String>())
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Masks `api_key` and anything shaped like a key (`sk-...`, `AIza...`, the token after
+/// `Bearer` or an `api_key` field) before text reaches the logs or an error shown to the user
+pub fn redact_secrets(text: &str, api_key: &str) -> String {
+    let api_key = api_key.trim();
+    let text = if api_key.len() >= 8 {
+        text.replace(api_key, &mask(api_key))
+    } else {
+        text.to_string()
+    };
+
+    let mut redacted = String::with_capacity(text.len());
+    let mut after_label = false;
+    let mut rest = text.as_str();
+    while let Some(c) = rest.chars().next() {
+        if !is_key_char(c) {
+            redacted.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        let end = rest.find(|c| !is_key_char(c)).unwrap_or(rest.len());
+        let token = &rest[..end];
+        let looks_like_key = token.len() >= 12 && KEY_PREFIXES.iter().any(|prefix| token.starts_with(prefix));
+        if looks_like_key || (after_label && token.len() > 4) {
+            redacted.push_str(&mask(token));
+        } else {
+            redacted.push_str(token);
+        }
+        after_label = KEY_LABELS.iter().any(|label| token.eq_ignore_ascii_case(label));
+        rest = &rest[end..];
+    }
+    redacted
+}
+
+fn preview(body: &str) -> String {
+    body.chars().take(PREVIEW_CHARS).collect()
+}
+
+/// Tells a dead proxy apart from an unreachable endpoint behind it by opening a plain TCP
+/// connection to the proxy
+async fn proxy_reachable(proxy_url: &str) -> bool {
+    let Ok(url) = parse_proxy(proxy_url) else {
+        return false;
+    };
+    let Some(host) = url.host_str().map(str::to_string) else {
+        return false;
+    };
+    let port = url.port_or_known_default().unwrap_or(1080);
+    tauri::async_runtime::spawn_blocking(move || {
+        use std::net::{TcpStream, ToSocketAddrs};
+        (host.as_str(), port)
+            .to_socket_addrs()
+            .map(|mut addrs| addrs.any(|addr| TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).is_ok()))
+            .unwrap_or(false)
+    })
+    .await
+    .unwrap_or(false)
+}
+
+/// Sends a minimal completion to check the key, base URL, model and proxy in one go; a
+/// missing `proxyUrl` uses the saved proxy settings
+#[tauri::command]
+pub async fn test_ai_connection(app_handle: AppHandle, mut config: AiConfig) -> Result<TestResult, CmdError> {
+    config.api_key = resolve_api_key(None, config.api_key)?;
+    validate(&config).map_err(CmdError::Validation)?;
+    if config.proxy_url.is_none() {
+        let pool = db::pool(&app_handle).await?;
+        config.proxy_url = saved_proxy_url(&pool).await?;
+    }
+    let url = chat_endpoint(&config)?;
+    let client = http_client(&config, TEST_TIMEOUT)?;
+
+    let started = Instant::now();
+    let response = post(&client, &config, &url, &test_body(&config)).send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            let detail = redact_secrets(&e.to_string(), &config.api_key);
+            log::warn!("[AI] Connection test to {} failed: {}", url, detail);
+            let mut message = friendly_transport_message(&config, &url, &e);
+            let mut failing_hop = "endpoint";
+            if let Some(proxy_url) = proxy_url(&config) {
+                let proxy = parse_proxy(proxy_url).map(|url| redacted(&url)).unwrap_or_default();
+                if proxy_reachable(proxy_url).await {
+                    message = format!("{} (through proxy {}, which is reachable)", message, proxy);
+                } else {
+                    failing_hop = "proxy";
+                    message = format!("Could not connect to proxy {}, check its host and port", proxy);
+                }
+            }
+            return Ok(TestResult {
+                ok: false,
+                status: None,
+                latency_ms,
+                preview: String::new(),
+                message,
+                failing_hop: Some(failing_hop.to_string()),
+            });
+        }
+    };
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let message = if status.is_success() {
+        "Connection successful".to_string()
+    } else {
+        friendly_status_message(&config, status.as_u16(), &body)
+    };
+    log::info!("[AI] Connection test to {} returned {} in {}ms", url, status, latency_ms);
+
+    Ok(TestResult {
+        ok: status.is_success(),
+        status: Some(status.as_u16()),
+        latency_ms,
+        preview: preview(&redact_secrets(&body, &config.api_key)),
+        message,
+        failing_hop: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(provider: &str) -> AiConfig {
+        AiConfig {
+            provider: Provider::from(provider.to_string()),
+            api_key: String::new(),
+            model: "test-model".to_string(),
+            base_url: None,
+            proxy_url: None,
+            timeout_secs: None,
+            max_retries: None,
+            cache_ttl_secs: None,
+            fallbacks: Vec::new(),
+        }
+    }
+
+    fn message(role: &str, content: &str) -> ChatMessage {
+        ChatMessage { role: role.to_string(), content: content.to_string() }
+    }
+
+    fn conversation() -> Vec<ChatMessage> {
+        vec![message("system", "Be brief"), message("user", "Hello")]
+    }
+
+    const JSON: ChatOptions = ChatOptions { json: true };
+
+    /// Serves one canned response per connection, in order, and reports how many requests came in
+    fn mock_server(responses: &[(u16, &str)]) -> (String, std::thread::JoinHandle<usize>) {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/v1/chat/completions", listener.local_addr().unwrap());
+        let responses: Vec<(u16, String)> = responses.iter().map(|(s, b)| (*s, b.to_string())).collect();
+        let handle = std::thread::spawn(move || {
+            listener.set_nonblocking(true).unwrap();
+            let mut served = 0;
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while served < responses.len() && Instant::now() < deadline {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    std::thread::sleep(Duration::from_millis(10));
+                    continue;
+                };
+                stream.set_nonblocking(false).unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                let (status, body) = &responses[served];
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nRetry-After: 0\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+                served += 1;
+            }
+            served
+        });
+        (url, handle)
+    }
+
+    fn send(config: &AiConfig, url: &str) -> Result<reqwest::Response, AiError> {
+        let client = http_client(config, CHAT_TIMEOUT).unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(send_with_retries(&client, config, url, &json!({})))
+    }
+
+    #[test]
+    fn openai_asks_for_json_object() {
+        let body = chat_body(&config("openai"), &conversation(), &JSON);
+        assert_eq!(body["response_format"], json!({ "type": "json_object" }));
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(chat_endpoint(&config("openai")).unwrap(), "https://api.openai.com/v1/chat/completions");
+    }
+
+    #[test]
+    fn lmstudio_asks_for_json_schema() {
+        let body = chat_body(&config("lmstudio"), &conversation(), &JSON);
+        assert_eq!(body["response_format"]["type"], "json_schema");
+        assert_eq!(body["response_format"]["json_schema"]["schema"], json!({ "type": "object" }));
+    }
+
+    #[test]
+    fn ollama_uses_native_chat_with_json_format() {
+        let body = chat_body(&config("ollama"), &conversation(), &JSON);
+        assert_eq!(body["format"], "json");
+        assert_eq!(body["stream"], false);
+        assert!(body.get("response_format").is_none());
+        assert_eq!(chat_endpoint(&config("ollama")).unwrap(), "http://localhost:11434/api/chat");
+    }
+
+    #[test]
+    fn claude_moves_system_prompt_to_top_level() {
+        let mut messages = conversation();
+        messages.push(message("user", "Still there?"));
+        let body = chat_body(&config("claude"), &messages, &JSON);
+        assert_eq!(body["system"], "Be brief");
+        assert_eq!(body["messages"], json!([{ "role": "user", "content": "Hello\n\nStill there?" }]));
+        assert_eq!(body["max_tokens"], CLAUDE_MAX_TOKENS);
+        assert!(body.get("response_format").is_none());
+    }
+
+    #[test]
+    fn gemini_sends_system_instruction() {
+        let mut messages = conversation();
+        messages.push(message("assistant", "Hi"));
+        let body = chat_body(&config("gemini"), &messages, &JSON);
+        assert_eq!(body["systemInstruction"], json!({ "parts": [{ "text": "Be brief" }] }));
+        assert_eq!(body["contents"][1]["role"], "model");
+        assert_eq!(body["generationConfig"]["responseMimeType"], "application/json");
+    }
+
+    #[test]
+    fn plain_requests_skip_json_mode() {
+        let plain = ChatOptions::default();
+        for provider in ["openai", "lmstudio", "ollama", "gemini"] {
+            let body = chat_body(&config(provider), &conversation(), &plain);
+            assert!(body.get("response_format").is_none(), "{}", provider);
+            assert!(body.get("format").is_none(), "{}", provider);
+            assert!(body.get("generationConfig").is_none(), "{}", provider);
+        }
+    }
+
+    #[test]
+    fn retries_unavailable_provider() {
+        let (url, server) = mock_server(&[(503, "{}"), (200, r#"{"ok":true}"#)]);
+        let config = AiConfig { max_retries: Some(2), ..config("custom") };
+        let response = send(&config, &url).expect("second attempt should succeed");
+        assert_eq!(response.status(), 200);
+        assert_eq!(server.join().unwrap(), 2);
+    }
+
+    #[test]
+    fn does_not_retry_rejected_key() {
+        let (url, server) = mock_server(&[(401, r#"{"error":{"message":"bad key"}}"#)]);
+        let config = AiConfig { max_retries: Some(2), ..config("custom") };
+        let error = send(&config, &url).unwrap_err();
+        assert_eq!(error.kind(), "unauthorized");
+        assert!(!error.to_string().contains("attempts"), "{}", error);
+        assert_eq!(server.join().unwrap(), 1);
+    }
+
+    #[test]
+    fn reports_attempt_count_when_retries_run_out() {
+        let (url, server) = mock_server(&[(503, "{}"), (503, "{}")]);
+        let config = AiConfig { max_retries: Some(1), ..config("custom") };
+        let error = send(&config, &url).unwrap_err();
+        assert_eq!(error.kind(), "unavailable");
+        assert!(error.to_string().contains("(after 2 attempts)"), "{}", error);
+        assert_eq!(server.join().unwrap(), 2);
+    }
+
+    #[test]
+    fn redacts_all_but_last_four_characters() {
+        let key = "sk-proj-A1b2C3d4E5f6G7h8";
+        let cases = [
+            (format!("Request failed: invalid key {}", key), key),
+            (format!("Authorization: Bearer {}", key), key),
+            (format!(r#"{{"api_key": "{}", "model": "gpt-4o"}}"#, key), key),
+            // Not the configured key and not prefixed, so only the label gives it away
+            (r#"{"apiKey":"Zq9Wx8Vu7Ts6Rp5On4Ml"}"#.to_string(), "Zq9Wx8Vu7Ts6Rp5On4Ml"),
+        ];
+        for (text, secret) in cases {
+            let logged = redact_secrets(&text, key);
+            assert!(!logged.contains(secret), "{}", logged);
+            assert!(logged.contains(&format!("****{}", &secret[secret.len() - 4..])), "{}", logged);
+        }
+    }
+
+    #[test]
+    fn unexpected_response_is_redacted() {
+        let mut config = config("openai");
+        config.api_key = "sk-proj-A1b2C3d4E5f6G7h8".to_string();
+        let body = json!({ "error": { "message": "Invalid key sk-proj-A1b2C3d4E5f6G7h8" } });
+        let error = parse_chat_response(&config, &body).unwrap_err().to_string();
+        assert!(error.starts_with("Unexpected response from"), "{}", error);
+        assert!(!error.contains(&config.api_key), "{}", error);
+    }
+
+    #[test]
+    fn redaction_keeps_ordinary_text() {
+        let text = r#"Model "gpt-4o" returned 429: {"error":{"message":"Rate limit reached"}}"#;
+        assert_eq!(redact_secrets(text, "sk-proj-A1b2C3d4E5f6G7h8"), text);
+    }
+}
@@ -0,0 +1,93 @@
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::CmdError;
+use crate::{db, AppState};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppInfo {
+    pub name: String,
+    pub version: String,
+    /// Short hash of the commit the build came from, or "unknown"
+    pub commit: String,
+    /// When the build ran, epoch millis
+    pub build_timestamp: i64,
+    pub os: String,
+    pub arch: String,
+    pub sqlite_version: String,
+    /// Newest migration applied to `tada.db`
+    pub schema_version: i64,
+    /// Newest migration this build knows; above `schema_version` until the database catches up
+    pub latest_schema_version: i64,
+}
+
+/// Command for the version details a bug report needs
+#[tauri::command]
+pub async fn get_app_info(app_handle: AppHandle) -> Result<AppInfo, CmdError> {
+    let package = app_handle.package_info();
+    let pool = db::pool(&app_handle).await?;
+    let sqlite_version = sqlx::query_scalar::<_, String>("SELECT sqlite_version()")
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to read the SQLite version: {}", e)))?;
+    Ok(AppInfo {
+        name: package.name.clone(),
+        version: package.version.to_string(),
+        commit: env!("TADA_GIT_COMMIT").to_string(),
+        build_timestamp: env!("TADA_BUILD_TIMESTAMP").parse().unwrap_or(0),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        sqlite_version,
+        schema_version: db::migration_version(&pool).await?,
+        latest_schema_version: crate::schema_version(),
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadyState {
+    /// Setup has finished: the database is reopened, settings restored and background jobs started
+    pub ready: bool,
+    /// Every migration this build knows has been applied
+    pub migrations_complete: bool,
+    /// Newest migration applied to `tada.db`
+    pub schema_version: i64,
+    /// Schedule settings were read from the database; false while booting, or when reading them
+    /// failed and the defaults are in use
+    pub schedule_settings_loaded: bool,
+}
+
+async fn ready_state(app: &AppHandle) -> Result<ReadyState, String> {
+    let state = app.state::<AppState>();
+    let pool = db::pool(app).await?;
+    let schema_version = db::migration_version(&pool).await?;
+    Ok(ReadyState {
+        ready: state.setup_complete.load(Ordering::SeqCst),
+        migrations_complete: schema_version >= crate::schema_version(),
+        schema_version,
+        schedule_settings_loaded: state.schedule_settings_loaded.load(Ordering::SeqCst),
+    })
+}
+
+/// Command for how far the backend has got booting. The frontend listens for `app-ready` and
+/// then calls this, so it can't miss the event when setup finished before its listener was in
+#[tauri::command]
+pub async fn app_ready(app_handle: AppHandle) -> Result<ReadyState, CmdError> {
+    Ok(ready_state(&app_handle).await?)
+}
+
+/// Marks setup finished and emits `app-ready` with the state; called last in `setup`
+pub fn announce_ready(app: &AppHandle) {
+    app.state::<AppState>().setup_complete.store(true, Ordering::SeqCst);
+    match tauri::async_runtime::block_on(ready_state(app)) {
+        Ok(state) => {
+            log::info!("[App] Ready at schema version {}", state.schema_version);
+            if let Err(e) = app.emit("app-ready", state) {
+                log::error!("[App] Failed to emit app-ready: {}", e);
+            }
+        }
+        Err(e) => log::error!("[App] Failed to read the ready state: {}", e),
+    }
+}
@@ -0,0 +1,218 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::error::CmdError;
+use crate::{db, paths};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub id: i64,
+    pub task_id: String,
+    /// The app's own copy, under the attachments directory
+    pub file_path: String,
+    pub file_name: String,
+    pub mime: String,
+    pub size: i64,
+    pub created_at: i64,
+}
+
+/// `attachments/<task id>` under the app data directory, where copies are kept
+fn attachments_dir(app: &AppHandle, task_id: &str) -> Result<PathBuf, String> {
+    let dir = paths::app_data_dir(app)?
+        .join("attachments")
+        .join(sanitize(task_id));
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// Keeps ids and names usable as a single path component on every platform
+fn sanitize(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c.is_control() || r#"/\:*?"<>|"#.contains(c) { '_' } else { c })
+        .collect();
+    match cleaned.trim_matches(|c: char| c == '.' || c.is_whitespace()) {
+        "" => "attachment".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// `name`, or `name (2).ext`, `name (3).ext`... for the first one not taken in `dir`
+fn unique_path(dir: &Path, file_name: &str) -> PathBuf {
+    let candidate = dir.join(file_name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+        _ => (file_name, None),
+    };
+    (2..)
+        .map(|n| match extension {
+            Some(extension) => dir.join(format!("{} ({}).{}", stem, n, extension)),
+            None => dir.join(format!("{} ({})", stem, n)),
+        })
+        .find(|path| !path.exists())
+        .unwrap_or(candidate)
+}
+
+fn mime_for(file_name: &str) -> &'static str {
+    let extension = file_name.rsplit_once('.').map(|(_, extension)| extension.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("heic") => "image/heic",
+        Some("pdf") => "application/pdf",
+        Some("txt" | "log") => "text/plain",
+        Some("md") => "text/markdown",
+        Some("csv") => "text/csv",
+        Some("json") => "application/json",
+        Some("zip") => "application/zip",
+        Some("doc") => "application/msword",
+        Some("docx") => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        Some("xls") => "application/vnd.ms-excel",
+        Some("xlsx") => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        Some("ppt") => "application/vnd.ms-powerpoint",
+        Some("pptx") => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        Some("mp3") => "audio/mpeg",
+        Some("mp4") => "video/mp4",
+        Some("mov") => "video/quicktime",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Command to attach a file to a task: it is copied into the attachments directory, so the
+/// original can move or go away. A name already taken there gets a ` (2)` style suffix
+#[tauri::command]
+pub async fn add_attachment(
+    app_handle: AppHandle,
+    task_id: String,
+    source_path: String,
+) -> Result<Attachment, CmdError> {
+    let source = PathBuf::from(&source_path);
+    if !source.is_file() {
+        return Err(CmdError::NotFound(format!("File not found: {}", source_path)));
+    }
+    let file_name = sanitize(&source.file_name().map(|name| name.to_string_lossy()).unwrap_or_default());
+    let pool = db::pool(&app_handle).await?;
+    let task_exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM tasks WHERE id = ? AND deleted_at IS NULL")
+        .bind(&task_id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to look up task '{}': {}", task_id, e)))?
+        > 0;
+    if !task_exists {
+        return Err(CmdError::NotFound(format!("Task '{}' not found", task_id)));
+    }
+
+    let target = unique_path(&attachments_dir(&app_handle, &task_id)?, &file_name);
+    let size = std::fs::copy(&source, &target)
+        .map_err(|e| CmdError::Io(format!("Failed to copy {} to {}: {}", source.display(), target.display(), e)))?;
+    let file_name = target
+        .file_name()
+        .map_or(file_name.clone(), |name| name.to_string_lossy().into_owned());
+    let mime = mime_for(&file_name).to_string();
+    let file_path = target.to_string_lossy().into_owned();
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let inserted = sqlx::query(
+        "INSERT INTO attachments (task_id, file_path, file_name, mime, size, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&task_id)
+    .bind(&file_path)
+    .bind(&file_name)
+    .bind(&mime)
+    .bind(size as i64)
+    .bind(now)
+    .execute(&pool)
+    .await;
+    let id = match inserted {
+        Ok(result) => result.last_insert_rowid(),
+        Err(e) => {
+            // Don't leave a copy behind that nothing points to
+            let _ = std::fs::remove_file(&target);
+            return Err(CmdError::Db(format!("Failed to record attachment: {}", e)));
+        }
+    };
+    log::info!("[Attachments] Attached {} to task {}", file_name, task_id);
+    Ok(Attachment { id, task_id, file_path, file_name, mime, size: size as i64, created_at: now })
+}
+
+async fn file_path(pool: &sqlx::SqlitePool, id: i64) -> Result<String, String> {
+    sqlx::query_scalar::<_, String>("SELECT file_path FROM attachments WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to look up attachment {}: {}", id, e))?
+        .ok_or_else(|| format!("Attachment {} not found", id))
+}
+
+/// Removes attachment copies from disk; a file that is already gone is not an error
+pub fn remove_files<I: IntoIterator<Item = String>>(paths: I) {
+    for path in paths {
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => log::warn!("[Attachments] Failed to remove {}: {}", path, e),
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn list_attachments(app_handle: AppHandle, task_id: String) -> Result<Vec<Attachment>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let rows = sqlx::query_as::<_, (i64, String, String, String, String, i64, i64)>(
+        "SELECT id, task_id, file_path, file_name, mime, size, created_at FROM attachments
+         WHERE task_id = ? ORDER BY created_at",
+    )
+    .bind(&task_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read attachments: {}", e)))?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, task_id, file_path, file_name, mime, size, created_at)| Attachment {
+            id,
+            task_id,
+            file_path,
+            file_name,
+            mime,
+            size,
+            created_at,
+        })
+        .collect())
+}
+
+/// Command to remove an attachment: both its row and the app's copy of the file
+#[tauri::command]
+pub async fn remove_attachment(app_handle: AppHandle, id: i64) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let path = file_path(&pool, id).await?;
+    sqlx::query("DELETE FROM attachments WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to remove attachment {}: {}", id, e)))?;
+    remove_files([path]);
+    Ok(())
+}
+
+/// Command to open an attachment in its default app
+#[tauri::command]
+pub async fn open_attachment(app_handle: AppHandle, id: i64) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let path = file_path(&pool, id).await?;
+    if !Path::new(&path).is_file() {
+        return Err(CmdError::NotFound(format!("Attachment file is missing: {}", path)));
+    }
+    app_handle
+        .opener()
+        .open_path(path.clone(), None::<&str>)
+        .map_err(|e| CmdError::Io(format!("Failed to open {}: {}", path, e)))
+}
@@ -0,0 +1,47 @@
+use tauri::AppHandle;
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::db;
+use crate::error::CmdError;
+
+/// Settings key mirroring the OS launch-at-login state for the UI checkbox
+const AUTOSTART_SETTING_KEY: &str = "autostart";
+
+/// Builds the autostart plugin (a LaunchAgent on macOS, the builder's default)
+pub fn plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+    tauri_plugin_autostart::Builder::new().build()
+}
+
+async fn persist_autostart(app_handle: &AppHandle, enabled: bool) -> Result<(), String> {
+    let pool = db::pool(app_handle).await?;
+    db::set_setting(&pool, AUTOSTART_SETTING_KEY, &enabled.to_string()).await
+}
+
+#[tauri::command]
+pub async fn enable_autostart(app_handle: AppHandle) -> Result<(), CmdError> {
+    app_handle
+        .autolaunch()
+        .enable()
+        .map_err(|e| format!("Failed to enable launch at login: {}", e))?;
+    log::info!("[Autostart] Launch at login enabled");
+    Ok(persist_autostart(&app_handle, true).await?)
+}
+
+#[tauri::command]
+pub async fn disable_autostart(app_handle: AppHandle) -> Result<(), CmdError> {
+    app_handle
+        .autolaunch()
+        .disable()
+        .map_err(|e| format!("Failed to disable launch at login: {}", e))?;
+    log::info!("[Autostart] Launch at login disabled");
+    Ok(persist_autostart(&app_handle, false).await?)
+}
+
+/// Reports the OS registration itself, since it can be changed outside the app
+#[tauri::command]
+pub fn is_autostart_enabled(app_handle: AppHandle) -> Result<bool, CmdError> {
+    app_handle
+        .autolaunch()
+        .is_enabled()
+        .map_err(|e| CmdError::Internal(format!("Failed to read launch at login state: {}", e)))
+}
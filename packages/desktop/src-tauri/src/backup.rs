@@ -0,0 +1,278 @@
+use serde::{Deserialize, Serialize};
+use sqlx::Connection;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::CmdError;
+use crate::{db, paths};
+
+/// Settings key for the automatic backup options
+const AUTO_BACKUP_KEY: &str = "auto_backup";
+/// Settings key for when the last automatic backup was taken (epoch millis)
+const LAST_AUTO_BACKUP_KEY: &str = "auto_backup_last";
+
+const BACKUP_PREFIX: &str = "tada-backup-";
+/// Copy of the current database taken before a restore, kept out of automatic pruning
+const PRE_RESTORE_PREFIX: &str = "tada-before-restore-";
+
+/// First 16 bytes of every SQLite database file
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// Time for the event announcing a replaced database to reach the frontend before the app restarts
+const RESTART_DELAY: Duration = Duration::from_millis(500);
+
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoBackupSettings {
+    pub enabled: bool,
+    /// Automatic backups kept in the backups directory; older ones are deleted
+    pub keep: u32,
+}
+
+impl Default for AutoBackupSettings {
+    fn default() -> Self {
+        Self { enabled: false, keep: 7 }
+    }
+}
+
+/// `backups` under the app data directory, where automatic backups go
+pub(crate) fn default_backup_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    paths::app_data_dir(app).map(|dir| dir.join("backups"))
+}
+
+/// A `<prefix><timestamp>.db` path in `dir` that isn't taken yet
+pub(crate) fn backup_path(dir: &Path, prefix: &str) -> PathBuf {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let path = dir.join(format!("{}{}.db", prefix, stamp));
+    if !path.exists() {
+        return path;
+    }
+    (2..)
+        .map(|n| dir.join(format!("{}{}-{}.db", prefix, stamp, n)))
+        .find(|path| !path.exists())
+        .unwrap_or(path)
+}
+
+/// Writes a consistent snapshot of the database into `dir`. `VACUUM INTO` reads through
+/// SQLite like any other query, so unlike a file copy it can't catch the database halfway
+/// through a write or miss pages still in the WAL
+pub(crate) async fn snapshot(app: &AppHandle, dir: &Path, prefix: &str) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = backup_path(dir, prefix);
+    let pool = db::pool(app).await?;
+    // Folds the WAL back into `tada.db` first, so the file on disk is as current as the backup
+    // and the snapshot doesn't have to read a long WAL. Best effort: readers can hold it back
+    if let Err(e) = sqlx::query("PRAGMA wal_checkpoint(PASSIVE)").execute(&pool).await {
+        log::warn!("[Backup] Failed to checkpoint the WAL: {}", e);
+    }
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().into_owned())
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to back up the database to {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Command to back up the database into `dest_dir`, or the app's backups directory; returns
+/// the path of the new file
+#[tauri::command]
+pub async fn backup_database(app_handle: AppHandle, dest_dir: Option<String>) -> Result<String, CmdError> {
+    let dir = match dest_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => default_backup_dir(&app_handle)?,
+    };
+    let path = snapshot(&app_handle, &dir, BACKUP_PREFIX).await?;
+    log::info!("[Backup] Database backed up to {}", path.display());
+    Ok(path.to_string_lossy().into_owned())
+}
+
+async fn auto_backup_settings(pool: &sqlx::SqlitePool) -> Result<AutoBackupSettings, String> {
+    Ok(db::get_setting(pool, AUTO_BACKUP_KEY)
+        .await?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn get_auto_backup_settings(app_handle: AppHandle) -> Result<AutoBackupSettings, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    Ok(auto_backup_settings(&pool).await?)
+}
+
+#[tauri::command]
+pub async fn set_auto_backup_settings(app_handle: AppHandle, settings: AutoBackupSettings) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let value = serde_json::to_string(&settings).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    db::set_setting(&pool, AUTO_BACKUP_KEY, &value).await.map_err(CmdError::Db)
+}
+
+/// Deletes all but the newest `keep` backups in `dir`. The timestamp in the name sorts them
+fn prune(dir: &Path, keep: u32) -> Result<usize, String> {
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(BACKUP_PREFIX) && name.ends_with(".db"))
+        })
+        .collect();
+    backups.sort();
+    let excess = backups.len().saturating_sub(keep.max(1) as usize);
+    for path in &backups[..excess] {
+        if let Err(e) = std::fs::remove_file(path) {
+            log::warn!("[Backup] Failed to remove old backup {}: {}", path.display(), e);
+        }
+    }
+    Ok(excess)
+}
+
+/// Takes the daily automatic backup when it is enabled and due; run from the scheduler thread
+pub fn run_due_backup(app: &AppHandle) {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        let settings = auto_backup_settings(&pool).await?;
+        if !settings.enabled {
+            return Ok(None);
+        }
+        let now = chrono::Utc::now().timestamp_millis();
+        let last = db::get_setting(&pool, LAST_AUTO_BACKUP_KEY)
+            .await?
+            .and_then(|value| value.parse::<i64>().ok())
+            .unwrap_or(0);
+        if now - last < DAY_MILLIS {
+            return Ok(None);
+        }
+        let dir = default_backup_dir(app)?;
+        let path = snapshot(app, &dir, BACKUP_PREFIX).await?;
+        db::set_setting(&pool, LAST_AUTO_BACKUP_KEY, &now.to_string()).await?;
+        let pruned = prune(&dir, settings.keep)?;
+        Ok::<_, String>(Some((path, pruned)))
+    });
+    match result {
+        Ok(None) => {}
+        Ok(Some((path, pruned))) => {
+            log::info!("[Backup] Automatic backup written to {} ({} old backups removed)", path.display(), pruned)
+        }
+        Err(e) => log::warn!("[Backup] Automatic backup failed: {}", e),
+    }
+}
+
+/// Checks that `path` is an intact Tada database this build can migrate; returns its schema
+/// version
+async fn validate(path: &Path) -> Result<i64, String> {
+    let options = sqlx::sqlite::SqliteConnectOptions::new().filename(path);
+    let mut conn = sqlx::SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let result = async {
+        let check = sqlx::query_scalar::<_, String>("PRAGMA quick_check")
+            .fetch_one(&mut conn)
+            .await
+            .map_err(|e| format!("Failed to check the backup: {}", e))?;
+        if check != "ok" {
+            return Err(format!("The backup is damaged: {}", check));
+        }
+        let tables = sqlx::query_scalar::<_, String>(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name IN ('lists', 'tasks', '_sqlx_migrations')",
+        )
+        .fetch_all(&mut conn)
+        .await
+        .map_err(|e| format!("Failed to read the backup: {}", e))?;
+        if tables.len() < 3 {
+            return Err("The file is not a Tada database".to_string());
+        }
+        let version = sqlx::query_scalar::<_, Option<i64>>("SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1")
+            .fetch_one(&mut conn)
+            .await
+            .map_err(|e| format!("Failed to read the backup's schema version: {}", e))?
+            .unwrap_or(0);
+        let supported = crate::schema_version();
+        if version > supported {
+            return Err(format!(
+                "The backup comes from a newer version of Tada (schema {}, this version supports up to {}); update Tada before restoring it",
+                version, supported
+            ));
+        }
+        Ok(version)
+    }
+    .await;
+    let _ = conn.close().await;
+    result
+}
+
+/// Command to replace the database with a backup. The file is validated first and the current
+/// database is saved to the backups directory, so a bad restore can be undone by restoring that
+/// copy. The app restarts afterwards to reopen and migrate the restored database; returns the
+/// path of the safety copy
+#[tauri::command]
+pub async fn restore_database(app_handle: AppHandle, src_path: String) -> Result<String, CmdError> {
+    let source = PathBuf::from(&src_path);
+    let mut header = [0u8; 16];
+    let is_sqlite = std::fs::File::open(&source)
+        .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut header))
+        .is_ok_and(|()| header == SQLITE_HEADER);
+    if !is_sqlite {
+        return Err(CmdError::Validation(format!("{} is not a SQLite database", src_path)));
+    }
+
+    // Validate a staged copy next to the database, so the rename below stays on one filesystem
+    let db_path = db::db_path(&app_handle)?;
+    let staged = db_path.with_extension("db.restoring");
+    std::fs::copy(&source, &staged).map_err(|e| CmdError::Io(format!("Failed to copy {}: {}", src_path, e)))?;
+    let version = match validate(&staged).await {
+        Ok(version) => version,
+        Err(e) => {
+            let _ = std::fs::remove_file(&staged);
+            return Err(e.into());
+        }
+    };
+    let safety_copy = match snapshot(&app_handle, &default_backup_dir(&app_handle)?, PRE_RESTORE_PREFIX).await {
+        Ok(path) => path,
+        Err(e) => {
+            let _ = std::fs::remove_file(&staged);
+            return Err(e.into());
+        }
+    };
+
+    // Past this point the pool is closed, so the app restarts whether or not the swap works
+    let swapped = swap_in(&app_handle, &staged).await;
+    let safety_copy = safety_copy.to_string_lossy().into_owned();
+    match &swapped {
+        Ok(()) => {
+            log::info!("[Backup] Restored {} (schema {}); previous database saved to {}", src_path, version, safety_copy);
+            if let Err(e) = app_handle.emit("database-restored", &safety_copy) {
+                log::error!("[Backup] Failed to emit database-restored: {}", e);
+            }
+        }
+        Err(e) => log::error!("[Backup] Restore failed, restarting on the previous database: {}", e),
+    }
+    restart_soon(&app_handle);
+    Ok(swapped.map(|()| safety_copy)?)
+}
+
+/// Closes the pool and moves `staged`, which must sit next to the database, over it. The pool
+/// stays closed even when the move fails, so callers restart the app either way
+pub(crate) async fn swap_in(app: &AppHandle, staged: &Path) -> Result<(), String> {
+    let db_path = db::db_path(app)?;
+    db::pool(app).await?.close().await;
+    for suffix in ["db-wal", "db-shm"] {
+        let sidecar = db_path.with_extension(suffix);
+        if sidecar.exists() {
+            std::fs::remove_file(&sidecar).map_err(|e| format!("Failed to remove {}: {}", sidecar.display(), e))?;
+        }
+    }
+    std::fs::rename(staged, &db_path).map_err(|e| format!("Failed to replace the database: {}", e))
+}
+
+/// Restarts the app after giving the event just emitted time to reach the frontend
+pub(crate) fn restart_soon(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(RESTART_DELAY).await;
+        app.restart();
+    });
+}
@@ -0,0 +1,133 @@
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Emitter};
+
+use crate::db;
+
+/// List used when `--add` is given without `--list` (or with an unknown one)
+const DEFAULT_LIST: &str = "Inbox";
+
+/// A task captured from the command line, e.g. `tada --add "Buy milk" --list Inbox`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddTaskArgs {
+    pub title: String,
+    pub list_name: Option<String>,
+}
+
+/// Finds `--add <title>` (and an optional `--list <name>`) in launch arguments; the first
+/// argument is the executable and is skipped. `None` when `--add` isn't present
+fn parse_add_args(args: &[String]) -> Option<Result<AddTaskArgs, String>> {
+    let mut title = None;
+    let mut list_name = None;
+    let mut found = false;
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--add" => {
+                found = true;
+                title = iter.next().map(|value| value.trim().to_string());
+            }
+            "--list" => list_name = iter.next().map(|value| value.trim().to_string()),
+            _ => {}
+        }
+    }
+    if !found {
+        return None;
+    }
+    Some(match title {
+        Some(title) if !title.is_empty() => Ok(AddTaskArgs {
+            title,
+            list_name: list_name.filter(|name| !name.is_empty()),
+        }),
+        _ => Err("--add needs a task title".to_string()),
+    })
+}
+
+/// Inserts the task the way the frontend's quick add does: on top of the open tasks, in
+/// the named list or the Inbox
+async fn insert_task(pool: &SqlitePool, task: &AddTaskArgs) -> Result<String, String> {
+    let requested = task.list_name.as_deref().unwrap_or(DEFAULT_LIST);
+    let mut list = sqlx::query_as::<_, (String, String)>("SELECT id, name FROM lists WHERE name = ? AND deleted_at IS NULL")
+        .bind(requested)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to look up list '{}': {}", requested, e))?;
+    if list.is_none() {
+        log::warn!("[Cli] List '{}' not found, adding to {}", requested, DEFAULT_LIST);
+        list = sqlx::query_as::<_, (String, String)>("SELECT id, name FROM lists WHERE name = ? AND deleted_at IS NULL")
+            .bind(DEFAULT_LIST)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| format!("Failed to look up list '{}': {}", DEFAULT_LIST, e))?;
+    }
+    let (list_id, list_name) = list.ok_or_else(|| format!("List '{}' not found", DEFAULT_LIST))?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let top_order = sqlx::query_scalar::<_, Option<i64>>(
+        r#"SELECT CAST(MIN("order") AS INTEGER) FROM tasks
+           WHERE completed = 0 AND list_name != 'Trash' AND deleted_at IS NULL"#,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Failed to read task order: {}", e))?;
+    let order = top_order.map_or(now, |order| order - 1000);
+
+    let id = format!("task-{}-cli", now);
+    sqlx::query(
+        r#"INSERT INTO tasks (id, title, completed, list_id, list_name, content, "order", created_at, updated_at, tags, group_category)
+           VALUES (?, ?, 0, ?, ?, '', ?, ?, ?, '[]', 'nodate')"#,
+    )
+    .bind(&id)
+    .bind(&task.title)
+    .bind(&list_id)
+    .bind(&list_name)
+    .bind(order)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to add task: {}", e))?;
+    Ok(id)
+}
+
+/// Handles `--add` on a cold start: the task goes straight into the database, before the
+/// frontend loads it, and the UI opens as usual
+pub fn handle_launch_args(app: &AppHandle) {
+    let args: Vec<String> = std::env::args().collect();
+    let task = match parse_add_args(&args) {
+        Some(Ok(task)) => task,
+        Some(Err(e)) => {
+            log::warn!("[Cli] {}", e);
+            return;
+        }
+        None => return,
+    };
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        insert_task(&pool, &task).await
+    });
+    match result {
+        Ok(id) => log::info!("[Cli] Added task {} from the command line", id),
+        Err(e) => log::error!("[Cli] {}", e),
+    }
+}
+
+/// Handles `--add` forwarded from a second launch; the running frontend owns the task cache,
+/// so it creates the task from a `cli-add-task` event. Returns whether `--add` was present
+pub fn handle_forwarded_args(app: &AppHandle, args: &[String]) -> bool {
+    match parse_add_args(args) {
+        Some(Ok(task)) => {
+            log::info!("[Cli] Forwarding task '{}' to the frontend", task.title);
+            if let Err(e) = app.emit("cli-add-task", task) {
+                log::error!("[Cli] Failed to emit cli-add-task: {}", e);
+            }
+            true
+        }
+        Some(Err(e)) => {
+            log::warn!("[Cli] {}", e);
+            true
+        }
+        None => false,
+    }
+}
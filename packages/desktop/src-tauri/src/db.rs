@@ -0,0 +1,191 @@
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqliteSynchronous};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::paths;
+
+/// Connection string shared with the frontend and the sql plugin preload config
+pub const DB_URL: &str = "sqlite:tada.db";
+
+/// URL the sql plugin preloads and migrates: `DB_URL`, or the database's absolute path when a
+/// data folder was given at launch. `configure_pool` files the pool under `DB_URL` either way
+pub fn preload_url() -> String {
+    match paths::data_dir_override() {
+        Some(dir) => format!("sqlite:{}", dir.join(DB_URL.trim_start_matches("sqlite:")).display()),
+        None => DB_URL.to_string(),
+    }
+}
+
+/// File behind `DB_URL`; the sql plugin resolves sqlite paths against the app config directory
+/// unless they're absolute, as they are in a data folder
+pub fn db_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    paths::app_config_dir(app).map(|dir| dir.join(DB_URL.trim_start_matches("sqlite:")))
+}
+
+/// Returns the pool the sql plugin opened (and migrated) for `tada.db`
+pub async fn pool(app: &AppHandle) -> Result<SqlitePool, String> {
+    let instances = app.state::<DbInstances>();
+    let instances = instances.0.read().await;
+    match instances.get(DB_URL) {
+        Some(DbPool::Sqlite(pool)) => Ok(pool.clone()),
+        None => Err(format!("Database {} is not loaded", DB_URL)),
+    }
+}
+
+/// Swaps the pool the sql plugin preloaded, which it opens from the bare URL, for one in WAL
+/// mode with `synchronous = NORMAL`. WAL lets the frontend read while the scheduler writes, and
+/// `NORMAL` only syncs at checkpoints, so a power cut can lose the last few commits but never
+/// corrupts the file. Foreign keys are enforced on every connection, so the schema's
+/// `ON DELETE` actions run. The pool is filed under `DB_URL` whatever `preload_url` the plugin
+/// opened it under, so the frontend and commands find it the same way. Runs before anything
+/// else touches the database
+pub fn configure_pool(app: &AppHandle) {
+    let result = tauri::async_runtime::block_on(async {
+        let instances = app.state::<DbInstances>();
+        let preloaded = preload_url();
+        if !instances.0.read().await.contains_key(&preloaded) {
+            return Err(format!("Database {} is not loaded", preloaded));
+        }
+        let pool = SqlitePool::connect_with(tuned(SqliteConnectOptions::new().filename(db_path(app)?))).await;
+        let mut instances = instances.0.write().await;
+        let previous = instances.remove(&preloaded);
+        let pool = match pool {
+            Ok(pool) => pool,
+            Err(e) => {
+                // Stay reachable under `DB_URL` with the plugin's own connection settings
+                if let Some(previous) = previous {
+                    instances.insert(DB_URL.to_string(), previous);
+                }
+                return Err(format!("Failed to open {}: {}", DB_URL, e));
+            }
+        };
+        instances.insert(DB_URL.to_string(), DbPool::Sqlite(pool));
+        if let Some(DbPool::Sqlite(previous)) = previous {
+            previous.close().await;
+        }
+        Ok(())
+    });
+    match result {
+        Ok(()) => log::info!("[Db] Opened {} in WAL mode", DB_URL),
+        Err(e) => log::warn!("[Db] Keeping the default connection settings: {}", e),
+    }
+}
+
+/// The connection settings `configure_pool` opens `tada.db` with
+fn tuned(options: SqliteConnectOptions) -> SqliteConnectOptions {
+    options
+        .journal_mode(SqliteJournalMode::Wal)
+        .synchronous(SqliteSynchronous::Normal)
+        .foreign_keys(true)
+}
+
+/// A fresh in-memory database with every migration applied, connected like `configure_pool`
+/// connects. One connection, since each in-memory connection is a database of its own. Applied
+/// versions go into `_sqlx_migrations` as the SQL plugin records them
+#[cfg(test)]
+pub(crate) async fn test_pool() -> SqlitePool {
+    let options = tuned("sqlite::memory:".parse().expect("valid in-memory URL"));
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .expect("open in-memory database");
+    sqlx::raw_sql(
+        "CREATE TABLE _sqlx_migrations \
+         (version BIGINT PRIMARY KEY, description TEXT NOT NULL, success BOOLEAN NOT NULL)",
+    )
+    .execute(&pool)
+    .await
+    .expect("create _sqlx_migrations");
+    for migration in crate::migrations() {
+        sqlx::raw_sql(migration.sql)
+            .execute(&pool)
+            .await
+            .unwrap_or_else(|e| panic!("migration {} failed: {}", migration.version, e));
+        sqlx::query("INSERT INTO _sqlx_migrations (version, description, success) VALUES (?, ?, 1)")
+            .bind(migration.version)
+            .bind(migration.description)
+            .execute(&pool)
+            .await
+            .expect("record migration");
+    }
+    pool
+}
+
+/// Reads the raw JSON value of a row in the `settings` table
+pub async fn get_setting(pool: &SqlitePool, key: &str) -> Result<Option<String>, String> {
+    sqlx::query_scalar::<_, String>("SELECT value FROM settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to read setting '{}': {}", key, e))
+}
+
+/// Writes a raw JSON value into the `settings` table, same shape as the frontend storage service
+pub async fn set_setting(pool: &SqlitePool, key: &str, value: &str) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    sqlx::query("INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES (?, ?, ?)")
+        .bind(key)
+        .bind(value)
+        .bind(now)
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to write setting '{}': {}", key, e))
+}
+
+/// Newest migration applied to `tada.db`
+pub async fn migration_version(pool: &SqlitePool) -> Result<i64, String> {
+    sqlx::query_scalar::<_, Option<i64>>("SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1")
+        .fetch_one(pool)
+        .await
+        .map(|version| version.unwrap_or(0))
+        .map_err(|e| format!("Failed to read the schema version: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deleting_a_task_cascades_to_its_subtasks() {
+        tauri::async_runtime::block_on(async {
+            let pool = test_pool().await;
+            let foreign_keys: i64 = sqlx::query_scalar("PRAGMA foreign_keys").fetch_one(&pool).await.unwrap();
+            assert_eq!(foreign_keys, 1);
+
+            for id in ["task-1", "task-2"] {
+                sqlx::query(
+                    r#"INSERT INTO tasks (id, title, list_name, "order", created_at, updated_at)
+                       VALUES (?, 'Task', 'Inbox', 0, 0, 0)"#,
+                )
+                .bind(id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+            for (id, parent_id, parent_subtask_id) in [
+                ("sub-1", "task-1", None),
+                ("sub-1a", "task-1", Some("sub-1")),
+                ("sub-2", "task-2", None),
+            ] {
+                sqlx::query(
+                    r#"INSERT INTO subtasks (id, parent_id, parent_subtask_id, title, "order", created_at, updated_at)
+                       VALUES (?, ?, ?, 'Step', 0, 0, 0)"#,
+                )
+                .bind(id)
+                .bind(parent_id)
+                .bind(parent_subtask_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+
+            sqlx::query("DELETE FROM tasks WHERE id = 'task-1'").execute(&pool).await.unwrap();
+            let left: Vec<String> =
+                sqlx::query_scalar("SELECT id FROM subtasks ORDER BY id").fetch_all(&pool).await.unwrap();
+            assert_eq!(left, ["sub-2"]);
+        });
+    }
+}
@@ -0,0 +1,51 @@
+use tauri::{AppHandle, Emitter, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::tray;
+
+/// URL scheme registered with the OS, e.g. `tada://task/<id>`
+const SCHEME: &str = "tada";
+
+/// Task id from a `tada://task/<id>` link
+fn parse_task_link(url: &Url) -> Option<String> {
+    if url.scheme() != SCHEME || url.host_str() != Some("task") {
+        return None;
+    }
+    let id = url.path().trim_matches('/');
+    (!id.is_empty() && !id.contains('/')).then(|| id.to_string())
+}
+
+fn open_url(app: &AppHandle, url: &Url) {
+    let Some(id) = parse_task_link(url) else {
+        log::warn!("[DeepLink] Ignoring unsupported link {}", url);
+        return;
+    };
+    log::info!("[DeepLink] Opening task {}", id);
+    tray::show_main_window(app);
+    if let Err(e) = app.emit("open-task", id) {
+        log::error!("[DeepLink] Failed to emit open-task: {}", e);
+    }
+}
+
+/// Routes `tada://` links to the main window: the one the app was launched with and any
+/// opened later; the single-instance plugin forwards links clicked while already running
+pub fn listen(app: &AppHandle) {
+    // Only bundled apps are registered by the installer; dev builds register at runtime
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        log::warn!("[DeepLink] Failed to register URL scheme: {}", e);
+    }
+
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => urls.iter().for_each(|url| open_url(app, url)),
+        Ok(None) => {}
+        Err(e) => log::warn!("[DeepLink] Failed to read launch link: {}", e),
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            open_url(&handle, &url);
+        }
+    });
+}
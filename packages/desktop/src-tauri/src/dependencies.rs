@@ -0,0 +1,170 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Emitter};
+
+use crate::db;
+use crate::error::CmdError;
+
+/// An open task waiting on at least one unfinished task
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedTask {
+    pub id: String,
+    pub title: String,
+    pub list_name: String,
+    /// Ids of the unfinished tasks it depends on
+    pub blocked_by: Vec<String>,
+}
+
+/// Whether adding `task_id -> depends_on_id` to `edges` (task, depends on) would close a cycle:
+/// it does when `depends_on_id` already waits on `task_id`, directly or through other tasks
+fn closes_cycle(edges: &[(String, String)], task_id: &str, depends_on_id: &str) -> bool {
+    let mut upstream: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (task, depends_on) in edges {
+        upstream.entry(task.as_str()).or_default().push(depends_on.as_str());
+    }
+    let mut seen = HashSet::new();
+    let mut pending = vec![depends_on_id];
+    while let Some(id) = pending.pop() {
+        if id == task_id {
+            return true;
+        }
+        if seen.insert(id) {
+            pending.extend(upstream.get(id).into_iter().flatten());
+        }
+    }
+    false
+}
+
+/// Command to make `task_id` wait on `depends_on_id`. Refused when it would close a cycle
+#[tauri::command]
+pub async fn add_dependency(app_handle: AppHandle, task_id: String, depends_on_id: String) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    for id in [&task_id, &depends_on_id] {
+        let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM tasks WHERE id = ? AND deleted_at IS NULL")
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| CmdError::Db(format!("Failed to look up task '{}': {}", id, e)))?
+            > 0;
+        if !exists {
+            return Err(CmdError::NotFound(format!("Task '{}' not found", id)));
+        }
+    }
+    let edges = sqlx::query_as::<_, (String, String)>("SELECT task_id, depends_on_id FROM task_dependencies")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to read task dependencies: {}", e)))?;
+    if closes_cycle(&edges, &task_id, &depends_on_id) {
+        return Err(CmdError::Validation(format!(
+            "Task '{}' can't depend on '{}': that would create a dependency cycle",
+            task_id, depends_on_id
+        )));
+    }
+    sqlx::query("INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_id, created_at) VALUES (?, ?, ?)")
+        .bind(&task_id)
+        .bind(&depends_on_id)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .execute(&pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to add dependency: {}", e)))?;
+    log::info!("[Dependencies] Task {} now depends on {}", task_id, depends_on_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_dependency(app_handle: AppHandle, task_id: String, depends_on_id: String) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query("DELETE FROM task_dependencies WHERE task_id = ? AND depends_on_id = ?")
+        .bind(&task_id)
+        .bind(&depends_on_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to remove dependency: {}", e)))?;
+    Ok(())
+}
+
+/// Command listing open tasks with unfinished dependencies. Deleted tasks block nothing
+#[tauri::command]
+pub async fn get_blocked_tasks(app_handle: AppHandle) -> Result<Vec<BlockedTask>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let rows = sqlx::query_as::<_, (String, String, String, String)>(
+        r#"SELECT t.id, t.title, t.list_name, d.depends_on_id
+           FROM task_dependencies d
+           JOIN tasks t ON t.id = d.task_id
+           JOIN tasks dep ON dep.id = d.depends_on_id
+           WHERE t.completed = 0 AND t.deleted_at IS NULL AND dep.completed = 0 AND dep.deleted_at IS NULL
+           ORDER BY t.pinned DESC, t."order", t.id"#,
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read blocked tasks: {}", e)))?;
+
+    let mut blocked: Vec<BlockedTask> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (id, title, list_name, depends_on_id) in rows {
+        match index.get(&id) {
+            Some(&position) => blocked[position].blocked_by.push(depends_on_id),
+            None => {
+                index.insert(id.clone(), blocked.len());
+                blocked.push(BlockedTask { id, title, list_name, blocked_by: vec![depends_on_id] });
+            }
+        }
+    }
+    Ok(blocked)
+}
+
+/// Command run after completing a task: emits `dependencies-unblocked` with the open tasks
+/// that were waiting on it and have nothing left to wait for, and returns their ids
+#[tauri::command]
+pub async fn check_unblocked_tasks(app_handle: AppHandle, task_id: String) -> Result<Vec<String>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let unblocked = sqlx::query_scalar::<_, String>(
+        "SELECT t.id FROM task_dependencies d JOIN tasks t ON t.id = d.task_id
+         WHERE d.depends_on_id = ? AND t.completed = 0 AND t.deleted_at IS NULL
+           AND NOT EXISTS (
+               SELECT 1 FROM task_dependencies other JOIN tasks dep ON dep.id = other.depends_on_id
+               WHERE other.task_id = t.id AND dep.completed = 0 AND dep.deleted_at IS NULL
+           )",
+    )
+    .bind(&task_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read unblocked tasks: {}", e)))?;
+    if !unblocked.is_empty() {
+        log::info!("[Dependencies] Completing {} unblocked {} task(s)", task_id, unblocked.len());
+        if let Err(e) = app_handle.emit("dependencies-unblocked", &unblocked) {
+            log::error!("[Dependencies] Failed to emit dependencies-unblocked: {}", e);
+        }
+    }
+    Ok(unblocked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(task, depends_on)| (task.to_string(), depends_on.to_string())).collect()
+    }
+
+    #[test]
+    fn refuses_three_node_cycle() {
+        let existing = edges(&[("A", "B"), ("B", "C")]);
+        assert!(closes_cycle(&existing, "C", "A"));
+    }
+
+    #[test]
+    fn refuses_self_and_two_node_cycles() {
+        assert!(closes_cycle(&[], "A", "A"));
+        assert!(closes_cycle(&edges(&[("A", "B")]), "B", "A"));
+    }
+
+    #[test]
+    fn allows_shared_dependencies() {
+        let existing = edges(&[("A", "B"), ("B", "C"), ("D", "C")]);
+        assert!(!closes_cycle(&existing, "A", "C"));
+        assert!(!closes_cycle(&existing, "A", "D"));
+        assert!(!closes_cycle(&existing, "C", "E"));
+    }
+}
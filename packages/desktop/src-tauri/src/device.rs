@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::error::CmdError;
+use crate::{db, paths};
+
+/// Settings key mirroring the id, for the triggers that stamp `device_id` on tasks and lists
+const DEVICE_ID_KEY: &str = "device_id";
+
+/// File beside the database the id is kept in. Not the settings row alone: a sync pull or a
+/// restore replaces the whole database, and would hand this install another device's id
+fn id_path(app: &AppHandle) -> Result<PathBuf, String> {
+    paths::app_config_dir(app).map(|dir| dir.join("device-id"))
+}
+
+/// A random version 4 UUID
+fn new_device_id() -> String {
+    use aes_gcm::aead::rand_core::RngCore;
+    use aes_gcm::aead::OsRng;
+
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+/// This install's id, generated on first run
+fn load_or_create(app: &AppHandle) -> Result<String, String> {
+    let path = id_path(app)?;
+    if let Some(id) = std::fs::read_to_string(&path).ok().map(|id| id.trim().to_string()).filter(|id| !id.is_empty()) {
+        return Ok(id);
+    }
+    let id = new_device_id();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(&path, &id).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    log::info!("[Device] Generated device id {}", id);
+    Ok(id)
+}
+
+/// Writes this install's id into settings on startup, replacing one a pull or restore brought
+/// along, so every write from here on is stamped with it
+pub fn sync_device_id(app: &AppHandle) {
+    let result = tauri::async_runtime::block_on(async {
+        let id = load_or_create(app)?;
+        let value = serde_json::to_string(&id).map_err(|e| format!("Failed to serialize device id: {}", e))?;
+        let pool = db::pool(app).await?;
+        if db::get_setting(&pool, DEVICE_ID_KEY).await?.as_deref() != Some(value.as_str()) {
+            db::set_setting(&pool, DEVICE_ID_KEY, &value).await?;
+        }
+        Ok::<_, String>(())
+    });
+    if let Err(e) = result {
+        log::warn!("[Device] Writes won't record this device: {}", e);
+    }
+}
+
+/// Command for the id of this install, which tasks and lists record as `device_id` when written
+#[tauri::command]
+pub async fn get_device_id(app_handle: AppHandle) -> Result<String, CmdError> {
+    Ok(load_or_create(&app_handle)?)
+}
@@ -0,0 +1,174 @@
+use chrono::{NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::error::CmdError;
+use crate::{db, idle};
+
+/// Settings key for whether reminders wait out the OS do-not-disturb state, as a JSON bool
+const RESPECT_SYSTEM_DND_KEY: &str = "respect_system_dnd";
+
+/// Settings key for the in-app quiet hours, a JSON array of `QuietRange`
+const QUIET_RANGES_KEY: &str = "quiet_ranges";
+
+/// Settings key for how long without input counts as away, in minutes; 0 turns it off
+const IDLE_THRESHOLD_KEY: &str = "idle_threshold_minutes";
+
+const DEFAULT_IDLE_THRESHOLD_MINUTES: u32 = 15;
+
+/// Daily local-time window without notifications, e.g. `22:00`-`07:00`; it may wrap midnight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietRange {
+    /// `HH:MM`, inclusive
+    pub start: String,
+    /// `HH:MM`, exclusive
+    pub end: String,
+}
+
+impl QuietRange {
+    fn contains(&self, now: NaiveTime) -> bool {
+        let (Ok(start), Ok(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        if start <= end {
+            start <= now && now < end
+        } else {
+            now >= start || now < end
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationSettings {
+    pub respect_system_dnd: bool,
+    pub quiet_ranges: Vec<QuietRange>,
+    /// Reminders wait while there was no input for this long, then go out as one notification
+    pub idle_threshold_minutes: u32,
+}
+
+/// Why a notification is held back, as reported in `notification-deferred`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuietReason {
+    SystemDnd,
+    QuietHours,
+    Idle,
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| format!("Invalid time '{}'; use HH:MM", value))
+}
+
+async fn load_settings(pool: &sqlx::SqlitePool) -> Result<NotificationSettings, String> {
+    let respect_system_dnd = db::get_setting(pool, RESPECT_SYSTEM_DND_KEY)
+        .await?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or(true);
+    let quiet_ranges = db::get_setting(pool, QUIET_RANGES_KEY)
+        .await?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default();
+    let idle_threshold_minutes = db::get_setting(pool, IDLE_THRESHOLD_KEY)
+        .await?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or(DEFAULT_IDLE_THRESHOLD_MINUTES);
+    Ok(NotificationSettings { respect_system_dnd, quiet_ranges, idle_threshold_minutes })
+}
+
+/// The OS do-not-disturb / Focus state, or `None` where it can't be read
+#[cfg(target_os = "macos")]
+fn system_dnd() -> Option<bool> {
+    // Focus (macOS 12+) records manually enabled modes here; older releases use the defaults flag
+    if let Some(home) = std::env::var_os("HOME") {
+        let path = std::path::Path::new(&home).join("Library/DoNotDisturb/DB/Assertions.json");
+        if let Ok(contents) = std::fs::read_to_string(path) {
+            let json: serde_json::Value = serde_json::from_str(&contents).ok()?;
+            let active = json["data"].as_array().is_some_and(|data| {
+                data.iter()
+                    .any(|entry| entry["storeAssertionRecords"].as_array().is_some_and(|records| !records.is_empty()))
+            });
+            return Some(active);
+        }
+    }
+    let output = std::process::Command::new("defaults")
+        .args(["-currentHost", "read", "com.apple.notificationcenterui", "doNotDisturb"])
+        .output()
+        .ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim() == "1")
+}
+
+/// The OS do-not-disturb state, or `None` where it can't be read. GNOME exposes it as the
+/// notification banners switch; other desktops fall back to quiet hours
+#[cfg(target_os = "linux")]
+fn system_dnd() -> Option<bool> {
+    let output = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "false" => Some(true),
+        "true" => Some(false),
+        _ => None,
+    }
+}
+
+/// Windows has no public API for Focus Assist, and mobile systems silence notifications
+/// themselves, so these rely on quiet hours
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn system_dnd() -> Option<bool> {
+    None
+}
+
+/// Whether notifications should wait right now, and why. The OS state wins where it can be
+/// read and is respected; otherwise the quiet hours decide. Past those, being away holds them
+pub async fn quiet_reason(app: &AppHandle) -> Result<Option<QuietReason>, String> {
+    let pool = db::pool(app).await?;
+    let settings = load_settings(&pool).await?;
+    let system = if settings.respect_system_dnd {
+        tauri::async_runtime::spawn_blocking(system_dnd).await.ok().flatten()
+    } else {
+        None
+    };
+    let now = chrono::Local::now().time().with_nanosecond(0).unwrap_or_default();
+    let quiet = match system {
+        Some(active) => active,
+        None => settings.quiet_ranges.iter().any(|range| range.contains(now)),
+    };
+    if quiet {
+        return Ok(Some(if system.is_some() { QuietReason::SystemDnd } else { QuietReason::QuietHours }));
+    }
+    if settings.idle_threshold_minutes > 0 {
+        let threshold = Duration::from_secs(u64::from(settings.idle_threshold_minutes) * 60);
+        let idle = tauri::async_runtime::spawn_blocking(idle::system_idle).await.ok().flatten();
+        if idle.is_some_and(|idle| idle >= threshold) {
+            return Ok(Some(QuietReason::Idle));
+        }
+    }
+    Ok(None)
+}
+
+#[tauri::command]
+pub async fn get_notification_settings(app_handle: AppHandle) -> Result<NotificationSettings, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    Ok(load_settings(&pool).await?)
+}
+
+#[tauri::command]
+pub async fn set_notification_settings(app_handle: AppHandle, settings: NotificationSettings) -> Result<(), CmdError> {
+    for range in &settings.quiet_ranges {
+        parse_time(&range.start).map_err(CmdError::Validation)?;
+        parse_time(&range.end).map_err(CmdError::Validation)?;
+    }
+    let pool = db::pool(&app_handle).await?;
+    db::set_setting(&pool, RESPECT_SYSTEM_DND_KEY, &settings.respect_system_dnd.to_string()).await?;
+    db::set_setting(&pool, IDLE_THRESHOLD_KEY, &settings.idle_threshold_minutes.to_string()).await?;
+    let ranges = serde_json::to_string(&settings.quiet_ranges)
+        .map_err(|e| format!("Failed to serialize quiet ranges: {}", e))?;
+    db::set_setting(&pool, QUIET_RANGES_KEY, &ranges).await.map_err(CmdError::Db)
+}
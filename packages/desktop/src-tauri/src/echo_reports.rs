@@ -0,0 +1,125 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::db;
+use crate::error::CmdError;
+
+/// Styles the frontend's `EchoReport` can have
+const STYLES: &[&str] = &["balanced", "exploration", "reflection"];
+
+/// Page size when `list_echo_reports` isn't given one
+const DEFAULT_PAGE_SIZE: u32 = 20;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EchoReport {
+    pub id: String,
+    pub created_at: i64,
+    pub content: String,
+    pub job_types: Vec<String>,
+    pub style: String,
+    pub user_input: Option<String>,
+}
+
+type EchoReportRow = (String, i64, String, String, String, Option<String>);
+
+fn echo_report((id, created_at, content, job_types, style, user_input): EchoReportRow) -> EchoReport {
+    EchoReport {
+        id,
+        created_at,
+        content,
+        job_types: serde_json::from_str(&job_types).unwrap_or_default(),
+        style,
+        user_input,
+    }
+}
+
+/// Command to store a generated echo report. Emits `echo-report-saved` with it
+#[tauri::command]
+pub async fn create_echo_report(
+    app_handle: AppHandle,
+    content: String,
+    job_types: Vec<String>,
+    style: String,
+    user_input: Option<String>,
+) -> Result<EchoReport, CmdError> {
+    if !STYLES.contains(&style.as_str()) {
+        return Err(CmdError::Validation(format!("Unknown echo report style '{}'", style)));
+    }
+    let pool = db::pool(&app_handle).await?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let id = format!("echo-{}-{}", now, chrono::Utc::now().timestamp_subsec_nanos());
+    let user_input = user_input.filter(|input| !input.trim().is_empty());
+    let job_types_json = serde_json::to_string(&job_types).map_err(|e| format!("Failed to serialize job types: {}", e))?;
+    sqlx::query(
+        "INSERT INTO echo_reports (id, created_at, content, job_types, style, user_input) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(now)
+    .bind(&content)
+    .bind(&job_types_json)
+    .bind(&style)
+    .bind(&user_input)
+    .execute(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to create echo report: {}", e)))?;
+
+    let report = EchoReport { id, created_at: now, content, job_types, style, user_input };
+    if let Err(e) = app_handle.emit("echo-report-saved", &report) {
+        log::error!("[EchoReports] Failed to emit echo-report-saved: {}", e);
+    }
+    Ok(report)
+}
+
+/// Command for a page of echo reports, newest first
+#[tauri::command]
+pub async fn list_echo_reports(
+    app_handle: AppHandle,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<EchoReport>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let rows = sqlx::query_as::<_, EchoReportRow>(
+        "SELECT id, created_at, content, job_types, style, user_input FROM echo_reports
+         ORDER BY created_at DESC LIMIT ? OFFSET ?",
+    )
+    .bind(limit.unwrap_or(DEFAULT_PAGE_SIZE))
+    .bind(offset.unwrap_or(0))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to load echo reports: {}", e)))?;
+    Ok(rows.into_iter().map(echo_report).collect())
+}
+
+#[tauri::command]
+pub async fn get_echo_report(app_handle: AppHandle, id: String) -> Result<EchoReport, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query_as::<_, EchoReportRow>(
+        "SELECT id, created_at, content, job_types, style, user_input FROM echo_reports WHERE id = ?",
+    )
+    .bind(&id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to load echo report '{}': {}", id, e)))?
+    .map(echo_report)
+    .ok_or_else(|| CmdError::NotFound(format!("Echo report '{}' not found", id)))
+}
+
+/// Command to delete an echo report. Emits `echo-report-deleted` with its id
+#[tauri::command]
+pub async fn delete_echo_report(app_handle: AppHandle, id: String) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let deleted = sqlx::query("DELETE FROM echo_reports WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to delete echo report '{}': {}", id, e)))?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(CmdError::NotFound(format!("Echo report '{}' not found", id)));
+    }
+    if let Err(e) = app_handle.emit("echo-report-deleted", &id) {
+        log::error!("[EchoReports] Failed to emit echo-report-deleted: {}", e);
+    }
+    Ok(())
+}
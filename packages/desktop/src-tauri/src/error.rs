@@ -0,0 +1,117 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+use tauri_plugin_http::reqwest;
+
+use crate::ai::AiError;
+
+/// Failure of a command, serialized as `{ code, message }` so the frontend can branch on `code`
+/// and show `message` as it is. AI failures carry the `AiError` kind too, e.g.
+/// `{ code: "ai", kind: "unauthorized", message }` when the provider rejected the API key
+#[derive(Debug, Clone)]
+pub enum CmdError {
+    /// Reading or writing `tada.db` failed
+    Db(String),
+    /// The arguments were rejected; the message says what to change
+    Validation(String),
+    /// The task, list or other item asked for doesn't exist, or no longer does
+    NotFound(String),
+    Ai(AiError),
+    /// A file, folder or network request failed
+    Io(String),
+    /// Anything else, including failures from helpers that report plain strings
+    Internal(String),
+}
+
+impl CmdError {
+    /// The `code` the frontend sees
+    pub fn code(&self) -> &'static str {
+        match self {
+            CmdError::Db(_) => "db",
+            CmdError::Validation(_) => "validation",
+            CmdError::NotFound(_) => "notFound",
+            CmdError::Ai(_) => "ai",
+            CmdError::Io(_) => "io",
+            CmdError::Internal(_) => "internal",
+        }
+    }
+}
+
+impl fmt::Display for CmdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CmdError::Db(message)
+            | CmdError::Validation(message)
+            | CmdError::NotFound(message)
+            | CmdError::Io(message)
+            | CmdError::Internal(message) => f.write_str(message),
+            CmdError::Ai(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for CmdError {}
+
+impl Serialize for CmdError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let kind = match self {
+            CmdError::Ai(error) => Some(error.kind()),
+            _ => None,
+        };
+        let mut state = serializer.serialize_struct("CmdError", if kind.is_some() { 3 } else { 2 })?;
+        state.serialize_field("code", self.code())?;
+        if let Some(kind) = kind {
+            state.serialize_field("kind", kind)?;
+        }
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl From<String> for CmdError {
+    fn from(message: String) -> Self {
+        CmdError::Internal(message)
+    }
+}
+
+impl From<sqlx::Error> for CmdError {
+    fn from(error: sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::RowNotFound => CmdError::NotFound(error.to_string()),
+            _ => CmdError::Db(error.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for CmdError {
+    fn from(error: std::io::Error) -> Self {
+        CmdError::Io(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for CmdError {
+    /// Malformed JSON came in from the caller or a file; only a failing reader or writer is `Io`
+    fn from(error: serde_json::Error) -> Self {
+        match error.classify() {
+            serde_json::error::Category::Io => CmdError::Io(error.to_string()),
+            _ => CmdError::Validation(error.to_string()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for CmdError {
+    fn from(error: reqwest::Error) -> Self {
+        CmdError::Io(error.to_string())
+    }
+}
+
+impl From<tauri::Error> for CmdError {
+    fn from(error: tauri::Error) -> Self {
+        CmdError::Internal(error.to_string())
+    }
+}
+
+impl From<AiError> for CmdError {
+    fn from(error: AiError) -> Self {
+        CmdError::Ai(error)
+    }
+}
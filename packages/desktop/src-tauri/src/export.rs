@@ -0,0 +1,571 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Column, Row, TypeInfo, ValueRef};
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+use crate::db;
+use crate::error::CmdError;
+
+/// Marks a file as a Tada export
+pub const EXPORT_FORMAT: &str = "tada-export";
+
+/// Version of the export layout itself, bumped when `import_data` has to convert older files
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// First bytes of an encrypted export; the salt and nonce follow, then the ciphertext
+const ENCRYPTED_MAGIC: &[u8; 8] = b"TADAENC1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Tables in an export, parents before children so an import can insert them in this order
+pub const EXPORT_TABLES: &[&str] = &[
+    "lists",
+    "tags",
+    "tasks",
+    "subtasks",
+    "task_tags",
+    "summaries",
+    "echo_reports",
+    "user_profile",
+];
+
+/// The interchange document: every row of `EXPORT_TABLES` keyed by column name, with values
+/// as stored, so timestamps are Unix millis and JSON columns (`tasks.tags`...) stay JSON text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportDocument {
+    pub format: String,
+    pub schema_version: u32,
+    /// Migration version of the database the rows came from
+    pub database_version: i64,
+    pub exported_at: i64,
+    /// Rows per table, for a quick look without reading `data`
+    pub counts: BTreeMap<String, usize>,
+    pub data: BTreeMap<String, Vec<serde_json::Map<String, serde_json::Value>>>,
+}
+
+/// A row as a JSON object, decoding each value by its SQLite storage class
+fn row_to_json(row: &sqlx::sqlite::SqliteRow) -> Result<serde_json::Map<String, serde_json::Value>, sqlx::Error> {
+    let mut object = serde_json::Map::new();
+    for column in row.columns() {
+        let index = column.ordinal();
+        let raw = row.try_get_raw(index)?;
+        let value = if raw.is_null() {
+            serde_json::Value::Null
+        } else {
+            match raw.type_info().name() {
+                "INTEGER" => row.try_get::<i64, _>(index)?.into(),
+                "REAL" => row.try_get::<f64, _>(index)?.into(),
+                "BLOB" => String::from_utf8_lossy(&row.try_get::<Vec<u8>, _>(index)?).into_owned().into(),
+                _ => row.try_get::<String, _>(index)?.into(),
+            }
+        };
+        object.insert(column.name().to_string(), value);
+    }
+    Ok(object)
+}
+
+/// Reads the whole database into an export document
+pub async fn build_export(pool: &sqlx::SqlitePool) -> Result<ExportDocument, String> {
+    let mut data = BTreeMap::new();
+    for table in EXPORT_TABLES {
+        let rows = sqlx::query(&format!("SELECT * FROM {}", table))
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", table, e))?;
+        let rows = rows
+            .iter()
+            .map(row_to_json)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read {}: {}", table, e))?;
+        data.insert(table.to_string(), rows);
+    }
+    let database_version = db::migration_version(pool).await?;
+    Ok(ExportDocument {
+        format: EXPORT_FORMAT.to_string(),
+        schema_version: EXPORT_SCHEMA_VERSION,
+        database_version,
+        exported_at: chrono::Utc::now().timestamp_millis(),
+        counts: data.iter().map(|(table, rows)| (table.clone(), rows.len())).collect(),
+        data,
+    })
+}
+
+/// Command to export everything to a JSON file the user picks; returns the path written.
+/// This is the format `import_data` reads
+#[tauri::command]
+pub async fn export_data(app_handle: AppHandle) -> Result<String, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let document = build_export(&pool).await?;
+    let json = serde_json::to_string_pretty(&document).map_err(|e| format!("Failed to serialize export: {}", e))?;
+
+    let file_name = format!("tada-export-{}.json", chrono::Local::now().format("%Y%m%d"));
+    let Some(path) = app_handle
+        .dialog()
+        .file()
+        .set_file_name(file_name)
+        .add_filter("JSON", &["json"])
+        .blocking_save_file()
+    else {
+        return Err(CmdError::Internal("Export cancelled".to_string()));
+    };
+    let path = path.into_path().map_err(|e| CmdError::Validation(format!("Invalid export path: {}", e)))?;
+    std::fs::write(&path, json).map_err(|e| CmdError::Io(format!("Failed to write {}: {}", path.display(), e)))?;
+    log::info!("[Export] Exported {} tasks to {}", document.counts.get("tasks").unwrap_or(&0), path.display());
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// A 256-bit key from the passphrase. The Argon2id parameters are spelled out rather than
+/// left to the crate's defaults, since files written today must still open after an upgrade
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let params = argon2::Params::new(19 * 1024, 2, 1, Some(32)).map_err(|e| format!("Invalid key parameters: {}", e))?;
+    let mut key = [0u8; 32];
+    argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive the key: {}", e))?;
+    Ok(key)
+}
+
+/// `plaintext` sealed with AES-256-GCM under a key derived from `passphrase`, behind a header
+/// of the magic bytes, the salt and the nonce
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    use aes_gcm::aead::rand_core::RngCore;
+    use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = aes_gcm::Aes256Gcm::new(&key.into());
+    let nonce = aes_gcm::Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, plaintext).map_err(|_| "Encryption failed".to_string())?;
+
+    let mut sealed = Vec::with_capacity(ENCRYPTED_MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(ENCRYPTED_MAGIC);
+    sealed.extend_from_slice(&salt);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Opens a file written by `encrypt`. A wrong passphrase and a tampered file look the same to
+/// GCM, and both come back as "Decryption failed"
+pub fn decrypt(sealed: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    use aes_gcm::aead::{Aead, KeyInit};
+
+    let header = ENCRYPTED_MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if sealed.len() < header || !sealed.starts_with(ENCRYPTED_MAGIC) {
+        return Err("The file is not an encrypted Tada export".to_string());
+    }
+    let salt = &sealed[ENCRYPTED_MAGIC.len()..ENCRYPTED_MAGIC.len() + SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce.copy_from_slice(&sealed[ENCRYPTED_MAGIC.len() + SALT_LEN..header]);
+    let key = derive_key(passphrase, salt)?;
+    aes_gcm::Aes256Gcm::new(&key.into())
+        .decrypt(&nonce.into(), &sealed[header..])
+        .map_err(|_| "Decryption failed: wrong passphrase or damaged file".to_string())
+}
+
+/// The bytes of an encrypted export file: `document` as JSON, sealed by `encrypt`
+pub fn seal_export(document: &ExportDocument, passphrase: &str) -> Result<Vec<u8>, String> {
+    let json = serde_json::to_vec(document).map_err(|e| format!("Failed to serialize export: {}", e))?;
+    encrypt(&json, passphrase)
+}
+
+/// Command to write the JSON export encrypted with `passphrase` to `path`; `import_encrypted`
+/// reads it back. Returns the path written
+#[tauri::command]
+pub async fn export_encrypted(app_handle: AppHandle, path: String, passphrase: String) -> Result<String, CmdError> {
+    if passphrase.is_empty() {
+        return Err(CmdError::Validation("A passphrase is required".to_string()));
+    }
+    let pool = db::pool(&app_handle).await?;
+    let document = build_export(&pool).await?;
+    let tasks = document.counts.get("tasks").copied().unwrap_or(0);
+    let sealed = tauri::async_runtime::spawn_blocking(move || seal_export(&document, &passphrase))
+        .await
+        .map_err(|e| format!("Encryption failed: {}", e))??;
+    std::fs::write(&path, sealed).map_err(|e| CmdError::Io(format!("Failed to write {}: {}", path, e)))?;
+    log::info!("[Export] Exported {} tasks encrypted to {}", tasks, path);
+    Ok(path)
+}
+
+/// (id, parent subtask, title, completed, due date)
+type SubtaskRow = (String, Option<String>, String, bool, Option<i64>);
+
+/// (id, title, content, list name, completed, complete percentage, due date, priority, tags,
+/// completed at, created at, updated at)
+type TaskCsvRow = (
+    String,
+    String,
+    Option<String>,
+    String,
+    bool,
+    Option<i64>,
+    Option<i64>,
+    Option<i64>,
+    Option<String>,
+    Option<i64>,
+    i64,
+    i64,
+);
+
+/// A title on one line, so it can't break out of its list item
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Appends `  (due 2024-03-01) #tag` style annotations for whatever the item has
+fn annotations(due_date: Option<i64>, tags: &[String]) -> String {
+    let mut line = String::new();
+    if let Some(due) = due_date.and_then(chrono::DateTime::from_timestamp_millis) {
+        line.push_str(&format!(" (due {})", due.with_timezone(&chrono::Local).format("%Y-%m-%d")));
+    }
+    for tag in tags {
+        line.push_str(&format!(" #{}", tag.replace(char::is_whitespace, "-")));
+    }
+    line
+}
+
+/// Command to render tasks as Markdown: each list a `##` heading, tasks as `- [ ]` / `- [x]`
+/// items in their `order` with subtasks indented below, due dates and tags inline. Covers one
+/// list, or every list that isn't archived; the text is returned for copying or saving
+#[tauri::command]
+pub async fn export_markdown(
+    app_handle: AppHandle,
+    list_id: Option<String>,
+    include_completed: bool,
+) -> Result<String, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let lists = sqlx::query_as::<_, (String, String)>(
+        r#"SELECT id, name FROM lists
+           WHERE deleted_at IS NULL AND (id = ?1 OR (?1 IS NULL AND archived = 0))
+           ORDER BY "order", created_at"#,
+    )
+    .bind(&list_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read lists: {}", e)))?;
+    if let Some(list_id) = &list_id
+        && lists.is_empty()
+    {
+        return Err(CmdError::NotFound(format!("List '{}' not found", list_id)));
+    }
+
+    let mut markdown = String::new();
+    for (id, name) in lists {
+        let tasks = sqlx::query_as::<_, (String, String, bool, Option<i64>, Option<String>)>(
+            r#"SELECT id, title, completed, due_date, tags FROM tasks
+               WHERE list_id = ? AND deleted_at IS NULL AND (completed = 0 OR ?)
+               ORDER BY "order""#,
+        )
+        .bind(&id)
+        .bind(include_completed)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to read tasks: {}", e)))?;
+
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+        markdown.push_str(&format!("## {}\n\n", single_line(&name)));
+        for (task_id, title, completed, due_date, tags) in tasks {
+            let tags: Vec<String> = tags.and_then(|tags| serde_json::from_str(&tags).ok()).unwrap_or_default();
+            markdown.push_str(&format!(
+                "- [{}] {}{}\n",
+                if completed { "x" } else { " " },
+                single_line(&title),
+                annotations(due_date, &tags)
+            ));
+
+            let subtasks = sqlx::query_as::<_, SubtaskRow>(
+                r#"SELECT id, parent_subtask_id, title, completed, due_date FROM subtasks
+                   WHERE parent_id = ? ORDER BY "order""#,
+            )
+            .bind(&task_id)
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| CmdError::Db(format!("Failed to read subtasks: {}", e)))?;
+            // Depth-first from the top level; a subtask whose parent is gone is shown at the top
+            let ids: std::collections::HashSet<&str> = subtasks.iter().map(|subtask| subtask.0.as_str()).collect();
+            let mut pending: Vec<(usize, &SubtaskRow)> = subtasks
+                .iter()
+                .rev()
+                .filter(|subtask| subtask.1.as_deref().is_none_or(|parent| !ids.contains(parent)))
+                .map(|subtask| (1, subtask))
+                .collect();
+            while let Some((depth, (subtask_id, _, title, completed, due_date))) = pending.pop() {
+                markdown.push_str(&format!(
+                    "{}- [{}] {}{}\n",
+                    "  ".repeat(depth),
+                    if *completed { "x" } else { " " },
+                    single_line(title),
+                    annotations(*due_date, &[])
+                ));
+                if depth < crate::subtasks::MAX_DEPTH {
+                    pending.extend(
+                        subtasks
+                            .iter()
+                            .rev()
+                            .filter(|subtask| subtask.1.as_deref() == Some(subtask_id.as_str()))
+                            .map(|subtask| (depth + 1, subtask)),
+                    );
+                }
+            }
+        }
+    }
+    Ok(markdown)
+}
+
+/// Escapes TEXT values per RFC 5545
+fn ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line to 75 octets, continuation lines starting with a space, and ends it
+/// with CRLF
+fn push_ics_line(ics: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            ics.push_str("\r\n ");
+            width = 1;
+        }
+        ics.push(c);
+        width += c.len_utf8();
+    }
+    ics.push_str("\r\n");
+}
+
+/// Command to render due tasks as an iCalendar feed, one VEVENT per task with a due date. A
+/// due date at local midnight becomes an all-day event, anything else a timed one lasting the
+/// task's estimate. The UID comes from the task id, so importing the feed again updates events
+/// instead of duplicating them. Completed tasks are left out unless `include_completed`, and
+/// then marked with a check in the summary
+#[tauri::command]
+pub async fn export_ics(
+    app_handle: AppHandle,
+    list_id: Option<String>,
+    include_completed: Option<bool>,
+) -> Result<String, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let tasks = sqlx::query_as::<_, (String, String, Option<String>, i64, bool, Option<i64>, i64)>(
+        r#"SELECT t.id, t.title, t.content, t.due_date, t.completed, t.estimated_minutes, t.updated_at
+           FROM tasks t JOIN lists l ON l.id = t.list_id
+           WHERE t.due_date IS NOT NULL AND t.deleted_at IS NULL AND l.deleted_at IS NULL
+             AND (t.list_id = ?1 OR (?1 IS NULL AND l.archived = 0))
+             AND (t.completed = 0 OR ?2)
+           ORDER BY t.due_date"#,
+    )
+    .bind(&list_id)
+    .bind(include_completed.unwrap_or(false))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read tasks: {}", e)))?;
+
+    let utc = |millis: i64| {
+        chrono::DateTime::from_timestamp_millis(millis).map(|date_time| date_time.format("%Y%m%dT%H%M%SZ").to_string())
+    };
+    let mut ics = String::new();
+    for line in ["BEGIN:VCALENDAR", "VERSION:2.0", "PRODID:-//LoadShine//Tada//EN", "CALSCALE:GREGORIAN", "X-WR-CALNAME:Tada"] {
+        push_ics_line(&mut ics, line);
+    }
+    for (id, title, content, due_date, completed, estimated_minutes, updated_at) in tasks {
+        let Some(due) = chrono::DateTime::from_timestamp_millis(due_date) else {
+            continue;
+        };
+        let local = due.with_timezone(&chrono::Local);
+        push_ics_line(&mut ics, "BEGIN:VEVENT");
+        push_ics_line(&mut ics, &format!("UID:{}@tada", ics_text(&id)));
+        if let Some(stamp) = utc(updated_at) {
+            push_ics_line(&mut ics, &format!("DTSTAMP:{}", stamp));
+            push_ics_line(&mut ics, &format!("LAST-MODIFIED:{}", stamp));
+        }
+        if local.time() == chrono::NaiveTime::MIN {
+            let day = local.date_naive();
+            push_ics_line(&mut ics, &format!("DTSTART;VALUE=DATE:{}", day.format("%Y%m%d")));
+            if let Some(next) = day.succ_opt() {
+                push_ics_line(&mut ics, &format!("DTEND;VALUE=DATE:{}", next.format("%Y%m%d")));
+            }
+        } else {
+            if let Some(start) = utc(due_date) {
+                push_ics_line(&mut ics, &format!("DTSTART:{}", start));
+            }
+            if let Some(end) = estimated_minutes.filter(|minutes| *minutes > 0).and_then(|minutes| utc(due_date + minutes * 60_000)) {
+                push_ics_line(&mut ics, &format!("DTEND:{}", end));
+            }
+        }
+        let summary = if completed { format!("✓ {}", title) } else { title };
+        push_ics_line(&mut ics, &format!("SUMMARY:{}", ics_text(&summary)));
+        if let Some(content) = content.filter(|content| !content.trim().is_empty()) {
+            push_ics_line(&mut ics, &format!("DESCRIPTION:{}", ics_text(&content)));
+        }
+        push_ics_line(&mut ics, "END:VEVENT");
+    }
+    push_ics_line(&mut ics, "END:VCALENDAR");
+    Ok(ics)
+}
+
+/// Columns of `export_csv`, in order
+const CSV_COLUMNS: &[&str] = &[
+    "id",
+    "title",
+    "content",
+    "list_name",
+    "completed",
+    "complete_percentage",
+    "due_date",
+    "priority",
+    "tags",
+    "completed_at",
+    "created_at",
+    "updated_at",
+];
+
+/// Which timestamp a `TaskFilter` date range applies to
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DateField {
+    #[default]
+    Due,
+    Completed,
+    Created,
+    Updated,
+}
+
+impl DateField {
+    fn column(self) -> &'static str {
+        match self {
+            DateField::Due => "due_date",
+            DateField::Completed => "completed_at",
+            DateField::Created => "created_at",
+            DateField::Updated => "updated_at",
+        }
+    }
+}
+
+/// Which tasks to export; every field is optional and an empty filter matches all live tasks
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaskFilter {
+    pub list_id: Option<String>,
+    pub completed: Option<bool>,
+    /// Range on `date_field`, epoch millis, `since` inclusive and `until` exclusive
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub date_field: DateField,
+}
+
+/// Epoch millis as a local RFC 3339 timestamp, or an empty cell
+fn iso(millis: Option<i64>) -> String {
+    millis
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .map(|date_time| {
+            date_time
+                .with_timezone(&chrono::Local)
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+        })
+        .unwrap_or_default()
+}
+
+/// Command to export the tasks matching `filters` as CSV, one row per task with a header row.
+/// Timestamps are local ISO 8601 and tags are joined with commas inside their cell; the `csv`
+/// crate quotes any field containing commas, quotes or newlines
+#[tauri::command]
+pub async fn export_csv(app_handle: AppHandle, filters: TaskFilter) -> Result<String, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let date_column = filters.date_field.column();
+    let rows = sqlx::query_as::<_, TaskCsvRow>(&format!(
+        r#"SELECT id, title, content, list_name, completed, complete_percentage, due_date, priority, tags, completed_at, created_at, updated_at
+           FROM tasks
+           WHERE deleted_at IS NULL
+             AND (?1 IS NULL OR list_id = ?1)
+             AND (?2 IS NULL OR completed = ?2)
+             AND (?3 IS NULL OR {column} >= ?3)
+             AND (?4 IS NULL OR {column} < ?4)
+           ORDER BY list_name, "order""#,
+        column = date_column
+    ))
+    .bind(&filters.list_id)
+    .bind(filters.completed)
+    .bind(filters.since)
+    .bind(filters.until)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read tasks: {}", e)))?;
+
+    let csv = tasks_csv(&rows)?;
+    log::info!("[Export] Exported {} tasks as CSV", rows.len());
+    Ok(csv)
+}
+
+/// Renders rows as CSV under the `CSV_COLUMNS` header row
+fn tasks_csv(rows: &[TaskCsvRow]) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(CSV_COLUMNS)
+        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    for row in rows {
+        let tags: Vec<String> = row.8.as_deref().and_then(|tags| serde_json::from_str(tags).ok()).unwrap_or_default();
+        writer
+            .write_record([
+                row.0.clone(),
+                row.1.clone(),
+                row.2.clone().unwrap_or_default(),
+                row.3.clone(),
+                row.4.to_string(),
+                row.5.map(|percentage| percentage.to_string()).unwrap_or_default(),
+                iso(row.6),
+                row.7.map(|priority| priority.to_string()).unwrap_or_default(),
+                tags.join(", "),
+                iso(row.9),
+                iso(Some(row.10)),
+                iso(Some(row.11)),
+            ])
+            .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    }
+    let bytes = writer.into_inner().map_err(|e| format!("Failed to write CSV: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("Failed to write CSV: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(id: &str, title: &str, content: Option<&str>, tags: &str) -> TaskCsvRow {
+        let content = content.map(str::to_string);
+        let tags = Some(tags.to_string());
+        (id.to_string(), title.to_string(), content, "Inbox".to_string(), false, None, None, Some(1), tags, None, 0, 0)
+    }
+
+    #[test]
+    fn csv_round_trips_awkward_text() {
+        let rows = vec![
+            row(
+                "a",
+                "Milk, eggs, \"good\" bread",
+                Some("line one\nline two, \"quoted\"\r\nthree"),
+                r#"["home","errands"]"#,
+            ),
+            row("b", "\"Just quotes\"", None, "[]"),
+            row("c", "Trailing newline\n", Some(",,,"), r#"["a,b"]"#),
+        ];
+        let csv = tasks_csv(&rows).unwrap();
+
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        assert_eq!(reader.headers().unwrap().iter().collect::<Vec<_>>(), CSV_COLUMNS);
+        let records: Vec<csv::StringRecord> = reader.records().collect::<Result<_, _>>().unwrap();
+        assert_eq!(records.len(), rows.len());
+        for (record, row) in records.iter().zip(&rows) {
+            assert_eq!(&record[0], row.0);
+            assert_eq!(&record[1], row.1);
+            assert_eq!(&record[2], row.2.as_deref().unwrap_or_default());
+            assert_eq!(&record[3], row.3);
+        }
+        assert_eq!(&records[0][8], "home, errands");
+        assert_eq!(&records[2][8], "a,b");
+    }
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+/// Time since the last keyboard or mouse input, or `None` where it can't be read
+#[cfg(target_os = "macos")]
+pub fn system_idle() -> Option<Duration> {
+    // The HID system reports its idle time in nanoseconds
+    let output = std::process::Command::new("ioreg").args(["-c", "IOHIDSystem", "-d", "4"]).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.lines().find(|line| line.contains("\"HIDIdleTime\""))?;
+    let nanos = line.rsplit('=').next()?.trim().parse::<u64>().ok()?;
+    Some(Duration::from_nanos(nanos))
+}
+
+/// Time since the last keyboard or mouse input, or `None` where it can't be read. X11
+/// sessions answer through `xprintidle`, GNOME ones (Wayland too) through Mutter
+#[cfg(target_os = "linux")]
+pub fn system_idle() -> Option<Duration> {
+    let run = |program: &str, args: &[&str]| {
+        let output = std::process::Command::new(program).args(args).output().ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    if let Some(millis) = run("xprintidle", &[]).and_then(|stdout| stdout.trim().parse::<u64>().ok()) {
+        return Some(Duration::from_millis(millis));
+    }
+    // Replies `(uint64 12345,)`
+    let stdout = run(
+        "gdbus",
+        &[
+            "call",
+            "--session",
+            "--dest",
+            "org.gnome.Mutter.IdleMonitor",
+            "--object-path",
+            "/org/gnome/Mutter/IdleMonitor/Core",
+            "--method",
+            "org.gnome.Mutter.IdleMonitor.GetIdletime",
+        ],
+    )?;
+    let millis = stdout.trim().trim_start_matches("(uint64").trim_end_matches(",)").trim().parse::<u64>().ok()?;
+    Some(Duration::from_millis(millis))
+}
+
+/// Time since the last keyboard or mouse input
+#[cfg(target_os = "windows")]
+pub fn system_idle() -> Option<Duration> {
+    use windows_sys::Win32::System::SystemInformation::GetTickCount;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO { cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32, dwTime: 0 };
+    // SAFETY: `info` is a LASTINPUTINFO with `cbSize` set, as the call requires
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return None;
+    }
+    // SAFETY: no arguments; both counters wrap at the same point, so the difference holds
+    let now = unsafe { GetTickCount() };
+    Some(Duration::from_millis(u64::from(now.wrapping_sub(info.dwTime))))
+}
+
+/// Mobile systems handle notifications while the user is away themselves
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+pub fn system_idle() -> Option<Duration> {
+    None
+}
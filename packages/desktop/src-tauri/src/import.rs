@@ -0,0 +1,770 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::sqlite::SqliteArguments;
+use sqlx::Sqlite;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+use crate::db;
+use crate::error::CmdError;
+use crate::export::{ExportDocument, EXPORT_FORMAT, EXPORT_SCHEMA_VERSION, EXPORT_TABLES};
+use crate::priority::Priority;
+use crate::subtasks::{MAX_DEPTH, ORDER_STEP};
+
+mod ticktick;
+mod todoist;
+
+/// Rows that hang off tasks without being part of an export; a replace import clears them too
+const DEPENDENT_TABLES: &[&str] = &["reminders", "task_dependencies", "time_entries", "attachments"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Wipe the database, then load the export
+    Replace,
+    /// Upsert by id; on conflict the row with the newer `updated_at` wins
+    Merge,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCounts {
+    pub inserted: u64,
+    pub updated: u64,
+    pub skipped: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub inserted: u64,
+    pub updated: u64,
+    pub skipped: u64,
+    pub tables: BTreeMap<String, ImportCounts>,
+}
+
+/// What an import from another app added, or would add on a dry run
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppImportSummary {
+    /// Lists created; a project whose name matches an existing list goes into that list
+    pub lists: u64,
+    pub tasks: u64,
+    pub subtasks: u64,
+    /// Fields that had a value but nothing to map onto, with how many rows had one
+    pub unmapped: BTreeMap<String, usize>,
+    /// Nothing was written; the counts are what the import would have added
+    pub dry_run: bool,
+}
+
+/// A task read from another app's backup, with its sub-items nested under it
+#[derive(Debug, Clone, Default)]
+pub struct Item {
+    pub title: String,
+    pub content: Option<String>,
+    pub priority: Priority,
+    pub due_date: Option<i64>,
+    pub completed: bool,
+    pub completed_at: Option<i64>,
+    pub labels: Vec<String>,
+    pub children: Vec<Item>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Project {
+    pub name: String,
+    pub items: Vec<Item>,
+}
+
+/// Another app's backup, parsed into lists of tasks
+#[derive(Debug, Clone, Default)]
+pub struct Backup {
+    pub projects: Vec<Project>,
+    /// Fields with a value that has nowhere to go, and how many rows had one
+    pub unmapped: BTreeMap<String, usize>,
+}
+
+fn count_unmapped(unmapped: &mut BTreeMap<String, usize>, field: impl Into<String>) {
+    *unmapped.entry(field.into()).or_default() += 1;
+}
+
+type Row = serde_json::Map<String, Value>;
+
+/// Brings an export written by an older layout up to `EXPORT_SCHEMA_VERSION`. Columns a
+/// newer database added since are simply missing and take their defaults on insert
+fn upgrade(document: ExportDocument) -> Result<ExportDocument, String> {
+    if document.format != EXPORT_FORMAT {
+        return Err("The file is not a Tada export".to_string());
+    }
+    match document.schema_version {
+        0 => Err("The export has no schema version".to_string()),
+        EXPORT_SCHEMA_VERSION => Ok(document),
+        version if version > EXPORT_SCHEMA_VERSION => Err(format!(
+            "The export comes from a newer version of Tada (format {}, this version reads up to {})",
+            version, EXPORT_SCHEMA_VERSION
+        )),
+        version => Err(format!("Unsupported export format {}", version)),
+    }
+}
+
+/// Column names of `table` and which of them make up its primary key
+async fn table_columns(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    table: &str,
+) -> Result<(Vec<String>, Vec<String>), String> {
+    let columns = sqlx::query_as::<_, (i64, String, String, i64, Option<String>, i64)>(&format!(
+        "PRAGMA table_info({})",
+        table
+    ))
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| format!("Failed to read columns of {}: {}", table, e))?;
+    let mut key: Vec<(i64, String)> =
+        columns.iter().filter(|column| column.5 > 0).map(|column| (column.5, column.1.clone())).collect();
+    key.sort();
+    Ok((columns.into_iter().map(|column| column.1).collect(), key.into_iter().map(|(_, name)| name).collect()))
+}
+
+fn bind_value<'q>(
+    query: sqlx::query::Query<'q, Sqlite, SqliteArguments<'q>>,
+    value: &Value,
+) -> sqlx::query::Query<'q, Sqlite, SqliteArguments<'q>> {
+    match value {
+        Value::Null => query.bind(None::<String>),
+        Value::Bool(flag) => query.bind(i64::from(*flag)),
+        Value::Number(number) => match number.as_i64() {
+            Some(integer) => query.bind(integer),
+            None => query.bind(number.as_f64()),
+        },
+        Value::String(text) => query.bind(text.clone()),
+        // Tags and the like arrive as JSON text, but accept them already parsed too
+        other => query.bind(other.to_string()),
+    }
+}
+
+fn quoted(columns: &[&String]) -> String {
+    columns.iter().map(|column| format!("\"{}\"", column)).collect::<Vec<_>>().join(", ")
+}
+
+/// Inserts one row. Task tags are written by the tasks insert trigger from `tasks.tags`, so an
+/// exported link the trigger already made is skipped rather than failing the import
+async fn insert_row(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    table: &str,
+    columns: &[&String],
+    row: &Row,
+) -> Result<Outcome, String> {
+    let sql = format!(
+        "INSERT {}INTO {} ({}) VALUES ({})",
+        if table == "task_tags" { "OR IGNORE " } else { "" },
+        table,
+        quoted(columns),
+        vec!["?"; columns.len()].join(", ")
+    );
+    let query = columns
+        .iter()
+        .fold(sqlx::query(&sql), |query, column| bind_value(query, &row[column.as_str()]));
+    query
+        .execute(&mut **tx)
+        .await
+        .map(|done| if done.rows_affected() > 0 { Outcome::Inserted } else { Outcome::Skipped })
+        .map_err(|e| format!("Failed to import into {}: {}", table, e))
+}
+
+/// Merges one row: inserted when new, overwritten when the import is newer, skipped otherwise.
+/// Tables without `updated_at` never overwrite
+async fn merge_row(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    table: &str,
+    columns: &[&String],
+    key: &[String],
+    row: &Row,
+) -> Result<Outcome, String> {
+    let has_key = !key.is_empty() && key.iter().all(|column| row.get(column).is_some_and(|value| !value.is_null()));
+    if !has_key {
+        return Ok(Outcome::Skipped);
+    }
+    let condition = key.iter().map(|column| format!("\"{}\" = ?", column)).collect::<Vec<_>>().join(" AND ");
+    let tracks_updates = columns.iter().any(|column| column.as_str() == "updated_at");
+    let select = format!(
+        "SELECT {} FROM {} WHERE {}",
+        if tracks_updates { "updated_at" } else { "NULL" },
+        table,
+        condition
+    );
+    let existing = key
+        .iter()
+        .fold(sqlx::query_scalar::<_, Option<i64>>(&select), |query, column| match &row[column.as_str()] {
+            Value::Number(number) if number.is_i64() => query.bind(number.as_i64()),
+            value => query.bind(value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string())),
+        })
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", table, e))?;
+
+    let Some(existing_updated_at) = existing else {
+        return insert_row(tx, table, columns, row).await;
+    };
+    let imported_updated_at = row.get("updated_at").and_then(Value::as_i64);
+    let newer = match (imported_updated_at, existing_updated_at) {
+        (Some(imported), Some(existing)) => imported > existing,
+        (Some(_), None) => tracks_updates,
+        _ => false,
+    };
+    if !newer {
+        return Ok(Outcome::Skipped);
+    }
+    let assignments: Vec<&String> = columns.iter().copied().filter(|column| !key.contains(column)).collect();
+    let sql = format!(
+        "UPDATE {} SET {} WHERE {}",
+        table,
+        assignments.iter().map(|column| format!("\"{}\" = ?", column)).collect::<Vec<_>>().join(", "),
+        condition
+    );
+    let query = assignments
+        .iter()
+        .copied()
+        .chain(key.iter())
+        .fold(sqlx::query(&sql), |query, column| bind_value(query, &row[column.as_str()]));
+    query
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| format!("Failed to update {}: {}", table, e))?;
+    Ok(Outcome::Updated)
+}
+
+enum Outcome {
+    Inserted,
+    Updated,
+    Skipped,
+}
+
+/// In merge mode, an imported list whose id is taken by a differently named local list gets a
+/// fresh id, so both survive; returns old id -> new id for the tasks pointing at it
+async fn remap_lists(tx: &mut sqlx::Transaction<'_, Sqlite>, lists: &mut [Row]) -> Result<HashMap<String, String>, String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut remapped = HashMap::new();
+    for (index, list) in lists.iter_mut().enumerate() {
+        let (Some(id), Some(name)) = (
+            list.get("id").and_then(Value::as_str).map(str::to_string),
+            list.get("name").and_then(Value::as_str).map(str::to_string),
+        ) else {
+            continue;
+        };
+        let local_name = sqlx::query_scalar::<_, String>("SELECT name FROM lists WHERE id = ?")
+            .bind(&id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| format!("Failed to read lists: {}", e))?;
+        if local_name.is_some_and(|local_name| local_name != name) {
+            let new_id = format!("list-{}-import{}", now, index);
+            list.insert("id".to_string(), Value::String(new_id.clone()));
+            remapped.insert(id, new_id);
+        }
+    }
+    Ok(remapped)
+}
+
+/// In merge mode, an imported tag goes onto the local tag with the same name, and one whose id
+/// a differently named local tag holds gets a fresh id; returns old id -> new id for task_tags
+async fn remap_tags(tx: &mut sqlx::Transaction<'_, Sqlite>, tags: &mut [Row]) -> Result<HashMap<i64, i64>, String> {
+    let local: HashMap<String, i64> = sqlx::query_as::<_, (String, i64)>("SELECT name, id FROM tags")
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| format!("Failed to read tags: {}", e))?
+        .into_iter()
+        .collect();
+    let mut next_id = local.values().copied().chain(tags.iter().filter_map(|tag| tag.get("id")?.as_i64())).max();
+    let mut remapped = HashMap::new();
+    for tag in tags.iter_mut() {
+        let (Some(id), Some(name)) = (tag.get("id").and_then(Value::as_i64), tag.get("name").and_then(Value::as_str))
+        else {
+            continue;
+        };
+        let new_id = match local.get(name) {
+            Some(&local_id) => local_id,
+            None if local.values().any(|&local_id| local_id == id) => {
+                let fresh = next_id.unwrap_or(0) + 1;
+                next_id = Some(fresh);
+                fresh
+            }
+            None => continue,
+        };
+        if new_id != id {
+            tag.insert("id".to_string(), serde_json::json!(new_id));
+            remapped.insert(id, new_id);
+        }
+    }
+    Ok(remapped)
+}
+
+async fn run_import(
+    pool: &sqlx::SqlitePool,
+    mut document: ExportDocument,
+    mode: ImportMode,
+) -> Result<(ImportSummary, Vec<String>), String> {
+    let mut tx = pool.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let mut removed_files = Vec::new();
+    if mode == ImportMode::Replace {
+        removed_files = sqlx::query_scalar::<_, String>("SELECT file_path FROM attachments")
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to read attachments: {}", e))?;
+        for table in DEPENDENT_TABLES.iter().chain(EXPORT_TABLES.iter().rev()) {
+            sqlx::query(&format!("DELETE FROM {}", table))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to clear {}: {}", table, e))?;
+        }
+    }
+
+    let list_ids = match (mode, document.data.get_mut("lists")) {
+        (ImportMode::Merge, Some(lists)) => remap_lists(&mut tx, lists).await?,
+        _ => HashMap::new(),
+    };
+    let tag_ids = match (mode, document.data.get_mut("tags")) {
+        (ImportMode::Merge, Some(tags)) => remap_tags(&mut tx, tags).await?,
+        _ => HashMap::new(),
+    };
+    if let Some(links) = document.data.get_mut("task_tags") {
+        for link in links.iter_mut() {
+            let new_id = link.get("tag_id").and_then(Value::as_i64).and_then(|id| tag_ids.get(&id)).copied();
+            if let Some(new_id) = new_id {
+                link.insert("tag_id".to_string(), serde_json::json!(new_id));
+            }
+        }
+    }
+    let mut clamped = 0;
+    if let Some(tasks) = document.data.get_mut("tasks") {
+        for task in tasks.iter_mut() {
+            let new_id = task.get("list_id").and_then(Value::as_str).and_then(|id| list_ids.get(id)).cloned();
+            if let Some(new_id) = new_id {
+                task.insert("list_id".to_string(), Value::String(new_id));
+            }
+            // Files written by other tools can carry any number; they get the nearest priority
+            if let Some(value) = task.get("priority").filter(|value| !value.is_null()) {
+                let stored = value.as_i64().or_else(|| value.as_f64().map(|value| value.round() as i64));
+                let priority = Priority::nearest(stored);
+                if stored.is_none() || Priority::try_from(stored).is_err() {
+                    clamped += 1;
+                    task.insert("priority".to_string(), serde_json::json!(priority.to_db()));
+                }
+            }
+        }
+    }
+    if clamped > 0 {
+        log::warn!("[Import] Replaced {} out-of-range task priorities with the nearest valid one", clamped);
+    }
+
+    let mut summary = ImportSummary::default();
+    for table in EXPORT_TABLES {
+        let Some(rows) = document.data.get(*table) else {
+            continue;
+        };
+        let (table_columns, key) = table_columns(&mut tx, table).await?;
+        let mut counts = ImportCounts::default();
+        for row in rows {
+            // Only columns both sides know; anything else in the file is ignored
+            let columns: Vec<&String> = table_columns.iter().filter(|column| row.contains_key(column.as_str())).collect();
+            if columns.is_empty() {
+                counts.skipped += 1;
+                continue;
+            }
+            let outcome = match mode {
+                ImportMode::Replace => insert_row(&mut tx, table, &columns, row).await?,
+                ImportMode::Merge => merge_row(&mut tx, table, &columns, &key, row).await?,
+            };
+            match outcome {
+                Outcome::Inserted => counts.inserted += 1,
+                Outcome::Updated => counts.updated += 1,
+                Outcome::Skipped => counts.skipped += 1,
+            }
+        }
+        summary.inserted += counts.inserted;
+        summary.updated += counts.updated;
+        summary.skipped += counts.skipped;
+        summary.tables.insert(table.to_string(), counts);
+    }
+    tx.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    Ok((summary, removed_files))
+}
+
+/// Checks and loads a parsed export, then tells the frontend to reload
+async fn import_document(
+    app: &AppHandle,
+    document: ExportDocument,
+    mode: ImportMode,
+    path: &str,
+) -> Result<ImportSummary, String> {
+    let document = upgrade(document)?;
+    let pool = db::pool(app).await?;
+    let (summary, removed_files) = run_import(&pool, document, mode).await?;
+    crate::attachments::remove_files(removed_files);
+    log::info!(
+        "[Import] Imported {} ({:?}): {} inserted, {} updated, {} skipped",
+        path,
+        mode,
+        summary.inserted,
+        summary.updated,
+        summary.skipped
+    );
+    if let Err(e) = app.emit("data-imported", &summary) {
+        log::error!("[Import] Failed to emit data-imported: {}", e);
+    }
+    Ok(summary)
+}
+
+fn parse_document(json: &[u8]) -> Result<ExportDocument, String> {
+    serde_json::from_slice(json).map_err(|e| format!("The file is not a valid Tada export: {}", e))
+}
+
+/// Command to load a JSON export. The whole import runs in one transaction, so on any error
+/// the database is left as it was. Emits `data-imported` so the frontend reloads its caches
+#[tauri::command]
+pub async fn import_data(app_handle: AppHandle, path: String, mode: ImportMode) -> Result<ImportSummary, CmdError> {
+    let contents = std::fs::read(&path).map_err(|e| CmdError::Io(format!("Failed to read {}: {}", path, e)))?;
+    let document = parse_document(&contents).map_err(CmdError::Validation)?;
+    Ok(import_document(&app_handle, document, mode, &path).await?)
+}
+
+/// Reads the bytes written by `seal_export`. A wrong passphrase is reported as "Decryption
+/// failed" rather than as a malformed export
+pub fn open_export(sealed: &[u8], passphrase: &str) -> Result<ExportDocument, CmdError> {
+    let json = crate::export::decrypt(sealed, passphrase).map_err(CmdError::Validation)?;
+    parse_document(&json).map_err(CmdError::Validation)
+}
+
+/// Command to load an export written by `export_encrypted`, replacing everything unless `mode`
+/// says merge. A wrong passphrase fails with "Decryption failed" before anything is touched
+#[tauri::command]
+pub async fn import_encrypted(
+    app_handle: AppHandle,
+    path: String,
+    passphrase: String,
+    mode: Option<ImportMode>,
+) -> Result<ImportSummary, CmdError> {
+    let sealed = std::fs::read(&path).map_err(|e| CmdError::Io(format!("Failed to read {}: {}", path, e)))?;
+    let document = tauri::async_runtime::spawn_blocking(move || open_export(&sealed, &passphrase))
+        .await
+        .map_err(|e| format!("Decryption failed: {}", e))??;
+    Ok(import_document(&app_handle, document, mode.unwrap_or(ImportMode::Replace), &path).await?)
+}
+
+/// The id of the live list called `name`, creating it at the end of the sidebar if there is none
+async fn list_for_project(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    name: &str,
+    id: String,
+    now: i64,
+) -> Result<(String, bool), String> {
+    let existing = sqlx::query_scalar::<_, String>("SELECT id FROM lists WHERE name = ? AND deleted_at IS NULL")
+        .bind(name)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| format!("Failed to look up list '{}': {}", name, e))?;
+    if let Some(existing) = existing {
+        return Ok((existing, false));
+    }
+    sqlx::query(
+        r#"INSERT INTO lists (id, name, icon, "order", created_at, updated_at)
+           VALUES (?, ?, 'list', (SELECT COALESCE(MAX("order"), 0) + 1 FROM lists), ?, ?)"#,
+    )
+    .bind(&id)
+    .bind(name)
+    .bind(now)
+    .bind(now)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| format!("Failed to create list '{}': {}", name, e))?;
+    Ok((id, true))
+}
+
+/// Writes a parsed backup in one transaction: projects become lists, items tasks and sub-items
+/// subtasks, nested up to `MAX_DEPTH` with anything deeper kept at the last level. On a dry
+/// run the transaction is rolled back, so the counts are exact but nothing is kept
+async fn insert_backup(
+    pool: &sqlx::SqlitePool,
+    backup: Backup,
+    source: &str,
+    dry_run: bool,
+) -> Result<AppImportSummary, String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut summary = AppImportSummary { unmapped: backup.unmapped, dry_run, ..AppImportSummary::default() };
+
+    let mut tx = pool.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let last_order = sqlx::query_scalar::<_, Option<i64>>(
+        r#"SELECT CAST(MAX("order") AS INTEGER) FROM tasks WHERE deleted_at IS NULL"#,
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to read task order: {}", e))?
+    .unwrap_or(0);
+    let today = chrono::Local::now().date_naive();
+    let mut sequence: i64 = 0;
+    for (index, project) in backup.projects.iter().enumerate() {
+        let (list_id, created) =
+            list_for_project(&mut tx, &project.name, format!("list-{}-{}{}", now, source, index), now).await?;
+        if created {
+            summary.lists += 1;
+        }
+        for item in &project.items {
+            sequence += 1;
+            let task_id = format!("task-{}-{}{}", now, source, sequence);
+            let tags = serde_json::to_string(&item.labels).unwrap_or_else(|_| "[]".to_string());
+            sqlx::query(
+                r#"INSERT INTO tasks (id, title, completed, completed_at, due_date, list_id, list_name, content, "order", created_at, updated_at, tags, priority, group_category)
+                   VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+            )
+            .bind(&task_id)
+            .bind(&item.title)
+            .bind(item.completed)
+            .bind(item.completed_at)
+            .bind(item.due_date)
+            .bind(&list_id)
+            .bind(&project.name)
+            .bind(item.content.as_deref().unwrap_or_default())
+            .bind(last_order + sequence * ORDER_STEP)
+            .bind(now)
+            .bind(now)
+            .bind(&tags)
+            .bind(item.priority.to_db())
+            .bind(crate::tasks::group_category(item.completed, &project.name, item.due_date, today))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to import task '{}': {}", item.title, e))?;
+            summary.tasks += 1;
+
+            // (parent subtask, depth, children); subtask ids carry the sequence, so order follows the file
+            let mut pending: Vec<(Option<String>, usize, &[Item])> = vec![(None, 1, &item.children)];
+            while let Some((parent_subtask_id, depth, children)) = pending.pop() {
+                for child in children {
+                    sequence += 1;
+                    let subtask_id = format!("subtask-{}-{}{}", now, source, sequence);
+                    sqlx::query(
+                        r#"INSERT INTO subtasks (id, parent_id, parent_subtask_id, title, completed, completed_at, due_date, "order", created_at, updated_at)
+                           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                    )
+                    .bind(&subtask_id)
+                    .bind(&task_id)
+                    .bind(&parent_subtask_id)
+                    .bind(&child.title)
+                    .bind(child.completed)
+                    .bind(child.completed_at)
+                    .bind(child.due_date)
+                    .bind(sequence * ORDER_STEP)
+                    .bind(now)
+                    .bind(now)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Failed to import subtask '{}': {}", child.title, e))?;
+                    summary.subtasks += 1;
+                    if child.content.is_some() {
+                        count_unmapped(&mut summary.unmapped, "sub-item description");
+                    }
+                    if !child.children.is_empty() {
+                        let nested = if depth < MAX_DEPTH {
+                            (Some(subtask_id), depth + 1)
+                        } else {
+                            (parent_subtask_id.clone(), depth)
+                        };
+                        pending.push((nested.0, nested.1, &child.children));
+                    }
+                }
+            }
+        }
+    }
+    if dry_run {
+        tx.rollback().await.map_err(|e| format!("Failed to roll back transaction: {}", e))?;
+    } else {
+        tx.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    }
+    Ok(summary)
+}
+
+/// Logs an import from another app and, unless it was a dry run, tells the frontend to reload
+fn finish_app_import(app: &AppHandle, path: &str, source: &str, summary: &AppImportSummary) {
+    if summary.dry_run {
+        log::info!(
+            "[Import] Dry run of {} from {}: {} lists, {} tasks, {} subtasks",
+            path,
+            source,
+            summary.lists,
+            summary.tasks,
+            summary.subtasks
+        );
+        return;
+    }
+    log::info!(
+        "[Import] Imported {} from {}: {} lists, {} tasks, {} subtasks",
+        path,
+        source,
+        summary.lists,
+        summary.tasks,
+        summary.subtasks
+    );
+    if let Err(e) = app.emit("data-imported", summary) {
+        log::error!("[Import] Failed to emit data-imported: {}", e);
+    }
+}
+
+/// Command to import a Todoist backup. Fields with no counterpart are counted in the summary
+/// instead of failing
+#[tauri::command]
+pub async fn import_todoist(app_handle: AppHandle, path: String) -> Result<AppImportSummary, CmdError> {
+    let backup = todoist::read(Path::new(&path))?;
+    let pool = db::pool(&app_handle).await?;
+    let summary = insert_backup(&pool, backup, "todoist", false).await?;
+    finish_app_import(&app_handle, &path, "Todoist", &summary);
+    Ok(summary)
+}
+
+/// Command to import a TickTick backup CSV. With `dry_run` nothing is written and the summary
+/// shows what would be imported, to check the mapping first
+#[tauri::command]
+pub async fn import_ticktick(
+    app_handle: AppHandle,
+    path: String,
+    dry_run: Option<bool>,
+) -> Result<AppImportSummary, CmdError> {
+    let contents = std::fs::read_to_string(&path).map_err(|e| CmdError::Io(format!("Failed to read {}: {}", path, e)))?;
+    let backup = ticktick::parse(&contents)?;
+    let pool = db::pool(&app_handle).await?;
+    let summary = insert_backup(&pool, backup, "ticktick", dry_run.unwrap_or(false)).await?;
+    finish_app_import(&app_handle, &path, "TickTick", &summary);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::seal_export;
+
+    fn document() -> ExportDocument {
+        let task = serde_json::json!({ "id": "task-1", "title": "Pay rent", "tags": "[\"home\"]", "due_date": 1 });
+        let Value::Object(task) = task else { unreachable!() };
+        ExportDocument {
+            format: EXPORT_FORMAT.to_string(),
+            schema_version: EXPORT_SCHEMA_VERSION,
+            database_version: 12,
+            exported_at: 1_700_000_000_000,
+            counts: BTreeMap::from([("tasks".to_string(), 1)]),
+            data: BTreeMap::from([("tasks".to_string(), vec![task])]),
+        }
+    }
+
+    async fn add_task(pool: &sqlx::SqlitePool, id: &str, tags: &str) {
+        sqlx::query(
+            r#"INSERT INTO tasks (id, title, list_id, list_name, "order", created_at, updated_at, tags)
+               VALUES (?, 'Task', 'inbox-default', 'Inbox', 0, 0, 0, ?)"#,
+        )
+        .bind(id)
+        .bind(tags)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn link(task: &str, tag: &str) -> (String, String) {
+        (task.to_string(), tag.to_string())
+    }
+
+    async fn links(pool: &sqlx::SqlitePool) -> Vec<(String, String)> {
+        sqlx::query_as(
+            "SELECT tt.task_id, g.name FROM task_tags tt JOIN tags g ON g.id = tt.tag_id ORDER BY tt.task_id, g.name",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn replace_import_keeps_task_tags() {
+        tauri::async_runtime::block_on(async {
+            let pool = db::test_pool().await;
+            add_task(&pool, "task-1", r#"["home","errands"]"#).await;
+            let document = crate::export::build_export(&pool).await.unwrap();
+
+            let (summary, _) = run_import(&pool, document, ImportMode::Replace).await.unwrap();
+            assert_eq!(summary.tables["task_tags"].skipped, 2);
+            assert_eq!(links(&pool).await, [link("task-1", "errands"), link("task-1", "home")]);
+        });
+    }
+
+    #[test]
+    fn merge_import_matches_tags_by_name() {
+        tauri::async_runtime::block_on(async {
+            let local = db::test_pool().await;
+            add_task(&local, "local-1", r#"["home"]"#).await;
+            let other = db::test_pool().await;
+            add_task(&other, "other-1", r#"["work"]"#).await;
+            add_task(&other, "other-2", r#"["home","work"]"#).await;
+            let document = crate::export::build_export(&other).await.unwrap();
+
+            run_import(&local, document, ImportMode::Merge).await.unwrap();
+            let tags: Vec<String> =
+                sqlx::query_scalar("SELECT name FROM tags ORDER BY name").fetch_all(&local).await.unwrap();
+            assert_eq!(tags, ["home", "work"]);
+            let expected =
+                [link("local-1", "home"), link("other-1", "work"), link("other-2", "home"), link("other-2", "work")];
+            assert_eq!(links(&local).await, expected);
+        });
+    }
+
+    #[test]
+    fn app_import_groups_tasks_by_due_date() {
+        tauri::async_runtime::block_on(async {
+            let pool = db::test_pool().await;
+            let day = 24 * 60 * 60 * 1000;
+            let now = chrono::Utc::now().timestamp_millis();
+            let item = |title: &str, due_date: Option<i64>, completed: bool| Item {
+                title: title.to_string(),
+                due_date,
+                completed,
+                ..Item::default()
+            };
+            let items = vec![
+                item("late", Some(now - 2 * day), false),
+                item("someday", None, false),
+                item("far", Some(now + 30 * day), false),
+                item("done", Some(now - 2 * day), true),
+            ];
+            let backup = Backup { projects: vec![Project { name: "Inbox".to_string(), items }], ..Backup::default() };
+            insert_backup(&pool, backup, "test", false).await.unwrap();
+
+            let groups: Vec<(String, String)> = sqlx::query_as("SELECT title, group_category FROM tasks ORDER BY title")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+            let group = |title: &str, category: &str| (title.to_string(), category.to_string());
+            let expected =
+                [group("done", "nodate"), group("far", "later"), group("late", "overdue"), group("someday", "nodate")];
+            assert_eq!(groups, expected);
+        });
+    }
+
+    #[test]
+    fn encrypted_export_round_trips() {
+        let original = document();
+        let sealed = seal_export(&original, "correct horse").unwrap();
+        assert!(!sealed.windows(8).any(|w| w == b"Pay rent"));
+        let opened = open_export(&sealed, "correct horse").unwrap();
+        assert_eq!(serde_json::to_value(&opened).unwrap(), serde_json::to_value(&original).unwrap());
+    }
+
+    #[test]
+    fn wrong_passphrase_fails_decryption() {
+        let sealed = seal_export(&document(), "correct horse").unwrap();
+        let error = open_export(&sealed, "battery staple").unwrap_err();
+        assert!(error.to_string().starts_with("Decryption failed"), "{}", error);
+    }
+
+    #[test]
+    fn damaged_file_fails_decryption() {
+        let mut sealed = seal_export(&document(), "correct horse").unwrap();
+        *sealed.last_mut().unwrap() ^= 1;
+        let error = open_export(&sealed, "correct horse").unwrap_err();
+        assert!(error.to_string().starts_with("Decryption failed"), "{}", error);
+        assert!(open_export(b"{\"format\":\"tada-export\"}", "correct horse").is_err());
+    }
+}
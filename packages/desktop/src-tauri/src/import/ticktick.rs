@@ -0,0 +1,172 @@
+//! Reads TickTick (滴答清单) backups: the CSV from Settings > Backup & Import, which starts with
+//! a few lines of metadata before the header row
+
+use chrono::DateTime;
+use std::collections::{BTreeMap, HashMap};
+
+use super::{count_unmapped, Backup, Item, Project};
+use crate::priority::Priority;
+
+/// Checklist lines in `Content`: unchecked and checked items
+const UNCHECKED_MARK: char = '▫';
+const CHECKED_MARK: char = '▪';
+
+/// Columns with nothing to map onto; reported when they have a value
+const UNMAPPED_COLUMNS: &[&str] = &["Folder Name", "Start Date", "Reminder", "Repeat", "Column Name"];
+
+/// TickTick's 5 (high), 3 (medium) and 1 (low); 0 is no priority
+fn priority_from_ticktick(priority: &str) -> Priority {
+    match priority.trim() {
+        "5" => Priority::High,
+        "3" => Priority::Medium,
+        "1" => Priority::Low,
+        _ => Priority::None,
+    }
+}
+
+/// `2024-01-05T16:00:00+0000` as epoch millis. All-day dates are stored as the local midnight
+/// they stand for, so no adjustment is needed
+fn parse_date(value: &str) -> Option<i64> {
+    let value = value.trim();
+    DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%z")
+        .or_else(|_| DateTime::parse_from_rfc3339(value))
+        .ok()
+        .map(|date_time| date_time.timestamp_millis())
+}
+
+/// Splits checklist items out of a task's `Content`; whatever else is there stays as its
+/// Markdown description
+fn split_checklist(content: &str, is_checklist: bool) -> (Option<String>, Vec<Item>) {
+    if !is_checklist {
+        return (Some(content.to_string()).filter(|content| !content.trim().is_empty()), Vec::new());
+    }
+    let mut description = Vec::new();
+    let mut items = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let completed = match trimmed.chars().next() {
+            Some(UNCHECKED_MARK) => false,
+            Some(CHECKED_MARK) => true,
+            _ => {
+                description.push(line);
+                continue;
+            }
+        };
+        let title = trimmed.trim_start_matches([UNCHECKED_MARK, CHECKED_MARK]).trim();
+        if !title.is_empty() {
+            items.push(Item { title: title.to_string(), completed, ..Item::default() });
+        }
+    }
+    let description = description.join("\n");
+    (Some(description).filter(|description| !description.trim().is_empty()), items)
+}
+
+/// Moves row `index` out of `slots` with its child tasks after its checklist items. Each row
+/// is taken once, so a parent cycle in a corrupted file can't loop
+fn build(index: usize, slots: &mut [Option<Item>], children_of: &HashMap<usize, Vec<usize>>) -> Option<Item> {
+    let mut item = slots[index].take()?;
+    for child in children_of.get(&index).into_iter().flatten() {
+        if let Some(child) = build(*child, slots, children_of) {
+            item.children.push(child);
+        }
+    }
+    Some(item)
+}
+
+/// Parses a backup CSV. Lists keep the order they first appear in; tasks with a `parentId`
+/// become subtasks of that task, after its checklist items
+pub fn parse(contents: &str) -> Result<Backup, String> {
+    // The metadata above the header has quoted fields that span lines, so find the header itself
+    let start = contents
+        .find("\"Folder Name\"")
+        .or_else(|| contents.find("Folder Name,"))
+        .ok_or("The file is not a TickTick backup: it has no task header")?;
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(&contents.as_bytes()[start..]);
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Failed to read TickTick backup: {}", e))?
+        .iter()
+        .map(|header| header.trim().to_string())
+        .collect();
+
+    let mut unmapped = BTreeMap::new();
+    // (list, task id, parent id, item) in file order, nested once every row is read
+    let mut rows: Vec<(String, String, String, Item)> = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Failed to read TickTick backup: {}", e))?;
+        let field = |name: &str| {
+            headers
+                .iter()
+                .position(|header| header == name)
+                .and_then(|index| record.get(index))
+                .unwrap_or_default()
+        };
+        let title = field("Title").trim();
+        if title.is_empty() {
+            continue;
+        }
+        for column in UNMAPPED_COLUMNS {
+            if !field(column).trim().is_empty() {
+                count_unmapped(&mut unmapped, *column);
+            }
+        }
+        let (content, checklist) = split_checklist(field("Content"), field("Is Check list").trim() == "Y");
+        // Status 1 is completed and 2 archived, which is only reachable from completed
+        let completed = matches!(field("Status").trim(), "1" | "2");
+        let completed_at = parse_date(field("Completed Time"))
+            .or_else(|| completed.then(|| parse_date(field("Created Time"))).flatten());
+        let item = Item {
+            title: title.to_string(),
+            content,
+            priority: priority_from_ticktick(field("Priority")),
+            due_date: parse_date(field("Due Date")),
+            completed,
+            completed_at,
+            labels: field("Tags")
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect(),
+            children: checklist,
+        };
+        let list = Some(field("List Name").trim()).filter(|list| !list.is_empty()).unwrap_or("Inbox");
+        rows.push((list.to_string(), field("taskId").trim().to_string(), field("parentId").trim().to_string(), item));
+    }
+
+    let known: HashMap<&str, usize> = rows
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, id, _, _))| !id.is_empty())
+        .map(|(index, (_, id, _, _))| (id.as_str(), index))
+        .collect();
+    let parents: Vec<Option<usize>> = rows
+        .iter()
+        .enumerate()
+        .map(|(index, (_, _, parent_id, _))| known.get(parent_id.as_str()).copied().filter(|parent| *parent != index))
+        .collect();
+    let mut children_of: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (index, parent) in parents.iter().enumerate() {
+        if let Some(parent) = parent {
+            children_of.entry(*parent).or_default().push(index);
+        }
+    }
+    let mut slots: Vec<Option<Item>> = rows.iter().map(|(_, _, _, item)| Some(item.clone())).collect();
+
+    let mut backup = Backup { projects: Vec::new(), unmapped };
+    let mut project_index: HashMap<String, usize> = HashMap::new();
+    for (index, (list, _, _, _)) in rows.iter().enumerate() {
+        if parents[index].is_some() {
+            continue;
+        }
+        let Some(item) = build(index, &mut slots, &children_of) else {
+            continue;
+        };
+        let project = *project_index.entry(list.clone()).or_insert_with(|| {
+            backup.projects.push(Project { name: list.clone(), items: Vec::new() });
+            backup.projects.len() - 1
+        });
+        backup.projects[project].items.push(item);
+    }
+    Ok(backup)
+}
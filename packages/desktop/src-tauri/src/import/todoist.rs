@@ -0,0 +1,431 @@
+//! Reads Todoist backups: the JSON of the sync API (`projects`, `items`, `notes`) or the
+//! per-project CSV files of a backup zip. Parsing only; `import_todoist` does the inserting
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use super::{count_unmapped, Backup, Item, Project};
+use crate::priority::Priority;
+
+/// Item keys in the JSON that are bookkeeping rather than data worth reporting
+const IGNORED_KEYS: &[&str] = &[
+    "id",
+    "v2_id",
+    "project_id",
+    "v2_project_id",
+    "parent_id",
+    "v2_parent_id",
+    "child_order",
+    "day_order",
+    "user_id",
+    "added_by_uid",
+    "assigned_by_uid",
+    "sync_id",
+    "is_deleted",
+    "collapsed",
+    "added_at",
+    "updated_at",
+];
+
+/// CSV columns that map to nothing here
+const UNMAPPED_COLUMNS: &[&str] =
+    &["AUTHOR", "RESPONSIBLE", "DATE_LANG", "TIMEZONE", "DURATION", "DURATION_UNIT", "DEADLINE", "DEADLINE_LANG"];
+
+/// Todoist's p1 (urgent) to p4 (none) onto high to low, with p4 left unset
+fn priority_from_label(p: i64) -> Priority {
+    match p {
+        1 => Priority::High,
+        2 => Priority::Medium,
+        3 => Priority::Low,
+        _ => Priority::None,
+    }
+}
+
+/// A Todoist date as epoch millis: an all-day `2024-03-01` is local midnight, a floating time
+/// is local, and one with an offset is taken as given
+fn parse_date(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0).and_then(local_millis);
+    }
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+        return Some(date_time.timestamp_millis());
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+        .ok()
+        .and_then(local_millis)
+}
+
+fn local_millis(date_time: NaiveDateTime) -> Option<i64> {
+    date_time
+        .and_local_timezone(chrono::Local)
+        .earliest()
+        .map(|date_time| date_time.timestamp_millis())
+}
+
+/// Ids are numbers in older exports and strings in newer ones
+fn id_of(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+fn has_value(value: &Value) -> bool {
+    match value {
+        Value::Null | Value::Bool(false) => false,
+        Value::String(text) => !text.is_empty(),
+        Value::Array(values) => !values.is_empty(),
+        Value::Object(fields) => !fields.is_empty(),
+        _ => true,
+    }
+}
+
+/// Moves item `index` out of `slots` with its children nested below it. Each item is taken
+/// once, so a parent cycle in a corrupted file can't loop
+fn build(
+    index: usize,
+    slots: &mut [Option<Item>],
+    ids: &[Option<String>],
+    children_of: &HashMap<String, Vec<usize>>,
+) -> Option<Item> {
+    let mut item = slots[index].take()?;
+    let children = ids[index].as_ref().and_then(|id| children_of.get(id)).cloned().unwrap_or_default();
+    item.children = children.into_iter().filter_map(|child| build(child, slots, ids, children_of)).collect();
+    Some(item)
+}
+
+/// Parses a sync API backup
+pub fn parse_json(contents: &str) -> Result<Backup, String> {
+    let root: Value = serde_json::from_str(contents).map_err(|e| format!("Failed to parse Todoist backup: {}", e))?;
+    let projects = root
+        .get("projects")
+        .and_then(Value::as_array)
+        .ok_or("The file is not a Todoist backup: it has no projects")?;
+    let items = root.get("items").and_then(Value::as_array).cloned().unwrap_or_default();
+    let mut unmapped = BTreeMap::new();
+
+    let mut notes: HashMap<String, Vec<String>> = HashMap::new();
+    for note in root.get("notes").and_then(Value::as_array).into_iter().flatten() {
+        if let (Some(item_id), Some(content)) = (id_of(note.get("item_id")), note.get("content").and_then(Value::as_str)) {
+            notes.entry(item_id).or_default().push(content.to_string());
+        }
+    }
+    if root.get("sections").and_then(Value::as_array).is_some_and(|sections| !sections.is_empty()) {
+        count_unmapped(&mut unmapped, "sections");
+    }
+
+    // (project, parent, child_order, id, item), nested below once every item is read
+    let mut parsed = Vec::new();
+    for item in &items {
+        let Some(fields) = item.as_object() else {
+            continue;
+        };
+        if fields.get("is_deleted").and_then(Value::as_bool).unwrap_or(false) {
+            continue;
+        }
+        let mut parsed_item = Item {
+            title: fields.get("content").and_then(Value::as_str).unwrap_or_default().to_string(),
+            ..Item::default()
+        };
+        for (key, value) in fields {
+            match key.as_str() {
+                "content" => {}
+                "description" => {
+                    parsed_item.content = value.as_str().filter(|text| !text.is_empty()).map(str::to_string);
+                }
+                // The API counts the other way: 4 is p1
+                "priority" => parsed_item.priority = value.as_i64().map(|p| priority_from_label(5 - p)).unwrap_or_default(),
+                "due" => {
+                    if let Some(due) = value.as_object() {
+                        parsed_item.due_date = due.get("date").and_then(Value::as_str).and_then(parse_date);
+                        if due.get("is_recurring").and_then(Value::as_bool).unwrap_or(false) {
+                            count_unmapped(&mut unmapped, "due.is_recurring");
+                        }
+                    }
+                }
+                "checked" => parsed_item.completed = value.as_bool().unwrap_or(false) || value.as_i64() == Some(1),
+                "completed_at" | "date_completed" => {
+                    parsed_item.completed_at = parsed_item.completed_at.or(value.as_str().and_then(parse_date));
+                }
+                "labels" => {
+                    parsed_item.labels = value
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|label| label.as_str().map(str::to_string))
+                        .collect();
+                }
+                key if IGNORED_KEYS.contains(&key) => {}
+                key => {
+                    if has_value(value) {
+                        count_unmapped(&mut unmapped, format!("items.{}", key));
+                    }
+                }
+            }
+        }
+        let id = id_of(fields.get("id"));
+        if let Some(comments) = id.as_ref().and_then(|id| notes.remove(id)) {
+            let mut content = parsed_item.content.take().into_iter().collect::<Vec<_>>();
+            content.extend(comments);
+            parsed_item.content = Some(content.join("\n\n"));
+        }
+        if parsed_item.completed && parsed_item.completed_at.is_none() {
+            parsed_item.completed_at = Some(chrono::Utc::now().timestamp_millis());
+        }
+        let order = fields.get("child_order").and_then(Value::as_i64).unwrap_or(0);
+        parsed.push((id_of(fields.get("project_id")), id_of(fields.get("parent_id")), order, id, parsed_item));
+    }
+    parsed.sort_by_key(|(_, _, order, _, _)| *order);
+
+    let known: HashSet<String> = parsed.iter().filter_map(|(_, _, _, id, _)| id.clone()).collect();
+    let mut children_of: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut roots = Vec::new();
+    for (index, (_, parent_id, _, _, _)) in parsed.iter().enumerate() {
+        match parent_id.as_ref().filter(|parent_id| known.contains(*parent_id)) {
+            Some(parent_id) => children_of.entry(parent_id.clone()).or_default().push(index),
+            None => roots.push(index),
+        }
+    }
+    let ids: Vec<Option<String>> = parsed.iter().map(|(_, _, _, id, _)| id.clone()).collect();
+    let project_ids: Vec<Option<String>> = parsed.iter().map(|(project_id, _, _, _, _)| project_id.clone()).collect();
+    let mut slots: Vec<Option<Item>> = parsed.into_iter().map(|(_, _, _, _, item)| Some(item)).collect();
+    let mut backup = Backup { projects: Vec::new(), unmapped };
+    let mut project_index: HashMap<String, usize> = HashMap::new();
+    for project in projects {
+        if project.get("is_deleted").and_then(Value::as_bool).unwrap_or(false) {
+            continue;
+        }
+        let name = project.get("name").and_then(Value::as_str).unwrap_or("Todoist").to_string();
+        if let Some(id) = id_of(project.get("id")) {
+            project_index.insert(id, backup.projects.len());
+        }
+        backup.projects.push(Project { name, items: Vec::new() });
+    }
+    for root in roots {
+        let Some(item) = build(root, &mut slots, &ids, &children_of) else {
+            continue;
+        };
+        let index = match project_ids[root].as_ref().and_then(|id| project_index.get(id).copied()) {
+            Some(index) => index,
+            None => {
+                count_unmapped(&mut backup.unmapped, "items.project_id");
+                continue;
+            }
+        };
+        backup.projects[index].items.push(item);
+    }
+    Ok(backup)
+}
+
+/// Parses one project's CSV, as found in a backup zip; `INDENT` nests sub-items and `note`
+/// rows become part of the task above them
+pub fn parse_csv(project_name: &str, contents: &str, unmapped: &mut BTreeMap<String, usize>) -> Result<Project, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(contents.trim_start_matches('\u{feff}').as_bytes());
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Failed to read {}: {}", project_name, e))?
+        .iter()
+        .map(|header| header.trim().to_ascii_uppercase())
+        .collect();
+    if !headers.iter().any(|header| header == "CONTENT") {
+        return Err(format!("{} is not a Todoist CSV export: it has no CONTENT column", project_name));
+    }
+
+    let mut flat: Vec<(usize, Item)> = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Failed to read {}: {}", project_name, e))?;
+        let field = |name: &str| {
+            headers
+                .iter()
+                .position(|header| header == name)
+                .and_then(|index| record.get(index))
+                .map(str::trim)
+                .unwrap_or_default()
+        };
+        match field("TYPE") {
+            "task" => {}
+            "note" => {
+                if let Some((_, item)) = flat.last_mut() {
+                    let note = field("CONTENT").to_string();
+                    item.content = Some(match item.content.take() {
+                        Some(content) => format!("{}\n\n{}", content, note),
+                        None => note,
+                    });
+                }
+                continue;
+            }
+            "" | "meta" => continue,
+            other => {
+                count_unmapped(unmapped, other);
+                continue;
+            }
+        }
+        for column in UNMAPPED_COLUMNS {
+            if !field(column).is_empty() {
+                count_unmapped(unmapped, *column);
+            }
+        }
+        let date = field("DATE");
+        let due_date = parse_date(date);
+        if due_date.is_none() && !date.is_empty() {
+            // Natural-language dates ("every mon") have no fixed value to carry over
+            count_unmapped(unmapped, "DATE");
+        }
+        let item = Item {
+            title: field("CONTENT").to_string(),
+            content: Some(field("DESCRIPTION").to_string()).filter(|text| !text.is_empty()),
+            priority: field("PRIORITY").parse().map(priority_from_label).unwrap_or_default(),
+            due_date,
+            ..Item::default()
+        };
+        flat.push((field("INDENT").parse().unwrap_or(1), item));
+    }
+    Ok(Project { name: project_name.to_string(), items: nest(flat) })
+}
+
+/// Builds the tree from `(indent, item)` rows in file order
+fn nest(flat: Vec<(usize, Item)>) -> Vec<Item> {
+    let mut roots = Vec::new();
+    let mut stack: Vec<(usize, Item)> = Vec::new();
+    for (indent, item) in flat {
+        close(&mut stack, &mut roots, indent);
+        stack.push((indent, item));
+    }
+    close(&mut stack, &mut roots, 0);
+    roots
+}
+
+/// Pops every open item at `indent` or deeper into its parent
+fn close(stack: &mut Vec<(usize, Item)>, roots: &mut Vec<Item>, indent: usize) {
+    while let Some((level, _)) = stack.last()
+        && *level >= indent
+    {
+        let Some((_, item)) = stack.pop() else {
+            break;
+        };
+        match stack.last_mut() {
+            Some((_, parent)) => parent.children.push(item),
+            None => roots.push(item),
+        }
+    }
+}
+
+/// Reads a backup from `path`: a `.json` sync backup, a single project `.csv`, or a folder of
+/// them (an unzipped backup), each CSV named after its project
+pub fn read(path: &Path) -> Result<Backup, String> {
+    let read = |path: &Path| std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e));
+    let project_name = |path: &Path| {
+        let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+        // Backups name the files "Project name [1234567].csv"
+        match stem.rsplit_once(" [") {
+            Some((name, id)) if id.ends_with(']') => name.to_string(),
+            _ => stem,
+        }
+    };
+    let is_csv = |path: &Path| path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+
+    if path.is_dir() {
+        let mut files: Vec<_> = std::fs::read_dir(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| is_csv(path))
+            .collect();
+        files.sort();
+        if files.is_empty() {
+            return Err(format!("No Todoist CSV files found in {}", path.display()));
+        }
+        let mut backup = Backup::default();
+        for file in files {
+            let project = parse_csv(&project_name(&file), &read(&file)?, &mut backup.unmapped)?;
+            backup.projects.push(project);
+        }
+        return Ok(backup);
+    }
+    if is_csv(path) {
+        let mut backup = Backup::default();
+        let project = parse_csv(&project_name(path), &read(path)?, &mut backup.unmapped)?;
+        backup.projects.push(project);
+        return Ok(backup);
+    }
+    parse_json(&read(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/todoist").join(name)
+    }
+
+    fn titles(items: &[Item]) -> Vec<&str> {
+        items.iter().map(|item| item.title.as_str()).collect()
+    }
+
+    #[test]
+    fn maps_p1_to_p4() {
+        assert_eq!(priority_from_label(1), Priority::High);
+        assert_eq!(priority_from_label(2), Priority::Medium);
+        assert_eq!(priority_from_label(3), Priority::Low);
+        assert_eq!(priority_from_label(4), Priority::None);
+    }
+
+    #[test]
+    fn parses_json_backup() {
+        let backup = read(&fixture("backup.json")).unwrap();
+        let names: Vec<&str> = backup.projects.iter().map(|project| project.name.as_str()).collect();
+        assert_eq!(names, ["Inbox", "Trip"]);
+
+        let inbox = &backup.projects[0];
+        assert_eq!(titles(&inbox.items), ["Buy milk"]);
+        assert_eq!(inbox.items[0].priority, Priority::None);
+        assert!(inbox.items[0].completed);
+        assert_eq!(inbox.items[0].completed_at, Some(1_706_781_600_000));
+
+        let plan = &backup.projects[1].items[0];
+        assert_eq!(plan.title, "Plan trip");
+        assert_eq!(plan.priority, Priority::High);
+        assert_eq!(plan.labels, ["travel"]);
+        assert_eq!(plan.content.as_deref(), Some("Two weeks in June\n\nPassport renewed"));
+        assert!(plan.due_date.is_some());
+        assert_eq!(titles(&plan.children), ["Book flights"]);
+        assert_eq!(plan.children[0].priority, Priority::Medium);
+        assert_eq!(titles(&plan.children[0].children), ["Pick seats"]);
+        assert_eq!(plan.children[0].children[0].priority, Priority::Low);
+
+        let unmapped: Vec<(&str, usize)> = backup.unmapped.iter().map(|(field, n)| (field.as_str(), *n)).collect();
+        assert_eq!(
+            unmapped,
+            [("due.is_recurring", 1), ("items.project_id", 1), ("items.section_id", 1), ("sections", 1)]
+        );
+    }
+
+    #[test]
+    fn parses_csv_backup() {
+        let backup = read(&fixture("Work [2203306143].csv")).unwrap();
+        assert_eq!(backup.projects.len(), 1);
+        let project = &backup.projects[0];
+        assert_eq!(project.name, "Work");
+        assert_eq!(titles(&project.items), ["Write report", "Tidy desk"]);
+
+        let report = &project.items[0];
+        assert_eq!(report.priority, Priority::High);
+        assert_eq!(report.content.as_deref(), Some("Quarterly numbers\n\nInclude charts"));
+        assert!(report.due_date.is_some());
+        assert_eq!(titles(&report.children), ["Collect data"]);
+        assert_eq!(report.children[0].priority, Priority::Medium);
+        assert_eq!(report.children[0].due_date, None);
+        assert_eq!(titles(&report.children[0].children), ["Ask finance"]);
+        assert_eq!(report.children[0].children[0].priority, Priority::Low);
+        assert_eq!(project.items[1].priority, Priority::None);
+
+        let unmapped: Vec<(&str, usize)> = backup.unmapped.iter().map(|(field, n)| (field.as_str(), *n)).collect();
+        assert_eq!(unmapped, [("AUTHOR", 2), ("DATE", 1), ("DATE_LANG", 2), ("TIMEZONE", 1), ("section", 1)]);
+    }
+}
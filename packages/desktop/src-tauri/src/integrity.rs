@@ -0,0 +1,295 @@
+use serde::Serialize;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection};
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::CmdError;
+use crate::{backup, db, AppState};
+
+/// Copy of the damaged database taken before a repair, kept out of automatic pruning
+const PRE_REPAIR_PREFIX: &str = "tada-before-repair-";
+
+/// Most problems `PRAGMA integrity_check` lists before it stops
+const MAX_ERRORS: u32 = 100;
+
+/// SQLite result codes (primary, without the extended bits) for a damaged file
+const SQLITE_CORRUPT: i64 = 11;
+const SQLITE_NOTADB: i64 = 26;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForeignKeyViolation {
+    pub table: String,
+    /// `None` for a `WITHOUT ROWID` table
+    pub rowid: Option<i64>,
+    /// Table the row points into
+    pub parent: String,
+    /// Which of the table's foreign keys is violated, in `PRAGMA foreign_key_list` order
+    pub fk_index: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    /// No structural damage; foreign key violations don't count, a repair can't fix them
+    pub intact: bool,
+    pub errors: Vec<String>,
+    pub foreign_key_violations: Vec<ForeignKeyViolation>,
+}
+
+fn is_corruption(e: &sqlx::Error) -> bool {
+    e.as_database_error()
+        .and_then(|e| e.code())
+        .and_then(|code| code.parse::<i64>().ok())
+        .is_some_and(|code| matches!(code & 0xff, SQLITE_CORRUPT | SQLITE_NOTADB))
+}
+
+/// Runs `PRAGMA integrity_check`, or the faster `quick_check` that skips matching indexes
+/// against their tables, and `PRAGMA foreign_key_check`. SQLite sometimes fails the check
+/// itself on a damaged file, which is reported as damage rather than as an error
+async fn check(pool: &sqlx::SqlitePool, quick: bool) -> Result<IntegrityReport, String> {
+    let pragma = if quick { "quick_check" } else { "integrity_check" };
+    let errors = match sqlx::query_scalar::<_, String>(&format!("PRAGMA {}({})", pragma, MAX_ERRORS))
+        .fetch_all(pool)
+        .await
+    {
+        Ok(rows) => rows.into_iter().filter(|row| row != "ok").collect(),
+        Err(e) if is_corruption(&e) => vec![e.to_string()],
+        Err(e) => return Err(format!("Failed to check the database: {}", e)),
+    };
+    let foreign_key_violations = match sqlx::query_as::<_, (String, Option<i64>, String, i64)>(
+        "PRAGMA foreign_key_check",
+    )
+    .fetch_all(pool)
+    .await
+    {
+        Ok(rows) => rows
+            .into_iter()
+            .map(|(table, rowid, parent, fk_index)| ForeignKeyViolation { table, rowid, parent, fk_index })
+            .collect(),
+        Err(e) if !errors.is_empty() || is_corruption(&e) => {
+            log::warn!("[Integrity] Foreign key check failed on the damaged database: {}", e);
+            Vec::new()
+        }
+        Err(e) => return Err(format!("Failed to check foreign keys: {}", e)),
+    };
+    Ok(IntegrityReport { intact: errors.is_empty(), errors, foreign_key_violations })
+}
+
+/// Command for a full integrity check of `tada.db`
+#[tauri::command]
+pub async fn check_database_integrity(app_handle: AppHandle) -> Result<IntegrityReport, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    Ok(check(&pool, false).await?)
+}
+
+fn quoted(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Builds a fresh database at `dest` from what can still be read of `source`: the schema from
+/// its `sqlite_master`, then every table's rows. The full-text index is left out, since
+/// `search::ensure_index` rebuilds it from `tasks` on the next start. Fails, leaving the
+/// damaged database as the only copy of the data, if any table can't be read in full
+async fn rebuild(source: &Path, dest: &Path) -> Result<(), String> {
+    let options = SqliteConnectOptions::new().filename(dest).create_if_missing(true).foreign_keys(false);
+    let mut conn = SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let result = async {
+        sqlx::query("ATTACH DATABASE ? AS old")
+            .bind(source.to_string_lossy().into_owned())
+            .execute(&mut conn)
+            .await
+            .map_err(|e| format!("Failed to open the damaged database: {}", e))?;
+        let schema = sqlx::query_as::<_, (String, String, String)>(
+            "SELECT type, name, sql FROM old.sqlite_master
+             WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%' ORDER BY rowid",
+        )
+        .fetch_all(&mut conn)
+        .await
+        .map_err(|e| format!("Failed to read the damaged database's schema: {}", e))?;
+
+        // Virtual tables, their shadow tables and the triggers that feed them
+        let virtual_tables: Vec<&str> = schema
+            .iter()
+            .filter(|(kind, _, sql)| kind == "table" && sql.to_uppercase().starts_with("CREATE VIRTUAL TABLE"))
+            .map(|(_, name, _)| name.as_str())
+            .collect();
+        let skipped = |name: &str, sql: &str| {
+            virtual_tables
+                .iter()
+                .any(|table| name == *table || name.starts_with(&format!("{}_", table)) || sql.contains(table))
+        };
+        let objects = |kind: &'static str| {
+            schema.iter().filter(move |(k, name, sql)| k == kind && !skipped(name, sql))
+        };
+
+        let mut tx = conn.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+        let mut unreadable = Vec::new();
+        for (_, name, sql) in objects("table") {
+            sqlx::query(sql)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to recreate table {}: {}", name, e))?;
+            let copy = format!("INSERT INTO main.{0} SELECT * FROM old.{0}", quoted(name));
+            if let Err(e) = sqlx::query(&copy).execute(&mut *tx).await {
+                log::error!("[Integrity] Failed to read table {}: {}", name, e);
+                unreadable.push(name.as_str());
+            }
+        }
+        if !unreadable.is_empty() {
+            return Err(format!(
+                "Could not read {} from the damaged database; restore a backup instead",
+                unreadable.join(", ")
+            ));
+        }
+        // Only there when a table uses AUTOINCREMENT. The copy above already moved the counters to
+        // the highest id left, but the old ones can be higher, and ids of deleted rows mustn't return
+        let has_sequence =
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM old.sqlite_master WHERE name = 'sqlite_sequence'")
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to read the damaged database's schema: {}", e))?
+                > 0;
+        if has_sequence
+            && let Err(e) = sqlx::query(
+                "DELETE FROM main.sqlite_sequence; INSERT INTO main.sqlite_sequence SELECT * FROM old.sqlite_sequence",
+            )
+            .execute(&mut *tx)
+            .await
+        {
+            log::warn!("[Integrity] Failed to copy AUTOINCREMENT counters: {}", e);
+        }
+        // Indexes go in after the rows, so they are built from clean data in one pass
+        for kind in ["index", "trigger", "view"] {
+            for (_, name, sql) in objects(kind) {
+                sqlx::query(sql)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Failed to recreate {} {}: {}", kind, name, e))?;
+            }
+        }
+        tx.commit().await.map_err(|e| format!("Failed to commit the repaired database: {}", e))?;
+        sqlx::query("DETACH DATABASE old")
+            .execute(&mut conn)
+            .await
+            .map_err(|e| format!("Failed to close the damaged database: {}", e))?;
+
+        let check = sqlx::query_scalar::<_, String>("PRAGMA integrity_check")
+            .fetch_one(&mut conn)
+            .await
+            .map_err(|e| format!("Failed to check the repaired database: {}", e))?;
+        if check != "ok" {
+            return Err(format!("The repaired database is damaged too: {}", check));
+        }
+        Ok(())
+    }
+    .await;
+    let _ = conn.close().await;
+    result
+}
+
+/// Command to repair a damaged database by copying what it holds into a fresh file and swapping
+/// that in. The damaged file is saved to the backups directory first, and the app restarts
+/// afterwards like after a restore; returns the path of that copy
+#[tauri::command]
+pub async fn repair_database(app_handle: AppHandle) -> Result<String, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let report = check(&pool, false).await?;
+    if report.intact {
+        return Err(CmdError::Validation(
+            "The database passed its integrity check; there is nothing to repair".to_string(),
+        ));
+    }
+    log::warn!("[Integrity] Repairing the database: {}", report.errors.join("; "));
+
+    // Built next to the database, so the rename in `swap_in` stays on one filesystem
+    let db_path = db::db_path(&app_handle)?;
+    let staged = db_path.with_extension("db.repairing");
+    if staged.exists() {
+        std::fs::remove_file(&staged)
+            .map_err(|e| CmdError::Io(format!("Failed to remove {}: {}", staged.display(), e)))?;
+    }
+    if let Err(e) = rebuild(&db_path, &staged).await {
+        let _ = std::fs::remove_file(&staged);
+        return Err(e.into());
+    }
+
+    // `VACUUM INTO` may well fail on the damaged file, so fall back to copying it as is
+    let dir = backup::default_backup_dir(&app_handle)?;
+    let safety_copy = match backup::snapshot(&app_handle, &dir, PRE_REPAIR_PREFIX).await {
+        Ok(path) => Ok(path),
+        Err(e) => {
+            log::warn!("[Integrity] {}; copying the file instead", e);
+            let path = backup::backup_path(&dir, PRE_REPAIR_PREFIX);
+            // The WAL goes along under the matching name, so opening the copy replays it
+            let wal = db_path.with_extension("db-wal");
+            std::fs::copy(&db_path, &path)
+                .and_then(|_| if wal.exists() { std::fs::copy(&wal, path.with_extension("db-wal")) } else { Ok(0) })
+                .map(|_| path)
+                .map_err(|e| CmdError::Io(format!("Failed to save a copy of the damaged database: {}", e)))
+        }
+    };
+    let safety_copy = match safety_copy {
+        Ok(path) => path.to_string_lossy().into_owned(),
+        Err(e) => {
+            let _ = std::fs::remove_file(&staged);
+            return Err(e);
+        }
+    };
+
+    // Past this point the pool is closed, so the app restarts whether or not the swap works
+    let swapped = backup::swap_in(&app_handle, &staged).await;
+    match &swapped {
+        Ok(()) => {
+            log::info!("[Integrity] Database repaired; damaged copy saved to {}", safety_copy);
+            if let Err(e) = app_handle.emit("database-repaired", &safety_copy) {
+                log::error!("[Integrity] Failed to emit database-repaired: {}", e);
+            }
+        }
+        Err(e) => log::error!("[Integrity] Repair failed, restarting on the damaged database: {}", e),
+    }
+    backup::restart_soon(&app_handle);
+    Ok(swapped.map(|()| safety_copy)?)
+}
+
+/// Runs a quick check on startup and logs the result. Damage is announced with
+/// `database-corrupted` once the frontend is ready to offer a restore, see `announce_corruption`
+pub fn check_on_startup(app: &AppHandle) {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        check(&pool, true).await
+    });
+    match result {
+        Ok(report) if report.intact => {
+            if report.foreign_key_violations.is_empty() {
+                log::info!("[Integrity] Database passed its quick check");
+            } else {
+                log::warn!(
+                    "[Integrity] Database has {} rows pointing at missing parents",
+                    report.foreign_key_violations.len()
+                );
+            }
+        }
+        Ok(report) => {
+            log::error!("[Integrity] Database is damaged: {}", report.errors.join("; "));
+            if let Ok(mut corruption) = app.state::<AppState>().corruption.lock() {
+                *corruption = Some(report);
+            }
+        }
+        Err(e) => log::warn!("[Integrity] Startup check failed: {}", e),
+    }
+}
+
+/// Emits `database-corrupted` with the startup report, if it found damage; called when the
+/// frontend says it is ready, since the event would be lost any earlier
+pub fn announce_corruption(app: &AppHandle) {
+    let report = app.state::<AppState>().corruption.lock().ok().and_then(|mut corruption| corruption.take());
+    if let Some(report) = report
+        && let Err(e) = app.emit("database-corrupted", report)
+    {
+        log::error!("[Integrity] Failed to emit database-corrupted: {}", e);
+    }
+}
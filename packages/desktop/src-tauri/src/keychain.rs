@@ -0,0 +1,148 @@
+use keyring::Entry;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::ai::AI_SETTINGS_KEY;
+use crate::db;
+use crate::error::CmdError;
+
+/// Keychain service the secrets are filed under, the app identifier
+const SERVICE: &str = "com.loadshine.tada";
+/// Keychain account holding the AI provider's API key
+const AI_API_KEY_ACCOUNT: &str = "ai-api-key";
+/// Saved as `apiKey` in the `ai` settings row while the real key lives in the keychain
+pub const API_KEY_PLACEHOLDER: &str = "__keychain__";
+
+/// Keychain account holding the WebDAV sync password
+pub const WEBDAV_PASSWORD_ACCOUNT: &str = "webdav-password";
+
+fn entry(account: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, account).map_err(|e| format!("Failed to open the keychain: {}", e))
+}
+
+/// The secret stored under `account`; empty when there is none
+pub fn load_secret(account: &str) -> Result<String, String> {
+    match entry(account)?.get_password() {
+        Ok(secret) => Ok(secret),
+        Err(keyring::Error::NoEntry) => Ok(String::new()),
+        Err(e) => Err(format!("Failed to read {} from the keychain: {}", account, e)),
+    }
+}
+
+/// Saves a secret under `account`; an empty one removes the entry
+pub fn store_secret(account: &str, secret: &str) -> Result<(), String> {
+    let entry = entry(account)?;
+    let result = if secret.is_empty() {
+        match entry.delete_credential() {
+            Err(keyring::Error::NoEntry) => Ok(()),
+            result => result,
+        }
+    } else {
+        entry.set_password(secret)
+    };
+    result.map_err(|e| format!("Failed to write {} to the keychain: {}", account, e))
+}
+
+/// Keychain account holding the key of the primary AI provider, or of `fallbacks[index]`
+fn api_key_account(fallback: Option<usize>) -> String {
+    match fallback {
+        Some(index) => format!("{}-fallback-{}", AI_API_KEY_ACCOUNT, index),
+        None => AI_API_KEY_ACCOUNT.to_string(),
+    }
+}
+
+/// The AI API key from the keychain; empty when none is stored
+pub fn load_api_key(fallback: Option<usize>) -> Result<String, String> {
+    load_secret(&api_key_account(fallback))
+}
+
+/// Saves the AI API key in the keychain; an empty key removes the entry
+fn store_api_key(fallback: Option<usize>, key: &str) -> Result<(), String> {
+    store_secret(&api_key_account(fallback), key)
+}
+
+/// Swaps the placeholder from a saved `ai` row, or from one of its fallbacks, for the real key
+pub fn resolve_api_key(fallback: Option<usize>, api_key: String) -> Result<String, String> {
+    if api_key == API_KEY_PLACEHOLDER {
+        load_api_key(fallback)
+    } else {
+        Ok(api_key)
+    }
+}
+
+/// The primary provider's settings in a saved `ai` row, or those of `fallbacks[index]`
+fn provider_settings(settings: &mut Value, fallback: Option<usize>) -> Option<&mut Value> {
+    let provider = match fallback {
+        Some(index) => settings.get_mut("fallbacks")?.get_mut(index)?,
+        None => settings,
+    };
+    provider.is_object().then_some(provider)
+}
+
+/// Points `apiKey` in the saved `ai` row at the keychain, or blanks it when there is no key
+async fn replace_saved_key(pool: &sqlx::SqlitePool, fallback: Option<usize>, has_key: bool) -> Result<(), String> {
+    let Some(value) = db::get_setting(pool, AI_SETTINGS_KEY).await? else {
+        return Ok(());
+    };
+    let mut settings: Value =
+        serde_json::from_str(&value).map_err(|e| format!("Failed to parse AI settings: {}", e))?;
+    let Some(provider) = provider_settings(&mut settings, fallback) else {
+        return Ok(());
+    };
+    let placeholder = if has_key { API_KEY_PLACEHOLDER } else { "" };
+    provider["apiKey"] = Value::from(placeholder);
+    db::set_setting(pool, AI_SETTINGS_KEY, &settings.to_string()).await
+}
+
+/// Moves plaintext keys left in the `ai` settings row by older versions, the primary
+/// provider's and each fallback's, into the keychain
+pub fn migrate_plaintext_key(app: &AppHandle) {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        let Some(value) = db::get_setting(&pool, AI_SETTINGS_KEY).await? else {
+            return Ok(0);
+        };
+        let mut settings: Value =
+            serde_json::from_str(&value).map_err(|e| format!("Failed to parse AI settings: {}", e))?;
+        let fallbacks = settings["fallbacks"].as_array().map_or(0, Vec::len);
+        let mut moved = 0;
+        for fallback in std::iter::once(None).chain((0..fallbacks).map(Some)) {
+            let Some(provider) = provider_settings(&mut settings, fallback) else {
+                continue;
+            };
+            let key = provider["apiKey"].as_str().unwrap_or_default();
+            if key.is_empty() || key == API_KEY_PLACEHOLDER {
+                continue;
+            }
+            store_api_key(fallback, key)?;
+            provider["apiKey"] = Value::from(API_KEY_PLACEHOLDER);
+            moved += 1;
+        }
+        if moved > 0 {
+            db::set_setting(&pool, AI_SETTINGS_KEY, &settings.to_string()).await?;
+        }
+        Ok::<_, String>(moved)
+    });
+    match result {
+        Ok(0) => {}
+        Ok(moved) => log::info!("[Keychain] Moved {} AI API key(s) into the keychain", moved),
+        Err(e) => log::warn!("[Keychain] Could not migrate the AI API keys: {}", e),
+    }
+}
+
+/// Saves the key of the primary AI provider, or of `fallbacks[fallback]` when given
+#[tauri::command]
+pub async fn set_ai_api_key(app_handle: AppHandle, key: String, fallback: Option<usize>) -> Result<(), CmdError> {
+    let key = key.trim();
+    store_api_key(fallback, key)?;
+    let pool = db::pool(&app_handle).await?;
+    replace_saved_key(&pool, fallback, !key.is_empty()).await?;
+    let provider = fallback.map_or_else(|| "AI API key".to_string(), |index| format!("AI fallback {} API key", index));
+    log::info!("[Keychain] {} {}", provider, if key.is_empty() { "removed" } else { "saved" });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_ai_api_key(fallback: Option<usize>) -> Result<String, CmdError> {
+    Ok(load_api_key(fallback)?)
+}
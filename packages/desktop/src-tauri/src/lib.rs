@@ -1,37 +1,94 @@
 use tauri_plugin_sql::{Migration, MigrationKind};
-use tauri::{
-    Manager, Emitter,
-};
-use std::sync::Mutex;
-use std::time::Duration;
-use chrono::{Timelike, Datelike};
-
-/// Schedule settings for automated report generation
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct ScheduleSettings {
-    pub enabled: bool,
-    pub time: String, // HH:mm format, e.g., "18:00"
-    pub days: Vec<u8>, // 0=Sunday, 1=Monday, ..., 6=Saturday
-}
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Condvar, Mutex};
+use std::thread::JoinHandle;
+use tauri::menu::CheckMenuItem;
+use tauri::{Manager, Wry};
 
-impl Default for ScheduleSettings {
-    fn default() -> Self {
-        Self {
-            enabled: false,
-            time: "18:00".to_string(),
-            days: vec![1, 2, 3, 4, 5], // Mon-Fri
-        }
-    }
-}
+mod ai;
+mod app_info;
+mod attachments;
+#[cfg(desktop)]
+mod autostart;
+mod backup;
+mod cli;
+mod db;
+#[cfg(desktop)]
+mod deep_link;
+mod dependencies;
+mod device;
+mod dnd;
+mod echo_reports;
+mod error;
+mod export;
+mod idle;
+mod import;
+mod integrity;
+#[cfg(desktop)]
+mod keychain;
+mod lists;
+mod logging;
+mod paths;
+mod priority;
+mod recurrence;
+mod reminders;
+mod rollover;
+mod scheduler;
+mod search;
+#[cfg(desktop)]
+mod shortcut;
+mod stats;
+mod subtasks;
+mod summaries;
+#[cfg(desktop)]
+mod sync;
+mod tags;
+mod tasks;
+mod time_tracking;
+mod trash;
+mod tray;
+#[cfg(desktop)]
+mod updater;
+mod user_profile;
+mod vacuum;
+mod window;
+
+pub use scheduler::{ScheduleEntry, ScheduleSettings};
 
 /// Application state to track quitting status and schedule settings
 struct AppState {
     schedule_settings: Mutex<ScheduleSettings>,
+    schedule_changed: Condvar,
+    schedule_settings_loaded: AtomicBool, // read from the database, not defaults after a failure
+    last_triggered: Mutex<HashMap<String, String>>, // schedule kind -> last fired occurrence
+    deferred_triggers: Mutex<Vec<scheduler::DueTrigger>>, // suppressed during quiet hours
+    frontend_ready: AtomicBool,
+    pending_triggers: Mutex<Vec<scheduler::ScheduleTriggerPayload>>, // held until frontend_ready
+    scheduler_stop: AtomicBool,
+    report_in_progress: AtomicBool, // confirm before quitting from the tray
+    scheduler_handle: Mutex<Option<JoinHandle<()>>>,
+    tray_schedule_item: Mutex<Option<CheckMenuItem<Wry>>>, // set once the tray is built
+    tray_always_on_top_item: Mutex<Option<CheckMenuItem<Wry>>>,
+    tray_busy: AtomicBool, // report generation in progress, see `tray::set_tray_status`
+    tray_left_click: Mutex<tray::TrayLeftClick>,
+    global_shortcut: Mutex<Option<String>>, // accelerator currently registered
+    ai_requests: Mutex<HashMap<String, (tokio::task::AbortHandle, bool)>>, // request id -> in-flight call, streams?
+    ai_cache: Mutex<HashMap<u64, ai::CachedResponse>>, // request hash -> recent reply
+    last_reminder: Mutex<Option<(String, std::time::Instant)>>, // task id, when it was shown
+    deferred_reminders: Mutex<HashMap<i64, dnd::QuietReason>>, // held back, see `reminders::defer`
+    fts_available: AtomicBool, // set by `search::ensure_index`
+    #[cfg(desktop)]
+    updater_available: AtomicBool, // the updater plugin loaded, see `updater::init`
+    corruption: Mutex<Option<integrity::IntegrityReport>>, // found at startup, held until frontend_ready
+    log_level: Mutex<log::LevelFilter>, // effective level, see `logging::set_log_level`
+    categories_day: Mutex<Option<chrono::NaiveDate>>, // local day groups were last recomputed for
+    setup_complete: AtomicBool, // see `app_info::announce_ready`
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    let migrations = vec![
+/// Schema migrations for `tada.db`, applied by the sql plugin when it opens the database
+fn migrations() -> Vec<Migration> {
+    vec![
         Migration {
             version: 1,
             description: "create_initial_tables",
@@ -158,136 +215,615 @@ pub fn run() {
                 VALUES ('default', 0, strftime('%s', 'now') * 1000, strftime('%s', 'now') * 1000);
             "#,
             kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 4,
+            description: "add_ai_usage",
+            sql: r#"
+                -- One row per AI call; token counts are NULL when the provider doesn't report them
+                CREATE TABLE IF NOT EXISTS ai_usage (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    created_at INTEGER NOT NULL,
+                    provider TEXT NOT NULL,
+                    model TEXT NOT NULL,
+                    prompt_tokens INTEGER,
+                    completion_tokens INTEGER,
+                    total_tokens INTEGER,
+                    purpose TEXT                    -- e.g. 'summary' | 'echo', set by the caller
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_ai_usage_created_at ON ai_usage(created_at);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 5,
+            description: "add_task_recurrence",
+            sql: r#"
+                -- RFC 5545 RRULE, e.g. 'FREQ=WEEKLY;BYDAY=MO,WE'; NULL for one-off tasks
+                ALTER TABLE tasks ADD COLUMN recurrence TEXT;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 6,
+            description: "add_reminders",
+            sql: r#"
+                -- Reminders table, one row per notification to deliver
+                CREATE TABLE IF NOT EXISTS reminders (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    task_id TEXT NOT NULL,
+                    remind_at INTEGER NOT NULL,
+                    delivered INTEGER NOT NULL DEFAULT 0,
+                    created_at INTEGER NOT NULL,
+                    FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_reminders_due ON reminders(delivered, remind_at);
+                CREATE INDEX IF NOT EXISTS idx_reminders_task_id ON reminders(task_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 7,
+            description: "add_soft_delete",
+            sql: r#"
+                -- Set instead of deleting the row; NULL for live tasks and lists
+                ALTER TABLE tasks ADD COLUMN deleted_at INTEGER;
+                ALTER TABLE lists ADD COLUMN deleted_at INTEGER;
+
+                CREATE INDEX IF NOT EXISTS idx_tasks_deleted_at ON tasks(deleted_at);
+                CREATE INDEX IF NOT EXISTS idx_lists_deleted_at ON lists(deleted_at);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 8,
+            description: "add_nested_subtasks",
+            sql: r#"
+                -- Parent subtask for nested checklist items; NULL directly under the task
+                ALTER TABLE subtasks ADD COLUMN parent_subtask_id TEXT REFERENCES subtasks (id) ON DELETE CASCADE;
+
+                -- "order" is scoped to siblings under the same parent
+                CREATE INDEX IF NOT EXISTS idx_subtasks_parent_order ON subtasks(parent_id, parent_subtask_id, "order");
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 9,
+            description: "add_task_dependencies",
+            sql: r#"
+                -- task_id can't start until depends_on_id is completed
+                CREATE TABLE IF NOT EXISTS task_dependencies (
+                    task_id TEXT NOT NULL,
+                    depends_on_id TEXT NOT NULL,
+                    created_at INTEGER NOT NULL,
+                    PRIMARY KEY (task_id, depends_on_id),
+                    FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE,
+                    FOREIGN KEY (depends_on_id) REFERENCES tasks (id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_task_dependencies_depends_on ON task_dependencies(depends_on_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "add_tags",
+            sql: r#"
+                -- Tags, previously a JSON array of names in tasks.tags
+                CREATE TABLE IF NOT EXISTS tags (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL UNIQUE,
+                    color TEXT,
+                    created_at INTEGER NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS task_tags (
+                    task_id TEXT NOT NULL,
+                    tag_id INTEGER NOT NULL,
+                    PRIMARY KEY (task_id, tag_id),
+                    FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE,
+                    FOREIGN KEY (tag_id) REFERENCES tags (id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_task_tags_tag_id ON task_tags(tag_id);
+
+                -- Existing tags; anything that isn't valid JSON is left behind
+                INSERT OR IGNORE INTO tags (name, created_at)
+                SELECT DISTINCT trim(j.value), CAST(strftime('%s', 'now') AS INTEGER) * 1000
+                FROM tasks t, json_each(CASE WHEN json_valid(t.tags) THEN t.tags ELSE '[]' END) j
+                WHERE trim(j.value) != '';
+
+                INSERT OR IGNORE INTO task_tags (task_id, tag_id)
+                SELECT t.id, g.id
+                FROM tasks t, json_each(CASE WHEN json_valid(t.tags) THEN t.tags ELSE '[]' END) j
+                JOIN tags g ON g.name = trim(j.value);
+
+                -- For one release tasks.tags stays in sync both ways: it is rewritten from task_tags, and
+                -- older code writing the JSON column updates task_tags. No OR IGNORE in these bodies: an
+                -- outer INSERT OR REPLACE would turn it into REPLACE and renumber the tags
+                CREATE TRIGGER IF NOT EXISTS task_tags_insert AFTER INSERT ON task_tags BEGIN
+                    UPDATE tasks SET tags = (
+                        SELECT json_group_array(name) FROM (
+                            SELECT g.name FROM task_tags tt JOIN tags g ON g.id = tt.tag_id WHERE tt.task_id = new.task_id ORDER BY tt.rowid
+                        )
+                    ) WHERE id = new.task_id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS task_tags_delete AFTER DELETE ON task_tags BEGIN
+                    UPDATE tasks SET tags = (
+                        SELECT json_group_array(name) FROM (
+                            SELECT g.name FROM task_tags tt JOIN tags g ON g.id = tt.tag_id WHERE tt.task_id = old.task_id ORDER BY tt.rowid
+                        )
+                    ) WHERE id = old.task_id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS tags_rename AFTER UPDATE OF name ON tags BEGIN
+                    UPDATE tasks SET tags = (
+                        SELECT json_group_array(name) FROM (
+                            SELECT g.name FROM task_tags tt JOIN tags g ON g.id = tt.tag_id WHERE tt.task_id = tasks.id ORDER BY tt.rowid
+                        )
+                    ) WHERE id IN (SELECT task_id FROM task_tags WHERE tag_id = new.id);
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS tasks_tags_insert AFTER INSERT ON tasks BEGIN
+                    INSERT INTO tags (name, created_at)
+                    SELECT DISTINCT trim(j.value), new.updated_at FROM json_each(CASE WHEN json_valid(new.tags) THEN new.tags ELSE '[]' END) j
+                    WHERE trim(j.value) != '' AND NOT EXISTS (SELECT 1 FROM tags WHERE name = trim(j.value));
+                    DELETE FROM task_tags WHERE task_id = new.id AND tag_id NOT IN (
+                        SELECT g.id FROM json_each(CASE WHEN json_valid(new.tags) THEN new.tags ELSE '[]' END) j JOIN tags g ON g.name = trim(j.value)
+                    );
+                    INSERT INTO task_tags (task_id, tag_id)
+                    SELECT DISTINCT new.id, g.id FROM json_each(CASE WHEN json_valid(new.tags) THEN new.tags ELSE '[]' END) j JOIN tags g ON g.name = trim(j.value)
+                    WHERE NOT EXISTS (SELECT 1 FROM task_tags WHERE task_id = new.id AND tag_id = g.id);
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS tasks_tags_update AFTER UPDATE OF tags ON tasks BEGIN
+                    INSERT INTO tags (name, created_at)
+                    SELECT DISTINCT trim(j.value), new.updated_at FROM json_each(CASE WHEN json_valid(new.tags) THEN new.tags ELSE '[]' END) j
+                    WHERE trim(j.value) != '' AND NOT EXISTS (SELECT 1 FROM tags WHERE name = trim(j.value));
+                    DELETE FROM task_tags WHERE task_id = new.id AND tag_id NOT IN (
+                        SELECT g.id FROM json_each(CASE WHEN json_valid(new.tags) THEN new.tags ELSE '[]' END) j JOIN tags g ON g.name = trim(j.value)
+                    );
+                    INSERT INTO task_tags (task_id, tag_id)
+                    SELECT DISTINCT new.id, g.id FROM json_each(CASE WHEN json_valid(new.tags) THEN new.tags ELSE '[]' END) j JOIN tags g ON g.name = trim(j.value)
+                    WHERE NOT EXISTS (SELECT 1 FROM task_tags WHERE task_id = new.id AND tag_id = g.id);
+                END;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 11,
+            description: "add_time_tracking",
+            sql: r#"
+                ALTER TABLE tasks ADD COLUMN estimated_minutes INTEGER;
+                ALTER TABLE tasks ADD COLUMN actual_minutes INTEGER NOT NULL DEFAULT 0;
+
+                -- One row per timer run; ended_at is NULL while it is running
+                CREATE TABLE IF NOT EXISTS time_entries (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    task_id TEXT NOT NULL,
+                    started_at INTEGER NOT NULL,
+                    ended_at INTEGER,
+                    FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_time_entries_started_at ON time_entries(started_at);
+                -- At most one running timer per task
+                CREATE UNIQUE INDEX IF NOT EXISTS idx_time_entries_running ON time_entries(task_id) WHERE ended_at IS NULL;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 12,
+            description: "add_list_archived",
+            sql: r#"
+                -- Archived lists are left out of the sidebar only
+                ALTER TABLE lists ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 13,
+            description: "add_task_pinned",
+            sql: r#"
+                -- Pinned tasks sort first within their group, then by "order" as before
+                ALTER TABLE tasks ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 14,
+            description: "add_attachments",
+            sql: r#"
+                -- Files attached to tasks; file_path is the app's copy under the data directory
+                CREATE TABLE IF NOT EXISTS attachments (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    task_id TEXT NOT NULL,
+                    file_path TEXT NOT NULL,
+                    file_name TEXT NOT NULL,
+                    mime TEXT NOT NULL,
+                    size INTEGER NOT NULL,
+                    created_at INTEGER NOT NULL,
+                    FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_attachments_task_id ON attachments(task_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 15,
+            description: "add_task_rolled_over_from",
+            sql: r#"
+                -- Due date a task had before the midnight rollover first moved it, for undoing it
+                ALTER TABLE tasks ADD COLUMN rolled_over_from INTEGER;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 16,
+            description: "remove_orphaned_rows",
+            sql: r#"
+                -- Rows whose parent went while foreign keys weren't enforced. Nested subtasks
+                -- keep their task and move to the top level, as `get_subtask_tree` shows them
+                DELETE FROM subtasks WHERE parent_id NOT IN (SELECT id FROM tasks);
+                UPDATE subtasks SET parent_subtask_id = NULL
+                    WHERE parent_subtask_id IS NOT NULL AND parent_subtask_id NOT IN (SELECT id FROM subtasks);
+                DELETE FROM reminders WHERE task_id NOT IN (SELECT id FROM tasks);
+                DELETE FROM task_dependencies
+                    WHERE task_id NOT IN (SELECT id FROM tasks) OR depends_on_id NOT IN (SELECT id FROM tasks);
+                DELETE FROM task_tags WHERE task_id NOT IN (SELECT id FROM tasks) OR tag_id NOT IN (SELECT id FROM tags);
+                DELETE FROM time_entries WHERE task_id NOT IN (SELECT id FROM tasks);
+                DELETE FROM attachments WHERE task_id NOT IN (SELECT id FROM tasks);
+                UPDATE tasks SET list_id = NULL WHERE list_id IS NOT NULL AND list_id NOT IN (SELECT id FROM lists);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 17,
+            description: "add_device_id",
+            sql: r#"
+                -- Install that last wrote the row, from the `device_id` setting. Triggers stamp it,
+                -- so the frontend's writes count as much as the commands'. Only writes that move
+                -- `updated_at` do, and one that sets `device_id` itself keeps its value
+                ALTER TABLE tasks ADD COLUMN device_id TEXT;
+                ALTER TABLE lists ADD COLUMN device_id TEXT;
+
+                CREATE TRIGGER IF NOT EXISTS tasks_device_insert AFTER INSERT ON tasks WHEN new.device_id IS NULL BEGIN
+                    UPDATE tasks SET device_id = (SELECT json_extract(value, '$') FROM settings WHERE key = 'device_id')
+                        WHERE id = new.id;
+                END;
+                CREATE TRIGGER IF NOT EXISTS tasks_device_update AFTER UPDATE ON tasks
+                    WHEN new.updated_at IS NOT old.updated_at AND new.device_id IS old.device_id BEGIN
+                    UPDATE tasks SET device_id = (SELECT json_extract(value, '$') FROM settings WHERE key = 'device_id')
+                        WHERE id = new.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS lists_device_insert AFTER INSERT ON lists WHEN new.device_id IS NULL BEGIN
+                    UPDATE lists SET device_id = (SELECT json_extract(value, '$') FROM settings WHERE key = 'device_id')
+                        WHERE id = new.id;
+                END;
+                CREATE TRIGGER IF NOT EXISTS lists_device_update AFTER UPDATE ON lists
+                    WHEN new.updated_at IS NOT old.updated_at AND new.device_id IS old.device_id BEGIN
+                    UPDATE lists SET device_id = (SELECT json_extract(value, '$') FROM settings WHERE key = 'device_id')
+                        WHERE id = new.id;
+                END;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 18,
+            description: "clamp_task_priority",
+            sql: r#"
+                -- Priority is 1 (high) to 3 (low) or NULL; imports could write anything. Values
+                -- that aren't numbers are dropped, the rest moved to the nearest valid one
+                UPDATE tasks SET priority = NULL
+                    WHERE priority IS NOT NULL AND typeof(priority) NOT IN ('integer', 'real');
+                UPDATE tasks SET priority = MIN(MAX(CAST(ROUND(priority) AS INTEGER), 1), 3)
+                    WHERE priority IS NOT NULL AND priority NOT IN (1, 2, 3);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 19,
+            description: "backfill_task_list_name",
+            sql: r#"
+                -- Renaming a list used to leave its tasks under the old name; trashed tasks keep `Trash`
+                UPDATE tasks SET list_name = (SELECT name FROM lists WHERE lists.id = tasks.list_id)
+                    WHERE list_name != 'Trash'
+                      AND EXISTS (SELECT 1 FROM lists WHERE lists.id = tasks.list_id AND lists.name != tasks.list_name);
+            "#,
+            kind: MigrationKind::Up,
         }
-    ];
-
-    #[tauri::command]
-    fn update_schedule_settings(
-        state: tauri::State<'_, AppState>,
-        settings: ScheduleSettings,
-    ) -> Result<(), String> {
-        log::info!("[Scheduler] Updating schedule settings: enabled={}, time={}, days={:?}", 
-            settings.enabled, settings.time, settings.days);
-        
-        match state.schedule_settings.lock() {
-            Ok(mut current) => {
-                *current = settings;
-                Ok(())
-            }
-            Err(e) => Err(format!("Failed to update schedule settings: {}", e)),
-        }
-    }
+    ]
+}
 
-    fn start_background_scheduler(app_handle: tauri::AppHandle) {
-        std::thread::spawn(move || {
-            log::info!("[Scheduler] Background scheduler started");
-            
-            loop {
-                // Sleep for 60 seconds
-                std::thread::sleep(Duration::from_secs(60));
-                
-                // Get current time
-                let now = chrono::Local::now();
-                let current_hour = now.hour();
-                let current_minute = now.minute();
-                let current_day = now.weekday().num_days_from_sunday() as u8; // 0=Sunday
-                let today_str = now.format("%Y-%m-%d").to_string();
-                
-                // Check schedule settings
-                let should_trigger = {
-                    let state = app_handle.state::<AppState>();
-                    match state.schedule_settings.lock() {
-                        Ok(settings) => {
-                            if !settings.enabled {
-                                false
-                            } else if !settings.days.contains(&current_day) {
-                                false
-                            } else {
-                                // Parse scheduled time
-                                let parts: Vec<&str> = settings.time.split(':').collect();
-                                if parts.len() == 2 {
-                                    if let (Ok(scheduled_hour), Ok(scheduled_minute)) = 
-                                        (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
-                                        current_hour == scheduled_hour && current_minute == scheduled_minute
-                                    } else {
-                                        false
-                                    }
-                                } else {
-                                    false
-                                }
-                            }
-                        }
-                        Err(_) => false,
-                    }
-                };
-                
-                if should_trigger {
-                    log::info!("[Scheduler] ⏰ Triggering scheduled report at {}:{:02}", 
-                        current_hour, current_minute);
-                    
-                    // Emit event to frontend
-                    #[derive(Clone, serde::Serialize)]
-                    struct ScheduleTriggerPayload {
-                        timestamp: i64,
-                        date: String,
-                        time: String,
-                    }
-                    
-                    let payload = ScheduleTriggerPayload {
-                        timestamp: now.timestamp_millis(),
-                        date: today_str,
-                        time: format!("{}:{:02}", current_hour, current_minute),
-                    };
-                    
-                    if let Err(e) = app_handle.emit("schedule-trigger", payload) {
-                        log::error!("[Scheduler] Failed to emit schedule-trigger event: {}", e);
-                    }
-                }
-            }
-        });
+/// Newest schema version this build knows how to migrate to
+pub(crate) fn schema_version() -> i64 {
+    migrations().iter().map(|migration| migration.version).max().unwrap_or(0)
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    let mut context = tauri::generate_context!();
+    paths::init(&mut context);
+
+    #[allow(unused_mut)]
+    let mut builder = tauri::Builder::default();
+    // Must be the first plugin so a second launch exits before anything else initializes;
+    // the plugin releases its lock itself when the app exits
+    #[cfg(desktop)]
+    {
+        builder = builder.plugin(tauri_plugin_single_instance::init(window::on_second_instance));
     }
 
-    tauri::Builder::default()
+    builder
         .manage(AppState {
             schedule_settings: Mutex::new(ScheduleSettings::default()),
+            schedule_changed: Condvar::new(),
+            schedule_settings_loaded: AtomicBool::new(false),
+            last_triggered: Mutex::new(HashMap::new()),
+            deferred_triggers: Mutex::new(Vec::new()),
+            frontend_ready: AtomicBool::new(false),
+            pending_triggers: Mutex::new(Vec::new()),
+            scheduler_stop: AtomicBool::new(false),
+            report_in_progress: AtomicBool::new(false),
+            scheduler_handle: Mutex::new(None),
+            tray_schedule_item: Mutex::new(None),
+            tray_always_on_top_item: Mutex::new(None),
+            tray_busy: AtomicBool::new(false),
+            tray_left_click: Mutex::new(tray::TrayLeftClick::default()),
+            global_shortcut: Mutex::new(None),
+            ai_requests: Mutex::new(HashMap::new()),
+            ai_cache: Mutex::new(HashMap::new()),
+            last_reminder: Mutex::new(None),
+            deferred_reminders: Mutex::new(HashMap::new()),
+            fts_available: AtomicBool::new(false),
+            #[cfg(desktop)]
+            updater_available: AtomicBool::new(false),
+            corruption: Mutex::new(None),
+            log_level: Mutex::new(logging::DEFAULT_LEVEL),
+            categories_day: Mutex::new(None),
+            setup_complete: AtomicBool::new(false),
         })
-        .invoke_handler(tauri::generate_handler![update_schedule_settings])
+        .invoke_handler(tauri::generate_handler![
+            ai::test_ai_connection,
+            ai::ai_chat_completion,
+            ai::get_available_ai_models,
+            ai::list_available_models,
+            ai::start_ai_stream,
+            ai::cancel_ai_request,
+            ai::get_ai_usage,
+            ai::clear_ai_cache,
+            app_info::get_app_info,
+            app_info::app_ready,
+            scheduler::update_schedule_settings,
+            scheduler::get_schedule_settings,
+            scheduler::trigger_report_now,
+            scheduler::mark_frontend_ready,
+            scheduler::next_scheduled_trigger,
+            scheduler::set_report_in_progress,
+            recurrence::materialize_recurring_tasks,
+            attachments::add_attachment,
+            attachments::list_attachments,
+            attachments::remove_attachment,
+            attachments::open_attachment,
+            backup::backup_database,
+            backup::get_auto_backup_settings,
+            backup::set_auto_backup_settings,
+            backup::restore_database,
+            export::export_data,
+            export::export_markdown,
+            export::export_ics,
+            export::export_csv,
+            export::export_encrypted,
+            import::import_data,
+            import::import_encrypted,
+            import::import_todoist,
+            import::import_ticktick,
+            integrity::check_database_integrity,
+            integrity::repair_database,
+            dependencies::add_dependency,
+            dependencies::remove_dependency,
+            dependencies::get_blocked_tasks,
+            dependencies::check_unblocked_tasks,
+            device::get_device_id,
+            dnd::get_notification_settings,
+            dnd::set_notification_settings,
+            echo_reports::create_echo_report,
+            echo_reports::list_echo_reports,
+            echo_reports::get_echo_report,
+            echo_reports::delete_echo_report,
+            lists::get_lists,
+            lists::archive_list,
+            lists::unarchive_list,
+            lists::rename_list,
+            logging::set_log_level,
+            logging::get_log_level,
+            logging::get_log_size,
+            logging::clear_logs,
+            paths::open_database_location,
+            priority::tasks_by_priority,
+            reminders::set_reminder,
+            reminders::clear_reminder,
+            rollover::get_auto_rollover,
+            rollover::set_auto_rollover,
+            rollover::undo_rollover,
+            search::search_tasks,
+            stats::get_statistics,
+            subtasks::add_subtask,
+            subtasks::delete_subtask,
+            subtasks::get_subtask_tree,
+            summaries::create_summary,
+            summaries::get_summaries,
+            tags::list_tags,
+            tags::rename_tag,
+            tags::set_tag_color,
+            tags::set_task_tags,
+            tags::tasks_by_tag,
+            tasks::pin_task,
+            tasks::unpin_task,
+            tasks::bulk_update_tasks,
+            tasks::recompute_group_categories,
+            tasks::get_agenda,
+            tasks::normalize_order,
+            tasks::reorder_task,
+            tasks::duplicate_task,
+            tasks::move_task,
+            time_tracking::start_timer,
+            time_tracking::stop_timer,
+            time_tracking::get_time_report,
+            trash::list_trash,
+            trash::restore_task,
+            trash::empty_trash,
+            trash::set_trash_retention_days,
+            trash::purge_completed,
+            trash::set_completed_retention_days,
+            tray::refresh_tray_tooltip,
+            tray::refresh_tray_badge,
+            tray::refresh_tray_menu,
+            tray::set_tray_status,
+            tray::set_tray_left_click,
+            tray::confirm_quit,
+            user_profile::get_user_profile,
+            user_profile::update_user_profile,
+            vacuum::vacuum_database,
+            window::set_start_minimized,
+            window::set_show_on_trigger,
+            window::set_always_on_top,
+            window::open_focus_widget,
+            #[cfg(desktop)]
+            shortcut::set_global_shortcut,
+            #[cfg(desktop)]
+            autostart::enable_autostart,
+            #[cfg(desktop)]
+            autostart::disable_autostart,
+            #[cfg(desktop)]
+            autostart::is_autostart_enabled,
+            #[cfg(desktop)]
+            keychain::set_ai_api_key,
+            #[cfg(desktop)]
+            keychain::get_ai_api_key,
+            #[cfg(desktop)]
+            sync::get_sync_settings,
+            #[cfg(desktop)]
+            sync::set_sync_settings,
+            #[cfg(desktop)]
+            sync::sync_push,
+            #[cfg(desktop)]
+            sync::sync_pull,
+            #[cfg(desktop)]
+            updater::check_for_update,
+            #[cfg(desktop)]
+            updater::install_update,
+            #[cfg(desktop)]
+            updater::get_update_channel,
+            #[cfg(desktop)]
+            updater::set_update_channel,
+        ])
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
         .plugin(
             tauri_plugin_log::Builder::default()
                 .targets([
                     tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Stdout),
-                    tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::LogDir {
-                        file_name: None,
-                    }),
+                    tauri_plugin_log::Target::new(paths::log_target()),
                     tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Webview),
                 ])
                 .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepSome(3))
-                .level(log::LevelFilter::Info)
+                // Lets everything through; `logging::restore_log_level` sets the level that applies
+                .level(log::LevelFilter::Trace)
                 .build(),
         )
         .plugin(
             tauri_plugin_sql::Builder::default()
-                .add_migrations("sqlite:tada.db", migrations)
+                .add_migrations(&db::preload_url(), migrations())
                 .build(),
         )
         .setup(|app| {
-            start_background_scheduler(app.handle().clone());
+            paths::check_on_startup(app.handle());
+            logging::restore_log_level(app.handle());
+            db::configure_pool(app.handle());
+            device::sync_device_id(app.handle());
+            scheduler::restore_state(app.handle());
+            integrity::check_on_startup(app.handle());
+            search::ensure_index(app.handle());
+            window::restore_always_on_top(app.handle());
+            scheduler::start_background_scheduler(app.handle().clone());
+            reminders::start_reminder_checker(app.handle().clone());
+            tray::create_tray(app.handle())?;
+            tray::start_refresh_timer(app.handle().clone());
+
+            #[cfg(desktop)]
+            {
+                app.handle().plugin(shortcut::plugin())?;
+                app.handle().plugin(autostart::plugin())?;
+                app.handle().plugin(tauri_plugin_deep_link::init())?;
+                shortcut::register_saved_shortcut(app.handle());
+                deep_link::listen(app.handle());
+                keychain::migrate_plaintext_key(app.handle());
+                updater::init(app.handle());
+            }
+
+            cli::handle_launch_args(app.handle());
+            app_info::announce_ready(app.handle());
+
+            // The main window starts hidden so it can be moved to its saved geometry first,
+            // and stays hidden in the tray when the user asked to start minimized
+            if let Some(main_window) = app.get_webview_window("main") {
+                window::restore_window_state(&main_window);
+            }
+            if !window::load_start_minimized(app.handle()) {
+                tray::show_main_window(app.handle());
+            }
 
             Ok(())
         })
-        // .plugin(tauri_plugin_updater::Builder::new().build())
-        .build(tauri::generate_context!())
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Focused(true) = event
+                && window.label() == "main"
+            {
+                reminders::on_main_focused(window.app_handle());
+            }
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event
+                && let Some(webview_window) = window.app_handle().get_webview_window(window.label())
+            {
+                window::save_window_state(&webview_window);
+                // Keep the main window around (hidden) while the widget is open, so closing
+                // it neither ends the app nor leaves the tray without a window to show
+                if window.label() == "main"
+                    && window.app_handle().get_webview_window(window::WIDGET_LABEL).is_some()
+                {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+        })
+        .build(context)
         .expect("error while building tauri application")
         .run(|app_handle, event| match event {
+            // Stop the scheduler while the database is still open; plugins see `Exit` before
+            // this callback and the sql plugin closes its pools there
+            tauri::RunEvent::ExitRequested { .. } => {
+                for label in ["main", window::WIDGET_LABEL] {
+                    if let Some(window) = app_handle.get_webview_window(label) {
+                        window::save_window_state(&window);
+                    }
+                }
+                scheduler::stop_background_scheduler(app_handle);
+            }
+            tauri::RunEvent::Exit => {
+                scheduler::stop_background_scheduler(app_handle);
+            }
             // To handle macOS, click the Dock icon to reopen the window
             #[cfg(target_os = "macos")]
             tauri::RunEvent::Reopen { .. } => {
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
+                tray::show_main_window(app_handle);
             }
             _ => {}
         });
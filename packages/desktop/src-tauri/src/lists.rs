@@ -0,0 +1,145 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::db;
+use crate::error::CmdError;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListInfo {
+    pub id: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    pub order: Option<i64>,
+    pub archived: bool,
+}
+
+/// Command listing live lists in sidebar order; archived ones only with `include_archived`
+#[tauri::command]
+pub async fn get_lists(app_handle: AppHandle, include_archived: Option<bool>) -> Result<Vec<ListInfo>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let rows = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, Option<i64>, bool)>(
+        r#"SELECT id, name, icon, color, "order", archived FROM lists
+           WHERE deleted_at IS NULL AND (archived = 0 OR ?)
+           ORDER BY "order", name"#,
+    )
+    .bind(include_archived.unwrap_or(false))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read lists: {}", e)))?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, name, icon, color, order, archived)| ListInfo { id, name, icon, color, order, archived })
+        .collect())
+}
+
+/// Only hides the list from the sidebar: its tasks still show in search, smart lists and
+/// everywhere else that doesn't go through `get_lists`
+async fn set_archived(app: &AppHandle, id: &str, archived: bool) -> Result<(), CmdError> {
+    let pool = db::pool(app).await?;
+    store_archived(&pool, id, archived).await?;
+    if let Err(e) = app.emit("list-archived", serde_json::json!({ "id": id, "archived": archived })) {
+        log::error!("[Lists] Failed to emit list-archived: {}", e);
+    }
+    Ok(())
+}
+
+/// Writes the `archived` flag; the Inbox can only be unarchived
+pub(crate) async fn store_archived(pool: &sqlx::SqlitePool, id: &str, archived: bool) -> Result<(), CmdError> {
+    let name = sqlx::query_scalar::<_, String>("SELECT name FROM lists WHERE id = ? AND deleted_at IS NULL")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to look up list '{}': {}", id, e)))?
+        .ok_or_else(|| CmdError::NotFound(format!("List '{}' not found", id)))?;
+    if archived && name == "Inbox" {
+        return Err(CmdError::Validation("The Inbox can't be archived".to_string()));
+    }
+    sqlx::query("UPDATE lists SET archived = ?, updated_at = ? WHERE id = ?")
+        .bind(archived)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to update list '{}': {}", id, e)))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn archive_list(app_handle: AppHandle, id: String) -> Result<(), CmdError> {
+    set_archived(&app_handle, &id, true).await
+}
+
+#[tauri::command]
+pub async fn unarchive_list(app_handle: AppHandle, id: String) -> Result<(), CmdError> {
+    set_archived(&app_handle, &id, false).await
+}
+
+/// Command to rename a list, together with the `list_name` its tasks carry, in one transaction.
+/// Trashed tasks keep `Trash` and pick the new name up when restored. Emits `list-renamed`, and
+/// `tasks-changed` for the tasks
+#[tauri::command]
+pub async fn rename_list(app_handle: AppHandle, id: String, new_name: String) -> Result<(), CmdError> {
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() {
+        return Err(CmdError::Validation("A list needs a name".to_string()));
+    }
+    if new_name.eq_ignore_ascii_case("Trash") || new_name.eq_ignore_ascii_case("Inbox") {
+        return Err(CmdError::Validation(format!("'{}' is reserved", new_name)));
+    }
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| CmdError::Db(format!("Failed to start transaction: {}", e)))?;
+    let name = sqlx::query_scalar::<_, String>("SELECT name FROM lists WHERE id = ? AND deleted_at IS NULL")
+        .bind(&id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to look up list '{}': {}", id, e)))?
+        .ok_or_else(|| CmdError::NotFound(format!("List '{}' not found", id)))?;
+    if name == "Inbox" {
+        return Err(CmdError::Validation("The Inbox can't be renamed".to_string()));
+    }
+    // Tasks are shown under their list by name, so two live lists can't share one
+    let taken = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM lists WHERE name = ? COLLATE NOCASE AND id != ? AND deleted_at IS NULL",
+    )
+    .bind(&new_name)
+    .bind(&id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to look up lists: {}", e)))?
+        > 0;
+    if taken {
+        return Err(CmdError::Validation(format!("A list named '{}' already exists", new_name)));
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    sqlx::query("UPDATE lists SET name = ?, updated_at = ? WHERE id = ?")
+        .bind(&new_name)
+        .bind(now)
+        .bind(&id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to rename list '{}': {}", id, e)))?;
+    let task_ids = sqlx::query_scalar::<_, String>(
+        "UPDATE tasks SET list_name = ?, updated_at = ? WHERE list_id = ? AND list_name != 'Trash' RETURNING id",
+    )
+    .bind(&new_name)
+    .bind(now)
+    .bind(&id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to update the tasks of list '{}': {}", id, e)))?;
+    tx.commit().await.map_err(|e| CmdError::Db(format!("Failed to commit the rename: {}", e)))?;
+
+    log::info!("[Lists] Renamed list '{}' to '{}' ({} tasks)", name, new_name, task_ids.len());
+    if let Err(e) = app_handle.emit("list-renamed", serde_json::json!({ "id": id, "name": new_name })) {
+        log::error!("[Lists] Failed to emit list-renamed: {}", e);
+    }
+    if !task_ids.is_empty()
+        && let Err(e) = app_handle.emit("tasks-changed", serde_json::json!({ "ids": task_ids }))
+    {
+        log::error!("[Lists] Failed to emit tasks-changed: {}", e);
+    }
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use log::LevelFilter;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::error::CmdError;
+use crate::{db, paths, AppState};
+
+/// Settings key for the chosen log level, kept as its lowercase name
+const LOG_LEVEL_KEY: &str = "log_level";
+
+/// Level used until a saved one is restored, and when none was ever chosen
+pub const DEFAULT_LEVEL: LevelFilter = LevelFilter::Info;
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    match level.trim().to_ascii_lowercase().as_str() {
+        "trace" => Ok(LevelFilter::Trace),
+        "debug" => Ok(LevelFilter::Debug),
+        "info" => Ok(LevelFilter::Info),
+        "warn" => Ok(LevelFilter::Warn),
+        "error" => Ok(LevelFilter::Error),
+        _ => Err(format!("Unknown log level '{}'; use trace, debug, info, warn or error", level)),
+    }
+}
+
+/// Makes `level` the effective filter. The log plugin is built to pass everything, so the
+/// `log` crate's max level is the one filter that decides, and it can change at any time
+fn apply(app: &AppHandle, level: LevelFilter) {
+    log::set_max_level(level);
+    if let Ok(mut current) = app.state::<AppState>().log_level.lock() {
+        *current = level;
+    }
+}
+
+/// Applies the saved log level; run at startup
+pub fn restore_log_level(app: &AppHandle) {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        db::get_setting(&pool, LOG_LEVEL_KEY).await
+    });
+    let level = match result {
+        Ok(Some(value)) => parse_level(&value).unwrap_or(DEFAULT_LEVEL),
+        Ok(None) => DEFAULT_LEVEL,
+        Err(e) => {
+            log::warn!("[Logging] Failed to load log_level: {}", e);
+            DEFAULT_LEVEL
+        }
+    };
+    apply(app, level);
+}
+
+/// Command to change the log level without a restart; it is saved and applies on later starts
+#[tauri::command]
+pub async fn set_log_level(app_handle: AppHandle, level: String) -> Result<(), CmdError> {
+    let filter = parse_level(&level).map_err(CmdError::Validation)?;
+    let pool = db::pool(&app_handle).await?;
+    db::set_setting(&pool, LOG_LEVEL_KEY, &filter.as_str().to_ascii_lowercase()).await?;
+    apply(&app_handle, filter);
+    log::info!("[Logging] Log level set to {}", filter);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_log_level(app_handle: AppHandle) -> String {
+    let level = app_handle
+        .state::<AppState>()
+        .log_level
+        .lock()
+        .map(|level| *level)
+        .unwrap_or(DEFAULT_LEVEL);
+    level.as_str().to_ascii_lowercase()
+}
+
+/// Log files in the log directory, with the one currently written to. The plugin writes
+/// `<app name>.log` and renames it aside with a timestamp when it rotates
+fn log_files(app: &AppHandle) -> Result<(Vec<(PathBuf, u64)>, PathBuf), String> {
+    let dir = paths::app_log_dir(app)?;
+    let active = dir.join(format!("{}.log", app.package_info().name));
+    if !dir.exists() {
+        return Ok((Vec::new(), active));
+    }
+    let files = std::fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|extension| extension == "log"))
+        .filter_map(|entry| Some((entry.path(), entry.metadata().ok().filter(|metadata| metadata.is_file())?.len())))
+        .collect();
+    Ok((files, active))
+}
+
+/// Command for the total size of the log files, in bytes
+#[tauri::command]
+pub fn get_log_size(app_handle: AppHandle) -> Result<u64, CmdError> {
+    let (files, _) = log_files(&app_handle)?;
+    Ok(files.iter().map(|(_, size)| size).sum())
+}
+
+/// Command to delete rotated log files; the active one is always kept. Returns bytes freed
+#[tauri::command]
+pub fn clear_logs(app_handle: AppHandle) -> Result<u64, CmdError> {
+    let (files, active) = log_files(&app_handle)?;
+    let mut freed = 0;
+    for (path, size) in files {
+        if path == active {
+            continue;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => freed += size,
+            Err(e) => log::warn!("[Logging] Failed to remove {}: {}", path.display(), e),
+        }
+    }
+    log::info!("[Logging] Cleared old logs, {} bytes freed", freed);
+    Ok(freed)
+}
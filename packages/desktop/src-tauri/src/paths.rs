@@ -0,0 +1,144 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::db;
+use crate::error::CmdError;
+
+/// Environment variable naming a folder to keep the database, logs, backups and attachments in
+/// instead of the platform's app directories
+const DATA_DIR_ENV: &str = "TADA_DATA_DIR";
+
+/// Launch flag doing the same as `TADA_DATA_DIR`, e.g. `tada --data-dir D:\Tada`; wins over it
+const DATA_DIR_FLAG: &str = "--data-dir";
+
+/// Launch flag for portable mode: everything is kept in `data` beside the executable
+const PORTABLE_FLAG: &str = "--portable";
+
+/// The folder asked for at launch, if any, or why it can't be used; resolved once by `init`
+static DATA_DIR: OnceLock<Result<Option<PathBuf>, String>> = OnceLock::new();
+
+/// Finds the data folder asked for in launch arguments (the first is the executable) or the
+/// environment. Relative paths are taken from the working directory
+fn requested(args: &[String], env: Option<String>) -> Result<Option<PathBuf>, String> {
+    let mut flag = None;
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        if arg == PORTABLE_FLAG {
+            let exe = std::env::current_exe().map_err(|e| format!("Failed to locate the executable: {}", e))?;
+            flag = exe.parent().map(|dir| dir.join("data"));
+        } else if arg == DATA_DIR_FLAG {
+            flag = Some(PathBuf::from(iter.next().ok_or_else(|| format!("{} needs a folder", DATA_DIR_FLAG))?));
+        } else if let Some(value) = arg.strip_prefix(DATA_DIR_FLAG).and_then(|rest| rest.strip_prefix('=')) {
+            flag = Some(PathBuf::from(value));
+        }
+    }
+    let Some(dir) = flag.or_else(|| env.filter(|value| !value.trim().is_empty()).map(PathBuf::from)) else {
+        return Ok(None);
+    };
+    std::path::absolute(&dir)
+        .map(Some)
+        .map_err(|e| format!("Failed to resolve data folder {}: {}", dir.display(), e))
+}
+
+/// Creates `dir` if needed and checks a file can be written and removed in it
+fn ensure_writable(dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Can't create data folder {}: {}", dir.display(), e))?;
+    let probe = dir.join(".tada-write-test");
+    std::fs::write(&probe, b"").map_err(|e| format!("Data folder {} isn't writable: {}", dir.display(), e))?;
+    std::fs::remove_file(&probe).map_err(|e| format!("Data folder {} isn't writable: {}", dir.display(), e))
+}
+
+/// Resolves and checks the data folder from the launch arguments and `TADA_DATA_DIR`, then
+/// points the sql plugin's preload at the database in it. Runs before the builder, so the
+/// plugins that open files at setup find the right paths. When the folder can't be used
+/// nothing is preloaded, and `check_on_startup` reports it
+pub fn init(context: &mut tauri::Context) {
+    let args: Vec<String> = std::env::args().collect();
+    let resolved = requested(&args, std::env::var(DATA_DIR_ENV).ok())
+        .and_then(|dir| dir.map(|dir| ensure_writable(&dir).map(|_| dir)).transpose());
+    let _ = DATA_DIR.set(resolved);
+    let preload = match DATA_DIR.get() {
+        Some(Ok(Some(_))) => vec![db::preload_url()],
+        Some(Err(_)) => Vec::new(),
+        _ => return,
+    };
+    if let Some(sql) = context.config_mut().plugins.0.get_mut("sql").and_then(|sql| sql.as_object_mut()) {
+        sql.insert("preload".to_string(), serde_json::json!(preload));
+    }
+}
+
+/// The folder given with `--data-dir`, `--portable` or `TADA_DATA_DIR`, once it checked out
+pub fn data_dir_override() -> Option<&'static Path> {
+    match DATA_DIR.get() {
+        Some(Ok(Some(dir))) => Some(dir.as_path()),
+        _ => None,
+    }
+}
+
+/// Where the log plugin writes: `logs` in the data folder, or the platform log directory
+pub fn log_target() -> tauri_plugin_log::TargetKind {
+    match data_dir_override() {
+        Some(dir) => tauri_plugin_log::TargetKind::Folder { path: dir.join("logs"), file_name: None },
+        None => tauri_plugin_log::TargetKind::LogDir { file_name: None },
+    }
+}
+
+/// Reports the data folder in use, or shows why the one asked for can't be and quits. First in
+/// `setup`, before anything reaches for the database
+pub fn check_on_startup(app: &AppHandle) {
+    match DATA_DIR.get() {
+        Some(Err(e)) => {
+            log::error!("[Paths] {}", e);
+            let message = format!("Tada can't use the data folder it was started with.\n\n{}", e);
+            app.dialog().message(message).title("Tada").kind(MessageDialogKind::Error).blocking_show();
+            std::process::exit(1);
+        }
+        Some(Ok(Some(dir))) => log::info!("[Paths] Keeping data in {}", dir.display()),
+        _ => {}
+    }
+}
+
+/// Directory `tada.db` and the device id are kept in
+pub fn app_config_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    match data_dir_override() {
+        Some(dir) => Ok(dir.to_path_buf()),
+        None => app.path().app_config_dir().map_err(|e| format!("Failed to resolve app config directory: {}", e)),
+    }
+}
+
+/// Directory backups, sync snapshots and attachments are kept under
+pub fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    match data_dir_override() {
+        Some(dir) => Ok(dir.to_path_buf()),
+        None => app.path().app_data_dir().map_err(|e| format!("Failed to resolve app data directory: {}", e)),
+    }
+}
+
+/// Directory the log plugin writes to, see `log_target`
+pub fn app_log_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    match data_dir_override() {
+        Some(dir) => Ok(dir.join("logs")),
+        None => app.path().app_log_dir().map_err(|e| format!("Failed to resolve log directory: {}", e)),
+    }
+}
+
+/// Command to show `tada.db` in the platform file manager, selected where it supports that, for
+/// backing it up by hand. Opens the folder instead when the file can't be revealed. Returns the
+/// file's absolute path for the UI to show
+#[tauri::command]
+pub async fn open_database_location(app_handle: AppHandle) -> Result<String, CmdError> {
+    let db_path = db::db_path(&app_handle)?;
+    let shown = db_path.display().to_string();
+    if let Err(e) = app_handle.opener().reveal_item_in_dir(&db_path) {
+        log::warn!("[Paths] Failed to reveal {}, opening its folder: {}", shown, e);
+        let dir = db_path.parent().ok_or_else(|| format!("{} has no parent folder", shown))?;
+        app_handle
+            .opener()
+            .open_path(dir.to_string_lossy(), None::<&str>)
+            .map_err(|e| CmdError::Io(format!("Failed to open {}: {}", dir.display(), e)))?;
+    }
+    Ok(shown)
+}
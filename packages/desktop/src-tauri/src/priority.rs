@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::db;
+use crate::error::CmdError;
+
+/// A task's priority. Stored in `tasks.priority` and sent to the frontend as its numbers:
+/// 1 (high) to 3 (low), with `null` for none
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Option<i64>", into = "Option<i64>")]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    pub fn to_db(self) -> Option<i64> {
+        match self {
+            Priority::None => None,
+            Priority::High => Some(1),
+            Priority::Medium => Some(2),
+            Priority::Low => Some(3),
+        }
+    }
+
+    /// The valid priority closest to a stored value that may be out of range, e.g. from an
+    /// older import: below 1 is high, above 3 low
+    pub fn nearest(value: Option<i64>) -> Self {
+        match value {
+            None => Priority::None,
+            Some(..=1) => Priority::High,
+            Some(2) => Priority::Medium,
+            Some(_) => Priority::Low,
+        }
+    }
+}
+
+impl TryFrom<Option<i64>> for Priority {
+    type Error = String;
+
+    fn try_from(value: Option<i64>) -> Result<Self, Self::Error> {
+        match value {
+            None => Ok(Priority::None),
+            Some(1) => Ok(Priority::High),
+            Some(2) => Ok(Priority::Medium),
+            Some(3) => Ok(Priority::Low),
+            Some(other) => Err(format!("Invalid priority {}; use 1 (high), 2 (medium), 3 (low) or null", other)),
+        }
+    }
+}
+
+impl From<Priority> for Option<i64> {
+    fn from(priority: Priority) -> Self {
+        priority.to_db()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityTask {
+    pub id: String,
+    pub title: String,
+    pub list_name: String,
+    pub completed: bool,
+    pub due_date: Option<i64>,
+}
+
+/// Command listing the live tasks with a priority, open ones first, in list order
+#[tauri::command]
+pub async fn tasks_by_priority(app_handle: AppHandle, priority: Priority) -> Result<Vec<PriorityTask>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let rows = sqlx::query_as::<_, (String, String, String, bool, Option<i64>)>(
+        r#"SELECT id, title, list_name, completed, due_date FROM tasks
+           WHERE priority IS ? AND deleted_at IS NULL AND list_name != 'Trash'
+           ORDER BY completed, pinned DESC, "order""#,
+    )
+    .bind(priority.to_db())
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read tasks by priority: {}", e)))?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, title, list_name, completed, due_date)| PriorityTask { id, title, list_name, completed, due_date })
+        .collect())
+}
@@ -0,0 +1,438 @@
+use chrono::{
+    DateTime, Datelike, Days, Local, Months, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday,
+};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::{db, tasks};
+use crate::error::CmdError;
+
+/// Upper bound on periods scanned, so a rule that never matches again can't spin forever
+const MAX_PERIODS: u32 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// The subset of an RFC 5545 RRULE that tasks use
+#[derive(Debug, Clone)]
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    by_day: Vec<Weekday>,
+    by_month_day: Vec<i32>,
+    until: Option<i64>,
+    count: Option<u32>,
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    Ok(match value {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return Err(format!("Invalid BYDAY value '{}'", value)),
+    })
+}
+
+/// `UNTIL` as `YYYYMMDD` (through the end of that local day), `YYYYMMDDTHHMMSS` (local)
+/// or `YYYYMMDDTHHMMSSZ` (UTC)
+fn parse_until(value: &str) -> Result<i64, String> {
+    let invalid = || format!("Invalid UNTIL value '{}'", value);
+    let local_millis = |naive: NaiveDateTime| {
+        Local
+            .from_local_datetime(&naive)
+            .latest()
+            .map(|date_time| date_time.timestamp_millis())
+            .ok_or_else(invalid)
+    };
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+        return Ok(Utc.from_utc_datetime(&naive).timestamp_millis());
+    }
+    if value.contains('T') {
+        let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+        return local_millis(naive);
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| invalid())?;
+    local_millis(date.and_hms_opt(23, 59, 59).ok_or_else(invalid)?)
+}
+
+fn parse_rule(rule: &str) -> Result<Rule, String> {
+    let mut frequency = None;
+    let mut parsed = Rule {
+        frequency: Frequency::Daily,
+        interval: 1,
+        by_day: Vec::new(),
+        by_month_day: Vec::new(),
+        until: None,
+        count: None,
+    };
+    let rule = rule.trim();
+    let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+    for part in rule.split(';').filter(|part| !part.is_empty()) {
+        let (name, value) = part
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid recurrence part '{}'", part))?;
+        match name.to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                frequency = Some(match value.to_ascii_uppercase().as_str() {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    _ => return Err(format!("Unsupported FREQ '{}'", value)),
+                })
+            }
+            "INTERVAL" => {
+                parsed.interval = value
+                    .parse()
+                    .ok()
+                    .filter(|&interval| interval > 0)
+                    .ok_or_else(|| format!("Invalid INTERVAL '{}'", value))?
+            }
+            "BYDAY" => {
+                parsed.by_day = value
+                    .split(',')
+                    .map(|day| parse_weekday(&day.to_ascii_uppercase()))
+                    .collect::<Result<_, _>>()?
+            }
+            "BYMONTHDAY" => {
+                parsed.by_month_day = value
+                    .split(',')
+                    .map(|day| {
+                        day.parse::<i32>()
+                            .ok()
+                            .filter(|day| (1..=31).contains(&day.abs()))
+                            .ok_or_else(|| format!("Invalid BYMONTHDAY value '{}'", day))
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+            "UNTIL" => parsed.until = Some(parse_until(value)?),
+            "COUNT" => {
+                parsed.count = Some(value.parse().map_err(|_| format!("Invalid COUNT '{}'", value))?)
+            }
+            // WKST and friends don't change anything for the rules tasks use
+            _ => {}
+        }
+    }
+    parsed.frequency = frequency.ok_or("Recurrence rule has no FREQ")?;
+    parsed.by_day.sort_by_key(|day| day.num_days_from_monday());
+    Ok(parsed)
+}
+
+fn last_day_of_month(year: i32, month: u32) -> u32 {
+    let first_next = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    };
+    first_next.and_then(|date| date.pred_opt()).map_or(28, |date| date.day())
+}
+
+/// Candidate dates of the `period`-th period after the anchor, in order. Days that don't
+/// exist in a period (the 31st in April, Feb 29 outside leap years) are skipped, as RFC 5545
+/// does
+fn period_dates(rule: &Rule, anchor: NaiveDate, period: u32) -> Vec<NaiveDate> {
+    let step = period * rule.interval;
+    match rule.frequency {
+        Frequency::Daily => anchor.checked_add_days(Days::new(step as u64)).into_iter().collect(),
+        Frequency::Weekly => {
+            let offset = step as u64 * 7;
+            if rule.by_day.is_empty() {
+                return anchor.checked_add_days(Days::new(offset)).into_iter().collect();
+            }
+            let week_start = anchor - Days::new(anchor.weekday().num_days_from_monday() as u64);
+            rule.by_day
+                .iter()
+                .filter_map(|day| {
+                    week_start.checked_add_days(Days::new(offset + day.num_days_from_monday() as u64))
+                })
+                .collect()
+        }
+        Frequency::Monthly => {
+            let Some(month) = anchor.with_day(1).and_then(|first| first.checked_add_months(Months::new(step)))
+            else {
+                return Vec::new();
+            };
+            let last_day = last_day_of_month(month.year(), month.month()) as i32;
+            let days = if rule.by_month_day.is_empty() {
+                vec![anchor.day() as i32]
+            } else {
+                rule.by_month_day.clone()
+            };
+            let mut dates: Vec<NaiveDate> = days
+                .into_iter()
+                .map(|day| if day < 0 { last_day + day + 1 } else { day })
+                .filter(|&day| day >= 1 && day <= last_day)
+                .filter_map(|day| month.with_day(day as u32))
+                .collect();
+            dates.sort();
+            dates.dedup();
+            dates
+        }
+        Frequency::Yearly => {
+            let year = anchor.year() + step as i32;
+            NaiveDate::from_ymd_opt(year, anchor.month(), anchor.day()).into_iter().collect()
+        }
+    }
+}
+
+/// The next `count` occurrences (epoch millis) of `rule` for a series whose current
+/// occurrence is `after`. `after` counts toward `COUNT`, so `COUNT=3` yields at most 2 more.
+/// Times of day are kept in local time across DST changes; an invalid rule yields nothing
+pub fn next_occurrences(rule: &str, after: i64, count: usize) -> Vec<i64> {
+    let rule = match parse_rule(rule) {
+        Ok(rule) => rule,
+        Err(e) => {
+            log::warn!("[Recurrence] {}", e);
+            return Vec::new();
+        }
+    };
+    let Some(anchor) = DateTime::from_timestamp_millis(after).map(|date_time| date_time.with_timezone(&Local))
+    else {
+        return Vec::new();
+    };
+    let remaining = rule.count.map_or(count, |total| count.min(total.saturating_sub(1) as usize));
+    let time = anchor.time();
+
+    let mut occurrences = Vec::new();
+    for period in 0..MAX_PERIODS {
+        for date in period_dates(&rule, anchor.date_naive(), period) {
+            if occurrences.len() >= remaining {
+                return occurrences;
+            }
+            // A time skipped by a DST jump has no local equivalent; that occurrence is dropped
+            let Some(occurrence) = Local.from_local_datetime(&date.and_time(time)).earliest() else {
+                continue;
+            };
+            let millis = occurrence.timestamp_millis();
+            if millis <= after {
+                continue;
+            }
+            if rule.until.is_some_and(|until| millis > until) {
+                return occurrences;
+            }
+            occurrences.push(millis);
+        }
+        if occurrences.len() >= remaining {
+            break;
+        }
+    }
+    occurrences
+}
+
+/// The rule carried over to the next instance: one fewer occurrence left when it has a `COUNT`
+fn advance_rule(rule: &str) -> String {
+    rule.split(';')
+        .map(|part| match part.split_once('=') {
+            Some((name, value)) if name.eq_ignore_ascii_case("COUNT") => match value.parse::<u32>() {
+                Ok(count) => format!("COUNT={}", count.saturating_sub(1)),
+                Err(_) => part.to_string(),
+            },
+            _ => part.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// A new instance of a recurring task, in the frontend's `Task` shape
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringTask {
+    pub id: String,
+    pub title: String,
+    pub completed: bool,
+    pub completed_at: Option<i64>,
+    pub complete_percentage: Option<i64>,
+    pub due_date: i64,
+    pub list_id: Option<String>,
+    pub list_name: String,
+    pub content: Option<String>,
+    pub order: f64,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub tags: Vec<String>,
+    pub priority: Option<i64>,
+    pub group_category: String,
+    pub recurrence: String,
+    pub subtasks: Vec<serde_json::Value>,
+}
+
+type CompletedRow = (
+    String,
+    String,
+    Option<String>,
+    String,
+    Option<String>,
+    f64,
+    Option<String>,
+    Option<i64>,
+    Option<i64>,
+    Option<i64>,
+    String,
+);
+
+/// Command run after completing tasks: each completed task with a recurrence gets its next
+/// instance, and hands its rule over to it so it is only materialized once. Returns the new tasks
+#[tauri::command]
+pub async fn materialize_recurring_tasks(app_handle: AppHandle) -> Result<Vec<RecurringTask>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let rows = sqlx::query_as::<_, CompletedRow>(
+        r#"SELECT id, title, list_id, list_name, content, CAST("order" AS REAL), tags, priority, due_date, completed_at,
+                  recurrence
+           FROM tasks
+           WHERE completed = 1 AND recurrence IS NOT NULL AND recurrence != '' AND list_name != 'Trash'
+             AND deleted_at IS NULL"#,
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read recurring tasks: {}", e)))?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut created = Vec::new();
+    for (index, row) in rows.into_iter().enumerate() {
+        let (id, title, list_id, list_name, content, order, tags, priority, due_date, completed_at, rule) = row;
+        let anchor = due_date.or(completed_at).unwrap_or(now);
+        let next = next_occurrences(&rule, anchor, 1).first().copied();
+
+        let mut tx = pool.begin().await.map_err(|e| CmdError::Db(format!("Failed to start transaction: {}", e)))?;
+        let task = match next {
+            Some(due_date) => {
+                let today = Local::now().date_naive();
+                let group_category = tasks::group_category(false, &list_name, Some(due_date), today);
+                let task = RecurringTask {
+                    id: format!("task-{}-recur{}", now, index),
+                    title,
+                    completed: false,
+                    completed_at: None,
+                    complete_percentage: None,
+                    due_date,
+                    list_id,
+                    list_name,
+                    content,
+                    order,
+                    created_at: now,
+                    updated_at: now,
+                    tags: tags.and_then(|tags| serde_json::from_str(&tags).ok()).unwrap_or_default(),
+                    priority,
+                    group_category: group_category.to_string(),
+                    recurrence: advance_rule(&rule),
+                    subtasks: Vec::new(),
+                };
+                sqlx::query(
+                    r#"INSERT INTO tasks (id, title, completed, due_date, list_id, list_name, content, "order", created_at, updated_at, tags, priority, group_category, recurrence)
+                       VALUES (?, ?, 0, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                )
+                .bind(&task.id)
+                .bind(&task.title)
+                .bind(task.due_date)
+                .bind(&task.list_id)
+                .bind(&task.list_name)
+                .bind(&task.content)
+                .bind(task.order)
+                .bind(now)
+                .bind(now)
+                .bind(serde_json::to_string(&task.tags).unwrap_or_else(|_| "[]".to_string()))
+                .bind(task.priority)
+                .bind(&task.group_category)
+                .bind(&task.recurrence)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CmdError::Db(format!("Failed to create the next instance of task '{}': {}", id, e)))?;
+                Some(task)
+            }
+            None => None,
+        };
+        // Either handed over to the new instance or the series has ended
+        sqlx::query("UPDATE tasks SET recurrence = NULL, updated_at = ? WHERE id = ?")
+            .bind(now)
+            .bind(&id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CmdError::Db(format!("Failed to update recurring task '{}': {}", id, e)))?;
+        tx.commit().await.map_err(|e| CmdError::Db(format!("Failed to commit transaction: {}", e)))?;
+
+        match task {
+            Some(task) => {
+                log::info!("[Recurrence] Task {} repeats as {}", id, task.id);
+                created.push(task);
+            }
+            None => log::info!("[Recurrence] Task {} has no further occurrences", id),
+        }
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Noon local time, clear of DST transitions
+    fn at(year: i32, month: u32, day: u32) -> i64 {
+        Local.with_ymd_and_hms(year, month, day, 12, 0, 0).earliest().unwrap().timestamp_millis()
+    }
+
+    #[test]
+    fn daily() {
+        let from = at(2025, 1, 30);
+        assert_eq!(next_occurrences("FREQ=DAILY", from, 3), vec![at(2025, 1, 31), at(2025, 2, 1), at(2025, 2, 2)]);
+        assert_eq!(next_occurrences("RRULE:FREQ=DAILY;INTERVAL=2", from, 2), vec![at(2025, 2, 1), at(2025, 2, 3)]);
+    }
+
+    #[test]
+    fn weekly_by_day() {
+        // 2025-01-01 is a Wednesday
+        let from = at(2025, 1, 1);
+        assert_eq!(
+            next_occurrences("FREQ=WEEKLY;BYDAY=FR,MO,WE", from, 4),
+            vec![at(2025, 1, 3), at(2025, 1, 6), at(2025, 1, 8), at(2025, 1, 10)]
+        );
+    }
+
+    #[test]
+    fn monthly_on_the_31st_skips_short_months() {
+        assert_eq!(
+            next_occurrences("FREQ=MONTHLY", at(2025, 1, 31), 3),
+            vec![at(2025, 3, 31), at(2025, 5, 31), at(2025, 7, 31)]
+        );
+        assert_eq!(
+            next_occurrences("FREQ=MONTHLY;BYMONTHDAY=-1", at(2025, 1, 31), 2),
+            vec![at(2025, 2, 28), at(2025, 3, 31)]
+        );
+    }
+
+    #[test]
+    fn stops_at_until() {
+        assert_eq!(
+            next_occurrences("FREQ=DAILY;UNTIL=20250103", at(2025, 1, 1), 10),
+            vec![at(2025, 1, 2), at(2025, 1, 3)]
+        );
+    }
+
+    #[test]
+    fn current_occurrence_counts_toward_count() {
+        assert_eq!(next_occurrences("FREQ=DAILY;COUNT=3", at(2025, 1, 1), 10), vec![at(2025, 1, 2), at(2025, 1, 3)]);
+        assert!(next_occurrences("FREQ=DAILY;COUNT=1", at(2025, 1, 1), 10).is_empty());
+    }
+
+    #[test]
+    fn invalid_rule_yields_nothing() {
+        assert!(next_occurrences("INTERVAL=2", at(2025, 1, 1), 1).is_empty());
+        assert!(next_occurrences("FREQ=HOURLY", at(2025, 1, 1), 1).is_empty());
+    }
+
+    #[test]
+    fn advance_rule_decrements_count() {
+        assert_eq!(advance_rule("FREQ=DAILY;COUNT=3"), "FREQ=DAILY;COUNT=2");
+        assert_eq!(advance_rule("FREQ=WEEKLY;count=1;BYDAY=MO"), "FREQ=WEEKLY;COUNT=0;BYDAY=MO");
+        assert_eq!(advance_rule("FREQ=DAILY;INTERVAL=2"), "FREQ=DAILY;INTERVAL=2");
+    }
+}
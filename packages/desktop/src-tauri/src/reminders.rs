@@ -0,0 +1,196 @@
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::error::CmdError;
+use crate::{db, dnd, AppState};
+
+/// How often the checker looks for due reminders
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Focusing the main window this soon after a reminder counts as clicking it. Desktop
+/// notifications don't report clicks, but clicking one brings the app to the front
+const CLICK_WINDOW: Duration = Duration::from_secs(2 * 60);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reminder {
+    pub id: i64,
+    pub task_id: String,
+    pub remind_at: i64,
+    pub delivered: bool,
+    pub created_at: i64,
+}
+
+/// Command to add a reminder for a task at `remind_at` (epoch millis)
+#[tauri::command]
+pub async fn set_reminder(app_handle: AppHandle, task_id: String, remind_at: i64) -> Result<Reminder, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let id = sqlx::query("INSERT INTO reminders (task_id, remind_at, delivered, created_at) VALUES (?, ?, 0, ?)")
+        .bind(&task_id)
+        .bind(remind_at)
+        .bind(now)
+        .execute(&pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to set reminder: {}", e)))?
+        .last_insert_rowid();
+    log::info!("[Reminders] Reminder {} set for task {}", id, task_id);
+    Ok(Reminder { id, task_id, remind_at, delivered: false, created_at: now })
+}
+
+#[tauri::command]
+pub async fn clear_reminder(app_handle: AppHandle, reminder_id: i64) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query("DELETE FROM reminders WHERE id = ?")
+        .bind(reminder_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to clear reminder: {}", e)))?;
+    Ok(())
+}
+
+/// Shows a notification for every due reminder and marks it delivered. Reminders of tasks
+/// completed in the meantime are marked without a notification. While the system is in
+/// do-not-disturb or quiet hours, or the user is away, reminders stay undelivered, so they go
+/// out on the first check after it ends, even across a restart
+async fn deliver_due(app: &AppHandle) -> Result<(), String> {
+    let pool = db::pool(app).await?;
+    // The cascade only runs with foreign keys enforced, so orphans are swept here as well
+    sqlx::query("DELETE FROM reminders WHERE task_id NOT IN (SELECT id FROM tasks)")
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to clean up reminders: {}", e))?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let due = sqlx::query_as::<_, (i64, String, String, bool)>(
+        "SELECT r.id, t.id, t.title, t.completed FROM reminders r JOIN tasks t ON t.id = r.task_id
+         WHERE r.delivered = 0 AND r.remind_at <= ? AND t.deleted_at IS NULL ORDER BY r.remind_at",
+    )
+    .bind(now)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to read due reminders: {}", e))?;
+
+    let quiet = if due.iter().any(|(_, _, _, completed)| !completed) {
+        dnd::quiet_reason(app).await?
+    } else {
+        None
+    };
+    let state = app.state::<AppState>();
+    let mut held_while_away = Vec::new();
+    for (id, task_id, title, completed) in due {
+        if !completed {
+            if let Some(reason) = quiet {
+                defer(app, id, &task_id, reason);
+                continue;
+            }
+            let deferred = state.deferred_reminders.lock().ok().and_then(|mut deferred| deferred.remove(&id));
+            if deferred == Some(dnd::QuietReason::Idle) {
+                held_while_away.push((task_id, title));
+            } else {
+                log::info!(
+                    "[Reminders] Delivering {}reminder {} for task {}",
+                    if deferred.is_some() { "deferred " } else { "" },
+                    id,
+                    task_id
+                );
+                show(app, "Tada reminder", &title);
+                if let Ok(mut last) = state.last_reminder.lock() {
+                    *last = Some((task_id, Instant::now()));
+                }
+            }
+        }
+        sqlx::query("UPDATE reminders SET delivered = 1 WHERE id = ?")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|e| format!("Failed to mark reminder {} delivered: {}", id, e))?;
+    }
+
+    // What piled up while the user was away goes out as one notification on their return
+    match held_while_away.len() {
+        0 => {}
+        1 => {
+            let (task_id, title) = held_while_away.remove(0);
+            log::info!("[Reminders] Delivering the reminder for task {} held while away", task_id);
+            show(app, "Tada reminder", &title);
+            if let Ok(mut last) = state.last_reminder.lock() {
+                *last = Some((task_id, Instant::now()));
+            }
+        }
+        count => {
+            log::info!("[Reminders] Delivering {} reminders held while away as one", count);
+            let titles: Vec<&str> = held_while_away.iter().map(|(_, title)| title.as_str()).collect();
+            show(app, &format!("You have {} pending reminders", count), &titles.join(", "));
+            // No single task to open when it is clicked
+            if let Ok(mut last) = state.last_reminder.lock() {
+                *last = None;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn show(app: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("[Reminders] Failed to show notification: {}", e);
+    }
+}
+
+/// Holds a reminder back; `notification-deferred` goes out once per reminder, not on every check
+fn defer(app: &AppHandle, id: i64, task_id: &str, reason: dnd::QuietReason) {
+    let first = app
+        .state::<AppState>()
+        .deferred_reminders
+        .lock()
+        .map(|mut deferred| {
+            // The first reason sticks, so what was held while away is still coalesced later
+            let first = !deferred.contains_key(&id);
+            deferred.entry(id).or_insert(reason);
+            first
+        })
+        .unwrap_or(false);
+    if !first {
+        return;
+    }
+    log::info!("[Reminders] Deferring reminder {} for task {}: {:?}", id, task_id, reason);
+    let payload = serde_json::json!({ "reminderId": id, "taskId": task_id, "reason": reason });
+    if let Err(e) = app.emit("notification-deferred", payload) {
+        log::error!("[Reminders] Failed to emit notification-deferred: {}", e);
+    }
+}
+
+/// Checks for due reminders right away, to catch the ones missed while the app was closed,
+/// and then every minute
+pub fn start_reminder_checker(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        log::info!("[Reminders] Reminder checker started");
+        loop {
+            if let Err(e) = deliver_due(&app).await {
+                log::warn!("[Reminders] {}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Called when the main window gains focus: right after a reminder, that is most likely a
+/// click on its notification, so the task is opened
+pub fn on_main_focused(app: &AppHandle) {
+    let last = match app.state::<AppState>().last_reminder.lock() {
+        Ok(mut last) => last.take(),
+        Err(_) => return,
+    };
+    let Some((task_id, shown_at)) = last else {
+        return;
+    };
+    if shown_at.elapsed() > CLICK_WINDOW {
+        return;
+    }
+    log::info!("[Reminders] Opening task {} from its reminder", task_id);
+    if let Err(e) = app.emit("open-task", task_id) {
+        log::error!("[Reminders] Failed to emit open-task: {}", e);
+    }
+}
@@ -0,0 +1,165 @@
+use chrono::{Local, NaiveDate, TimeZone};
+use tauri::{AppHandle, Emitter};
+
+use crate::db;
+use crate::error::CmdError;
+use crate::tray::local_midnight_millis;
+
+/// Settings key for whether overdue tasks move to today at midnight, as a JSON bool
+const AUTO_ROLLOVER_KEY: &str = "auto_rollover";
+
+/// Settings key for the local `YYYY-MM-DD` the rollover last ran on
+const LAST_ROLLOVER_KEY: &str = "auto_rollover_last";
+
+async fn enabled(pool: &sqlx::SqlitePool) -> Result<bool, String> {
+    Ok(db::get_setting(pool, AUTO_ROLLOVER_KEY)
+        .await?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or(false))
+}
+
+/// `due_date` moved onto local day `today`, keeping its local time of day
+fn on_day(due_date: i64, today: NaiveDate) -> i64 {
+    Local
+        .timestamp_millis_opt(due_date)
+        .single()
+        .and_then(|due| today.and_time(due.time()).and_local_timezone(Local).earliest())
+        .map_or_else(|| local_midnight_millis(today), |due| due.timestamp_millis())
+}
+
+/// Moves open tasks due before today to the same time today, remembering the first due date
+/// they had in `rolled_over_from`. Returns their ids
+async fn roll_over(pool: &sqlx::SqlitePool) -> Result<Vec<String>, String> {
+    let today = Local::now().date_naive();
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut tx = pool.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let overdue = sqlx::query_as::<_, (String, i64)>(
+        "SELECT id, due_date FROM tasks
+         WHERE completed = 0 AND deleted_at IS NULL AND list_name != 'Trash' AND due_date < ?",
+    )
+    .bind(local_midnight_millis(today))
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to read overdue tasks: {}", e))?;
+    for (id, due_date) in &overdue {
+        sqlx::query(
+            "UPDATE tasks SET rolled_over_from = COALESCE(rolled_over_from, due_date), due_date = ?,
+                              group_category = 'today', updated_at = ?
+             WHERE id = ?",
+        )
+        .bind(on_day(*due_date, today))
+        .bind(now)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to roll over task '{}': {}", id, e))?;
+    }
+    tx.commit().await.map_err(|e| format!("Failed to commit the rollover: {}", e))?;
+    Ok(overdue.into_iter().map(|(id, _)| id).collect())
+}
+
+fn emit_rolled_over(app: &AppHandle, ids: &[String]) {
+    log::info!("[Rollover] Moved {} overdue tasks to today", ids.len());
+    if let Err(e) = app.emit("tasks-rolled-over", serde_json::json!({ "ids": ids })) {
+        log::error!("[Rollover] Failed to emit tasks-rolled-over: {}", e);
+    }
+}
+
+/// Rolls overdue tasks over once per local day when `auto_rollover` is on; run from the
+/// scheduler thread, so it happens on the first check after midnight
+pub fn run_due_rollover(app: &AppHandle) {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        if !enabled(&pool).await? {
+            return Ok(Vec::new());
+        }
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        if db::get_setting(&pool, LAST_ROLLOVER_KEY).await?.as_deref() == Some(today.as_str()) {
+            return Ok(Vec::new());
+        }
+        let ids = roll_over(&pool).await?;
+        db::set_setting(&pool, LAST_ROLLOVER_KEY, &today).await?;
+        Ok::<_, String>(ids)
+    });
+    match result {
+        Ok(ids) if ids.is_empty() => {}
+        Ok(ids) => emit_rolled_over(app, &ids),
+        Err(e) => log::warn!("[Rollover] {}", e),
+    }
+}
+
+#[tauri::command]
+pub async fn get_auto_rollover(app_handle: AppHandle) -> Result<bool, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    Ok(enabled(&pool).await?)
+}
+
+#[tauri::command]
+pub async fn set_auto_rollover(app_handle: AppHandle, enabled: bool) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    db::set_setting(&pool, AUTO_ROLLOVER_KEY, &enabled.to_string()).await.map_err(CmdError::Db)
+}
+
+/// Command to put rolled-over tasks back on their original due dates: the given ones, or all
+/// of them. Tasks completed since keep the date they were completed under
+#[tauri::command]
+pub async fn undo_rollover(app_handle: AppHandle, ids: Option<Vec<String>>) -> Result<Vec<String>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let filter = match &ids {
+        Some(ids) => format!(" AND id IN ({})", vec!["?"; ids.len()].join(", ")),
+        None => String::new(),
+    };
+    // Back before today, so they are overdue again
+    let sql = format!(
+        "UPDATE tasks SET due_date = rolled_over_from, rolled_over_from = NULL, group_category = 'overdue', updated_at = ?
+         WHERE rolled_over_from IS NOT NULL AND completed = 0{}
+         RETURNING id",
+        filter
+    );
+    let mut query = sqlx::query_scalar::<_, String>(&sql).bind(now);
+    for id in ids.iter().flatten() {
+        query = query.bind(id);
+    }
+    let restored = query
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to undo the rollover: {}", e)))?;
+    log::info!("[Rollover] Restored the due dates of {} tasks", restored.len());
+    if let Err(e) = app_handle.emit("tasks-changed", serde_json::json!({ "ids": restored })) {
+        log::error!("[Rollover] Failed to emit tasks-changed: {}", e);
+    }
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rollover_keeps_the_time_of_day() {
+        tauri::async_runtime::block_on(async {
+            let pool = db::test_pool().await;
+            let today = Local::now().date_naive();
+            let at = |day: NaiveDate| day.and_hms_opt(15, 30, 0).unwrap().and_local_timezone(Local).earliest().unwrap();
+            let due = at(today - chrono::Days::new(3)).timestamp_millis();
+            sqlx::query(
+                r#"INSERT INTO tasks (id, title, list_id, list_name, "order", created_at, updated_at, due_date)
+                   VALUES ('late', 'Task', 'inbox-default', 'Inbox', 0, 0, 0, ?)"#,
+            )
+            .bind(due)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            assert_eq!(roll_over(&pool).await.unwrap(), ["late"]);
+            let (due_date, from, group) = sqlx::query_as::<_, (i64, i64, String)>(
+                "SELECT due_date, rolled_over_from, group_category FROM tasks WHERE id = 'late'",
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!((due_date, from, group.as_str()), (at(today).timestamp_millis(), due, "today"));
+        });
+    }
+}
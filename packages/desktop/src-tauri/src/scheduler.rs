@@ -0,0 +1,774 @@
+use crate::error::CmdError;
+use crate::{backup, db, integrity, rollover, tasks, trash, tray, vacuum, window, AppState};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+/// Settings key holding the persisted `ScheduleSettings` JSON
+const SCHEDULE_SETTINGS_KEY: &str = "schedule";
+
+/// Settings key holding, per schedule (kind and list), the `YYYY-MM-DD HH:mm` occurrence last emitted
+const LAST_TRIGGERED_KEY: &str = "schedule_last_triggered";
+
+/// Re-check interval while suppressed triggers wait for their quiet range to end
+const DEFERRED_RECHECK: Duration = Duration::from_secs(60);
+
+/// How long shutdown waits for the scheduler thread to finish its current iteration
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Upper bound on a single sleep. Thread sleeps don't advance while the machine is
+/// suspended, so waking periodically lets the loop resync with the wall clock.
+const MAX_SLEEP: Duration = Duration::from_secs(5 * 60);
+
+/// Schedule settings for automated report generation.
+/// The flattened primary entry keeps the original `{ enabled, time, days }` shape working.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ScheduleSettings {
+    pub enabled: bool,
+    pub catch_up: bool, // Fire today's trigger late if it was missed (sleep, app closed)
+    pub timezone: Option<String>, // IANA name, e.g. "Asia/Shanghai"; None uses the system zone
+    pub quiet_ranges: Vec<(String, String)>, // HH:mm start/end pairs, may wrap past midnight
+    pub defer_suppressed: bool, // Re-queue suppressed triggers as catch-ups once quiet hours end
+    #[serde(flatten)]
+    pub primary: ScheduleEntry,
+    pub schedules: Vec<ScheduleEntry>, // Additional labeled schedules, e.g. a weekly review
+}
+
+/// A single labeled schedule; `kind` tells the frontend which report to generate
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ScheduleEntry {
+    pub kind: String, // e.g. "daily", "weekly"
+    pub list_id: Option<String>, // Summarize only this list; None covers all tasks
+    pub time: String, // HH:mm format, e.g., "18:00"
+    pub days: Vec<u8>, // 0=Sunday, 1=Monday, ..., 6=Saturday
+    pub days_of_month: Vec<u8>, // 1-31, combined with `days` using OR
+    pub clamp_to_month_end: bool, // Fire day 29-31 on the last day of shorter months
+    pub interval_minutes: Option<u32>, // Also fire every N minutes inside the window
+    pub window_start: Option<String>, // HH:mm, defaults to 00:00
+    pub window_end: Option<String>, // HH:mm (inclusive), defaults to 23:59
+}
+
+impl Default for ScheduleEntry {
+    fn default() -> Self {
+        Self {
+            kind: "daily".to_string(),
+            list_id: None,
+            time: "18:00".to_string(),
+            days: vec![1, 2, 3, 4, 5], // Mon-Fri
+            days_of_month: Vec::new(),
+            clamp_to_month_end: false,
+            interval_minutes: None,
+            window_start: None,
+            window_end: None,
+        }
+    }
+}
+
+impl DueTrigger {
+    /// Identifies the schedule for de-duplication; plain `kind` keeps keys from older builds valid
+    fn schedule_key(&self) -> String {
+        match &self.list_id {
+            Some(list_id) => format!("{}:{}", self.kind, list_id),
+            None => self.kind.clone(),
+        }
+    }
+}
+
+impl ScheduleSettings {
+    /// The primary schedule followed by any additional ones
+    fn entries(&self) -> impl Iterator<Item = &ScheduleEntry> {
+        std::iter::once(&self.primary).chain(self.schedules.iter())
+    }
+
+    /// Rejects settings that would silently never fire, with a message the UI can show
+    pub fn validate(&self) -> Result<(), String> {
+        resolve_timezone(self)?;
+        for (start, end) in &self.quiet_ranges {
+            validate_time(start, "quiet range start")?;
+            validate_time(end, "quiet range end")?;
+        }
+        for entry in self.entries() {
+            entry.validate(self.enabled)?;
+        }
+        Ok(())
+    }
+}
+
+impl ScheduleEntry {
+    fn validate(&self, enabled: bool) -> Result<(), String> {
+        let label = |message: String| format!("'{}' schedule: {}", self.kind, message);
+
+        validate_time(&self.time, "time").map_err(label)?;
+        if let Some(day) = self.days.iter().find(|day| **day > 6) {
+            return Err(label(format!("day {} is out of range (0=Sunday ... 6=Saturday)", day)));
+        }
+        if let Some(day) = self.days_of_month.iter().find(|day| !(1..=31).contains(*day)) {
+            return Err(label(format!("day of month {} is out of range (1-31)", day)));
+        }
+        if enabled && self.days.is_empty() && self.days_of_month.is_empty() {
+            return Err(label("no days selected".to_string()));
+        }
+        if self.interval_minutes == Some(0) {
+            return Err(label("interval must be at least 1 minute".to_string()));
+        }
+        if let Some(start) = &self.window_start {
+            validate_time(start, "window start").map_err(label)?;
+        }
+        if let Some(end) = &self.window_end {
+            validate_time(end, "window end").map_err(label)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks a strict `HH:mm` value with hour 0-23 and minute 0-59
+fn validate_time(time: &str, field: &str) -> Result<(), String> {
+    let well_formed = time.len() == 5
+        && time.as_bytes()[2] == b':'
+        && time.bytes().enumerate().all(|(i, b)| i == 2 || b.is_ascii_digit());
+    match parse_schedule_time(time) {
+        Some((hour, minute)) if well_formed && hour <= 23 && minute <= 59 => Ok(()),
+        _ => Err(format!("Invalid {} '{}', expected HH:mm between 00:00 and 23:59", field, time)),
+    }
+}
+
+/// A trigger that is due now, expressed in the schedule's timezone
+pub(crate) struct DueTrigger {
+    kind: String,
+    list_id: Option<String>,
+    date: String, // YYYY-MM-DD
+    time: String, // Scheduled HH:mm
+    missed: bool,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub(crate) struct ScheduleTriggerPayload {
+    timestamp: i64,
+    kind: String,
+    list_id: Option<String>,
+    date: String,
+    time: String,
+    missed: bool,
+}
+
+/// Parses an `HH:mm` schedule time into (hour, minute)
+fn parse_schedule_time(time: &str) -> Option<(u32, u32)> {
+    let (hour, minute) = time.split_once(':')?;
+    Some((hour.parse().ok()?, minute.parse().ok()?))
+}
+
+/// Every (hour, minute) the entry fires on a scheduled day, sorted and deduplicated.
+/// Interval boundaries are window_start + k * interval for as long as they stay inside the window.
+fn daily_times(entry: &ScheduleEntry) -> Vec<(u32, u32)> {
+    let mut minutes: Vec<u32> = parse_schedule_time(&entry.time)
+        .map(|(hour, minute)| hour * 60 + minute)
+        .into_iter()
+        .collect();
+
+    if let Some(interval) = entry.interval_minutes.filter(|interval| *interval > 0) {
+        let bound = |time: &Option<String>, default: u32| match time.as_deref() {
+            Some(time) => parse_schedule_time(time).map(|(hour, minute)| hour * 60 + minute),
+            None => Some(default),
+        };
+        match (bound(&entry.window_start, 0), bound(&entry.window_end, 23 * 60 + 59)) {
+            (Some(start), Some(end)) => {
+                minutes.extend((start..=end).step_by(interval as usize));
+            }
+            _ => log::warn!("[Scheduler] Ignoring interval for '{}' schedule with malformed window", entry.kind),
+        }
+    }
+
+    minutes.sort_unstable();
+    minutes.dedup();
+    minutes.into_iter().map(|minute| (minute / 60, minute % 60)).collect()
+}
+
+/// Whether the entry fires on `date`, by weekday or by day of month
+fn runs_on(entry: &ScheduleEntry, date: NaiveDate) -> bool {
+    if entry.days.contains(&(date.weekday().num_days_from_sunday() as u8)) {
+        return true;
+    }
+    let day = date.day();
+    if entry.days_of_month.iter().any(|d| u32::from(*d) == day) {
+        return true;
+    }
+    // e.g. day 31 requested in February fires on the 28th/29th instead
+    let is_month_end = date.succ_opt().is_none_or(|next| next.month() != date.month());
+    entry.clamp_to_month_end && is_month_end && entry.days_of_month.iter().any(|d| u32::from(*d) > day)
+}
+
+/// Whether `time` falls inside any quiet range; the start is inclusive and the end exclusive
+fn in_quiet_range(settings: &ScheduleSettings, time: (u32, u32)) -> bool {
+    let minute = time.0 * 60 + time.1;
+    settings.quiet_ranges.iter().any(|(start, end)| {
+        let (Some(start), Some(end)) = (parse_schedule_time(start), parse_schedule_time(end)) else {
+            return false;
+        };
+        let (start, end) = (start.0 * 60 + start.1, end.0 * 60 + end.1);
+        if start <= end {
+            (start..end).contains(&minute)
+        } else {
+            // Wraps past midnight, e.g. 22:00-07:00
+            minute >= start || minute < end
+        }
+    })
+}
+
+/// Resolves the configured IANA timezone; `Ok(None)` means the system zone
+fn resolve_timezone(settings: &ScheduleSettings) -> Result<Option<Tz>, String> {
+    settings
+        .timezone
+        .as_deref()
+        .map(|name| name.parse::<Tz>().map_err(|_| format!("Unknown timezone '{}'", name)))
+        .transpose()
+}
+
+/// Returns the first instant strictly after `after` at which the entry fires
+fn next_entry_occurrence<Z: TimeZone>(entry: &ScheduleEntry, after: &DateTime<Z>) -> Option<DateTime<Z>> {
+    let times: Vec<NaiveTime> = daily_times(entry)
+        .into_iter()
+        .filter_map(|(hour, minute)| NaiveTime::from_hms_opt(hour, minute, 0))
+        .collect();
+
+    // A full week covers every weekday; day-of-month rules can skip months (e.g. the 31st)
+    let horizon = if entry.days_of_month.is_empty() { 7 } else { 62 };
+    (0..=horizon)
+        .filter_map(|offset| after.date_naive().checked_add_days(chrono::Days::new(offset)))
+        .filter(|date| runs_on(entry, *date))
+        .flat_map(|date| {
+            // `earliest` skips times that don't exist on DST transition days
+            times
+                .iter()
+                .filter_map(move |time| after.timezone().from_local_datetime(&date.and_time(*time)).earliest())
+        })
+        .find(|candidate| candidate > after)
+}
+
+/// Returns the first scheduled instant strictly after `after`, or `None` when nothing is scheduled
+fn next_occurrence<Z: TimeZone>(settings: &ScheduleSettings, after: &DateTime<Z>) -> Option<DateTime<Z>> {
+    if !settings.enabled {
+        return None;
+    }
+    settings
+        .entries()
+        .filter_map(|entry| next_entry_occurrence(entry, after))
+        .min()
+}
+
+/// Returns the entry's trigger due at `now`, if any. A trigger is due on the exact minute,
+/// or afterwards the same day when catch-up is on (the latest passed time is used).
+fn due_trigger<Z: TimeZone>(entry: &ScheduleEntry, catch_up: bool, now: &DateTime<Z>) -> Option<DueTrigger>
+where
+    Z::Offset: std::fmt::Display,
+{
+    if !runs_on(entry, now.date_naive()) {
+        return None;
+    }
+    let current = (now.hour(), now.minute());
+    let times = daily_times(entry);
+    let (target, missed) = if times.contains(&current) {
+        (current, false)
+    } else if catch_up {
+        (times.into_iter().rfind(|time| *time < current)?, true)
+    } else {
+        return None;
+    };
+    Some(DueTrigger {
+        kind: entry.kind.clone(),
+        list_id: entry.list_id.clone(),
+        date: now.format("%Y-%m-%d").to_string(),
+        time: format!("{:02}:{:02}", target.0, target.1),
+        missed,
+    })
+}
+
+/// Evaluates every entry's `due_trigger` in the schedule's timezone
+fn due_triggers_now(settings: &ScheduleSettings) -> Vec<DueTrigger> {
+    if !settings.enabled {
+        return Vec::new();
+    }
+    let due = |entry: &ScheduleEntry| match resolve_timezone(settings) {
+        Ok(Some(tz)) => due_trigger(entry, settings.catch_up, &Utc::now().with_timezone(&tz)),
+        Ok(None) => due_trigger(entry, settings.catch_up, &Local::now()),
+        Err(e) => {
+            log::warn!("[Scheduler] {}", e);
+            None
+        }
+    };
+    settings.entries().filter_map(due).collect()
+}
+
+/// Unix millis of the next firing in the schedule's timezone; shared by the loop and the UI countdown
+fn next_occurrence_millis(settings: &ScheduleSettings) -> Option<i64> {
+    match resolve_timezone(settings) {
+        Ok(Some(tz)) => next_occurrence(settings, &Utc::now().with_timezone(&tz)).map(|next| next.timestamp_millis()),
+        Ok(None) => next_occurrence(settings, &Local::now()).map(|next| next.timestamp_millis()),
+        Err(_) => None,
+    }
+}
+
+/// How long the loop may sleep before the next occurrence needs checking
+fn wait_duration_now(settings: &ScheduleSettings) -> Duration {
+    next_occurrence_millis(settings)
+        .and_then(|next| u64::try_from(next - Utc::now().timestamp_millis()).ok())
+        .map_or(MAX_SLEEP, |wait| Duration::from_millis(wait).min(MAX_SLEEP))
+}
+
+/// Unix millis of the next scheduled trigger, or `None` when disabled or nothing is scheduled
+#[tauri::command]
+pub fn next_scheduled_trigger(
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<i64>, CmdError> {
+    state
+        .schedule_settings
+        .lock()
+        .map(|settings| next_occurrence_millis(&settings))
+        .map_err(|e| CmdError::Internal(format!("Failed to read schedule settings: {}", e)))
+}
+
+/// Builds and emits `schedule-trigger`; shared by the scheduler loop and `trigger_report_now`.
+/// Until the frontend reports ready the payload is queued, since events sent before its
+/// listener registers are lost.
+fn emit_schedule_trigger(app_handle: &tauri::AppHandle, trigger: DueTrigger) -> Result<(), String> {
+    let payload = ScheduleTriggerPayload {
+        timestamp: Utc::now().timestamp_millis(),
+        kind: trigger.kind,
+        list_id: trigger.list_id,
+        date: trigger.date,
+        time: trigger.time,
+        missed: trigger.missed,
+    };
+
+    let state = app_handle.state::<AppState>();
+    {
+        // The ready flag is flipped under this lock, so a payload can't be queued after the flush
+        let mut pending = state
+            .pending_triggers
+            .lock()
+            .map_err(|e| format!("Failed to queue schedule-trigger event: {}", e))?;
+        if !state.frontend_ready.load(Ordering::SeqCst) {
+            log::info!("[Scheduler] Frontend not ready, queueing '{}' trigger", payload.kind);
+            pending.push(payload);
+            return Ok(());
+        }
+    }
+
+    app_handle
+        .emit("schedule-trigger", payload)
+        .map_err(|e| format!("Failed to emit schedule-trigger event: {}", e))?;
+    window::attract_attention(app_handle);
+    Ok(())
+}
+
+/// Called by the frontend once its `schedule-trigger` listener is registered; flushes queued triggers
+#[tauri::command]
+pub fn mark_frontend_ready(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), CmdError> {
+    let pending = {
+        let mut pending = state
+            .pending_triggers
+            .lock()
+            .map_err(|e| format!("Failed to flush queued triggers: {}", e))?;
+        state.frontend_ready.store(true, Ordering::SeqCst);
+        std::mem::take(&mut *pending)
+    };
+    integrity::announce_corruption(&app_handle);
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+    log::info!("[Scheduler] Frontend ready, flushing {} queued trigger(s)", pending.len());
+    for payload in pending {
+        app_handle
+            .emit("schedule-trigger", payload)
+            .map_err(|e| format!("Failed to emit schedule-trigger event: {}", e))?;
+    }
+    window::attract_attention(&app_handle);
+    Ok(())
+}
+
+/// Current date and `HH:mm` in the schedule's timezone
+fn current_date_time(settings: &ScheduleSettings) -> (String, String) {
+    fn split<Z: TimeZone>(now: DateTime<Z>) -> (String, String)
+    where
+        Z::Offset: std::fmt::Display,
+    {
+        (now.format("%Y-%m-%d").to_string(), now.format("%H:%M").to_string())
+    }
+
+    match resolve_timezone(settings) {
+        Ok(Some(tz)) => split(Utc::now().with_timezone(&tz)),
+        _ => split(Local::now()),
+    }
+}
+
+/// Emits a trigger immediately, e.g. for a "Run now" button; doesn't affect scheduled occurrences
+#[tauri::command]
+pub fn trigger_report_now(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    kind: Option<String>,
+    list_id: Option<String>,
+) -> Result<(), CmdError> {
+    let (date, time) = match state.schedule_settings.lock() {
+        Ok(settings) => current_date_time(&settings),
+        Err(e) => return Err(CmdError::Internal(format!("Failed to read schedule settings: {}", e))),
+    };
+    let kind = kind.unwrap_or_else(|| ScheduleEntry::default().kind);
+    log::info!("[Scheduler] ⏰ Manually triggering '{}' report", kind);
+
+    Ok(emit_schedule_trigger(&app_handle, DueTrigger { kind, list_id, date, time, missed: false })?)
+}
+
+/// Command for the frontend to flag report generation, so quitting from the tray asks first
+#[tauri::command]
+pub fn set_report_in_progress(state: tauri::State<'_, AppState>, in_progress: bool) {
+    state.report_in_progress.store(in_progress, Ordering::SeqCst);
+}
+
+#[tauri::command]
+pub async fn update_schedule_settings(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    settings: ScheduleSettings,
+) -> Result<(), CmdError> {
+    log::info!("[Scheduler] Updating schedule settings: enabled={}, catch_up={}, timezone={:?}, schedules={:?}",
+        settings.enabled, settings.catch_up, settings.timezone, settings.entries().collect::<Vec<_>>());
+
+    settings.validate().map_err(CmdError::Validation)?;
+
+    let pool = db::pool(&app_handle).await?;
+    for list_id in settings.entries().filter_map(|entry| entry.list_id.as_deref()) {
+        let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM lists WHERE id = ? AND deleted_at IS NULL")
+            .bind(list_id)
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| CmdError::Db(format!("Failed to look up list '{}': {}", list_id, e)))?;
+        if exists == 0 {
+            return Err(CmdError::NotFound(format!("Scheduled list '{}' no longer exists", list_id)));
+        }
+    }
+
+    let value = serde_json::to_string(&settings)
+        .map_err(|e| format!("Failed to serialize schedule settings: {}", e))?;
+
+    match state.schedule_settings.lock() {
+        Ok(mut current) => *current = settings,
+        Err(e) => return Err(CmdError::Internal(format!("Failed to update schedule settings: {}", e))),
+    }
+    // Wake the scheduler so it recomputes its sleep against the new settings
+    state.schedule_changed.notify_all();
+    tray::sync_schedule_toggle(&app_handle);
+
+    db::set_setting(&pool, SCHEDULE_SETTINGS_KEY, &value).await.map_err(CmdError::Db)
+}
+
+/// Flips `enabled` outside the settings UI (e.g. from the tray), persists it and tells
+/// the frontend so an open Settings view stays in sync
+pub async fn set_schedule_enabled(app_handle: &tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let settings = match state.schedule_settings.lock() {
+        Ok(mut current) => {
+            current.enabled = enabled;
+            current.clone()
+        }
+        Err(e) => return Err(format!("Failed to update schedule settings: {}", e)),
+    };
+    state.schedule_changed.notify_all();
+    log::info!("[Scheduler] Scheduled reports {}", if enabled { "enabled" } else { "disabled" });
+
+    let value = serde_json::to_string(&settings)
+        .map_err(|e| format!("Failed to serialize schedule settings: {}", e))?;
+    let pool = db::pool(app_handle).await?;
+    db::set_setting(&pool, SCHEDULE_SETTINGS_KEY, &value).await?;
+
+    app_handle
+        .emit("schedule-settings-changed", &settings)
+        .map_err(|e| format!("Failed to emit schedule-settings-changed: {}", e))
+}
+
+#[tauri::command]
+pub fn get_schedule_settings(
+    state: tauri::State<'_, AppState>,
+) -> Result<ScheduleSettings, CmdError> {
+    state
+        .schedule_settings
+        .lock()
+        .map(|current| current.clone())
+        .map_err(|e| CmdError::Internal(format!("Failed to read schedule settings: {}", e)))
+}
+
+fn load_schedule_settings(app_handle: &tauri::AppHandle) -> Result<ScheduleSettings, String> {
+    let value = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app_handle).await?;
+        db::get_setting(&pool, SCHEDULE_SETTINGS_KEY).await
+    })?;
+    Ok(value
+        .map(|value| {
+            serde_json::from_str(&value).unwrap_or_else(|e| {
+                log::warn!("[Scheduler] Malformed persisted schedule settings, using defaults: {}", e);
+                ScheduleSettings::default()
+            })
+        })
+        .unwrap_or_default())
+}
+
+fn persist_last_triggered(app_handle: &tauri::AppHandle, last_triggered: &HashMap<String, String>) {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app_handle).await?;
+        let value = serde_json::to_string(last_triggered).map_err(|e| e.to_string())?;
+        db::set_setting(&pool, LAST_TRIGGERED_KEY, &value).await
+    });
+    if let Err(e) = result {
+        log::error!("[Scheduler] Failed to persist last trigger: {}", e);
+    }
+}
+
+fn load_last_triggered(app_handle: &tauri::AppHandle) -> HashMap<String, String> {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app_handle).await?;
+        db::get_setting(&pool, LAST_TRIGGERED_KEY).await
+    });
+    let value = match result {
+        Ok(Some(value)) => value,
+        Ok(None) => return HashMap::new(),
+        Err(e) => {
+            log::warn!("[Scheduler] Failed to load last trigger: {}", e);
+            return HashMap::new();
+        }
+    };
+    serde_json::from_str::<HashMap<String, String>>(&value)
+        .or_else(|_| {
+            // Older builds stored a single key for the one (daily) schedule
+            serde_json::from_str::<String>(&value).map(|key| HashMap::from([("daily".to_string(), key)]))
+        })
+        .unwrap_or_default()
+}
+
+/// Restores persisted schedule settings and the last fired occurrences into `AppState`
+pub fn restore_state(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<AppState>();
+
+    let settings = match load_schedule_settings(app_handle) {
+        Ok(settings) => {
+            state.schedule_settings_loaded.store(true, Ordering::SeqCst);
+            settings
+        }
+        Err(e) => {
+            log::warn!("[Scheduler] Failed to load schedule settings, using defaults: {}", e);
+            ScheduleSettings::default()
+        }
+    };
+    log::info!("[Scheduler] Loaded schedule settings: enabled={}, schedules={}",
+        settings.enabled, settings.entries().count());
+    if let Ok(mut current) = state.schedule_settings.lock() {
+        *current = settings;
+    }
+
+    if let Ok(mut current) = state.last_triggered.lock() {
+        *current = load_last_triggered(app_handle);
+    }
+}
+
+/// Emits a trigger for each schedule that is due now and hasn't fired yet
+fn check_and_trigger(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<AppState>();
+
+    let Ok((triggers, quiet, defer_suppressed)) = state.schedule_settings.lock().map(|settings| {
+        let (_, time) = current_date_time(&settings);
+        let quiet = parse_schedule_time(&time).is_some_and(|time| in_quiet_range(&settings, time));
+        (due_triggers_now(&settings), quiet, settings.enabled && settings.defer_suppressed)
+    }) else {
+        return;
+    };
+
+    // Skip unless the occurrence is newer than the last one fired for its schedule (loop woke
+    // twice, app restarted mid-minute, or catch-up pointing at an occurrence already covered)
+    let (triggers, last_triggered) = {
+        let Ok(mut last_triggered) = state.last_triggered.lock() else {
+            return;
+        };
+        let triggers: Vec<DueTrigger> = triggers
+            .into_iter()
+            .filter(|trigger| {
+                let occurrence_key = format!("{} {}", trigger.date, trigger.time);
+                let schedule_key = trigger.schedule_key();
+                let is_new = last_triggered
+                    .get(&schedule_key)
+                    .is_none_or(|last| occurrence_key.as_str() > last.as_str());
+                if is_new {
+                    last_triggered.insert(schedule_key, occurrence_key);
+                }
+                is_new
+            })
+            .collect();
+        (triggers, last_triggered.clone())
+    };
+    if !triggers.is_empty() {
+        persist_last_triggered(app_handle, &last_triggered);
+    }
+
+    let Ok(mut deferred) = state.deferred_triggers.lock() else {
+        return;
+    };
+    if !defer_suppressed {
+        deferred.clear();
+    }
+
+    if quiet {
+        // Suppressed occurrences stay marked as fired; deferral re-emits them after quiet hours
+        for trigger in triggers {
+            log::info!("[Scheduler] 🔕 Suppressed '{}' report scheduled for {} (quiet hours){}",
+                trigger.kind, trigger.time, if defer_suppressed { ", deferring" } else { "" });
+            if defer_suppressed {
+                deferred.push(trigger);
+            }
+        }
+        return;
+    }
+
+    let triggers: Vec<DueTrigger> = deferred
+        .drain(..)
+        .map(|trigger| DueTrigger { missed: true, ..trigger })
+        .chain(triggers)
+        .collect();
+    drop(deferred);
+
+    for trigger in triggers {
+        if trigger.missed {
+            log::info!("[Scheduler] ⏰ Catching up on missed '{}' report scheduled for {} {}",
+                trigger.kind, trigger.date, trigger.time);
+        } else {
+            log::info!("[Scheduler] ⏰ Triggering scheduled '{}' report at {}", trigger.kind, trigger.time);
+        }
+
+        if let Err(e) = emit_schedule_trigger(app_handle, trigger) {
+            log::error!("[Scheduler] {}", e);
+        }
+    }
+}
+
+pub fn start_background_scheduler(app_handle: tauri::AppHandle) {
+    let thread_handle = app_handle.clone();
+    let handle = std::thread::spawn(move || {
+        let app_handle = thread_handle;
+        log::info!("[Scheduler] Background scheduler started");
+        let state = app_handle.state::<AppState>();
+
+        while !state.scheduler_stop.load(Ordering::SeqCst) {
+            check_and_trigger(&app_handle);
+            trash::sweep_expired(&app_handle);
+            trash::sweep_completed(&app_handle);
+            backup::run_due_backup(&app_handle);
+            rollover::run_due_rollover(&app_handle);
+            tasks::run_due_recompute(&app_handle);
+            vacuum::run_due_vacuum(&app_handle);
+
+            // Sleep until the next occurrence; settings updates and shutdown notify the condvar
+            // to wake us early. The stop flag and wait are checked under the same lock the wait
+            // releases, so no notification can slip in between.
+            let Ok(settings) = state.schedule_settings.lock() else {
+                log::error!("[Scheduler] Schedule settings lock poisoned, stopping scheduler");
+                return;
+            };
+            if state.scheduler_stop.load(Ordering::SeqCst) {
+                break;
+            }
+            let mut wait = wait_duration_now(&settings);
+            if state.deferred_triggers.lock().is_ok_and(|deferred| !deferred.is_empty()) {
+                wait = wait.min(DEFERRED_RECHECK);
+            }
+            log::debug!("[Scheduler] Sleeping for {}s", wait.as_secs());
+            if state.schedule_changed.wait_timeout(settings, wait).is_err() {
+                log::error!("[Scheduler] Schedule settings lock poisoned, stopping scheduler");
+                return;
+            }
+        }
+
+        log::info!("[Scheduler] Background scheduler stopped");
+    });
+
+    if let Ok(mut current) = app_handle.state::<AppState>().scheduler_handle.lock() {
+        *current = Some(handle);
+    }
+}
+
+/// Signals the scheduler thread to exit and waits briefly for any in-flight state flush
+pub fn stop_background_scheduler(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<AppState>();
+    if state.scheduler_stop.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    // Taking the lock orders this notify after the loop has either seen the flag or started waiting
+    drop(state.schedule_settings.lock());
+    state.schedule_changed.notify_all();
+
+    let Some(handle) = state.scheduler_handle.lock().ok().and_then(|mut handle| handle.take()) else {
+        return;
+    };
+    let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+    while !handle.is_finished() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    if handle.is_finished() {
+        let _ = handle.join();
+    } else {
+        log::warn!("[Scheduler] Scheduler thread did not stop within {:?}", SHUTDOWN_TIMEOUT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(entry: ScheduleEntry) -> ScheduleSettings {
+        ScheduleSettings { enabled: true, primary: entry, ..Default::default() }
+    }
+
+    #[test]
+    fn accepts_default_schedule() {
+        assert_eq!(settings(ScheduleEntry::default()).validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_malformed_time() {
+        for time in ["8:00", "18:0", "24:00", "12:60", "ab:cd", "18-00", ""] {
+            let entry = ScheduleEntry { time: time.to_string(), ..Default::default() };
+            let error = settings(entry).validate().unwrap_err();
+            assert!(error.contains("Invalid time"), "{}: {}", time, error);
+        }
+    }
+
+    #[test]
+    fn rejects_out_of_range_day() {
+        let entry = ScheduleEntry { days: vec![1, 7], ..Default::default() };
+        let error = settings(entry).validate().unwrap_err();
+        assert!(error.contains("day 7 is out of range"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_empty_days_only_when_enabled() {
+        let entry = ScheduleEntry { days: Vec::new(), ..Default::default() };
+        let error = settings(entry.clone()).validate().unwrap_err();
+        assert!(error.contains("no days selected"), "{}", error);
+
+        let disabled = ScheduleSettings { enabled: false, primary: entry, ..Default::default() };
+        assert_eq!(disabled.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validates_additional_schedules() {
+        let weekly = ScheduleEntry { kind: "weekly".to_string(), days: vec![9], ..Default::default() };
+        let with_weekly = ScheduleSettings { schedules: vec![weekly], ..settings(ScheduleEntry::default()) };
+        let error = with_weekly.validate().unwrap_err();
+        assert!(error.starts_with("'weekly' schedule:"), "{}", error);
+    }
+}
@@ -0,0 +1,216 @@
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Manager};
+
+use crate::error::CmdError;
+use crate::{db, AppState};
+
+/// Words either side of a match kept in a snippet
+const SNIPPET_TOKENS: u32 = 12;
+/// Characters either side of a match kept in a fallback snippet
+const FALLBACK_CONTEXT: usize = 40;
+
+/// The index and its triggers. A plain (not external content) table keyed by task id, so
+/// `INSERT OR REPLACE` on `tasks`, which skips delete triggers, can't leave stale rows behind
+const FTS_SCHEMA: &[&str] = &[
+    "CREATE VIRTUAL TABLE IF NOT EXISTS tasks_fts USING fts5(task_id UNINDEXED, title, content)",
+    "CREATE TRIGGER IF NOT EXISTS tasks_fts_insert AFTER INSERT ON tasks BEGIN
+         DELETE FROM tasks_fts WHERE task_id = new.id;
+         INSERT INTO tasks_fts (task_id, title, content) VALUES (new.id, new.title, COALESCE(new.content, ''));
+     END",
+    "CREATE TRIGGER IF NOT EXISTS tasks_fts_update AFTER UPDATE OF title, content ON tasks BEGIN
+         DELETE FROM tasks_fts WHERE task_id = old.id;
+         INSERT INTO tasks_fts (task_id, title, content) VALUES (new.id, new.title, COALESCE(new.content, ''));
+     END",
+    "CREATE TRIGGER IF NOT EXISTS tasks_fts_delete AFTER DELETE ON tasks BEGIN
+         DELETE FROM tasks_fts WHERE task_id = old.id;
+     END",
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskHit {
+    pub id: String,
+    pub title: String,
+    pub list_name: String,
+    pub completed: bool,
+    /// Matching excerpt with the matched words wrapped in `[` `]`
+    pub snippet: String,
+}
+
+async fn create_index(pool: &sqlx::SqlitePool) -> Result<(), String> {
+    let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM sqlite_master WHERE name = 'tasks_fts'")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to look up the search index: {}", e))?
+        > 0;
+    let mut tx = pool.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+    for sql in FTS_SCHEMA {
+        sqlx::query(sql)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to create the search index: {}", e))?;
+    }
+    if !exists {
+        sqlx::query("INSERT INTO tasks_fts (task_id, title, content) SELECT id, title, COALESCE(content, '') FROM tasks")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to fill the search index: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))
+}
+
+/// Creates the FTS5 index on startup. This isn't a migration: on a SQLite built without FTS5,
+/// a failing migration would keep the whole database from opening, while here search just
+/// falls back to `LIKE`
+pub fn ensure_index(app: &AppHandle) {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        create_index(&pool).await
+    });
+    let available = match result {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("[Search] Full-text search unavailable, falling back to LIKE: {}", e);
+            false
+        }
+    };
+    app.state::<AppState>().fts_available.store(available, Ordering::SeqCst);
+}
+
+/// Each word as a quoted FTS5 string, the last as a prefix so results follow typing
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    let (last, rest) = terms.split_last()?;
+    Some(rest.iter().cloned().chain(std::iter::once(format!("{}*", last))).collect::<Vec<_>>().join(" "))
+}
+
+async fn search_fts(pool: &sqlx::SqlitePool, query: &str, limit: u32) -> Result<Vec<TaskHit>, String> {
+    let Some(query) = fts_query(query) else {
+        return Ok(Vec::new());
+    };
+    let rows = sqlx::query_as::<_, (String, String, String, bool, String)>(
+        "SELECT t.id, t.title, t.list_name, t.completed, snippet(tasks_fts, -1, '[', ']', '…', ?)
+         FROM tasks_fts JOIN tasks t ON t.id = tasks_fts.task_id
+         WHERE tasks_fts MATCH ? AND t.deleted_at IS NULL
+         ORDER BY bm25(tasks_fts, 0.0, 10.0, 1.0)
+         LIMIT ?",
+    )
+    .bind(SNIPPET_TOKENS)
+    .bind(&query)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to search tasks: {}", e))?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, title, list_name, completed, snippet)| TaskHit { id, title, list_name, completed, snippet })
+        .collect())
+}
+
+/// Excerpt around the first case-insensitive occurrence of `needle`, marked like FTS5 snippets
+fn fallback_snippet(text: &str, needle: &str) -> Option<String> {
+    let lower = text.to_lowercase();
+    // Lowercasing can change byte lengths, so positions are only trusted when it didn't
+    if lower.len() != text.len() {
+        return None;
+    }
+    let start = lower.find(&needle.to_lowercase())?;
+    let end = start + needle.len();
+    let before_start = text[..start]
+        .char_indices()
+        .rev()
+        .nth(FALLBACK_CONTEXT.saturating_sub(1))
+        .map_or(0, |(index, _)| index);
+    let after_end = text[end..].char_indices().nth(FALLBACK_CONTEXT).map_or(text.len(), |(index, _)| end + index);
+    Some(format!(
+        "{}{}[{}]{}{}",
+        if before_start > 0 { "…" } else { "" },
+        &text[before_start..start],
+        &text[start..end],
+        &text[end..after_end],
+        if after_end < text.len() { "…" } else { "" },
+    ))
+}
+
+async fn search_like(pool: &sqlx::SqlitePool, query: &str, limit: u32) -> Result<Vec<TaskHit>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pattern = format!("%{}%", query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+    let rows = sqlx::query_as::<_, (String, String, String, bool, Option<String>)>(
+        r#"SELECT id, title, list_name, completed, content FROM tasks
+           WHERE deleted_at IS NULL AND (title LIKE ?1 ESCAPE '\' OR content LIKE ?1 ESCAPE '\')
+           ORDER BY title LIKE ?1 ESCAPE '\' DESC, updated_at DESC
+           LIMIT ?2"#,
+    )
+    .bind(&pattern)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to search tasks: {}", e))?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, title, list_name, completed, content)| {
+            let snippet = fallback_snippet(&title, query)
+                .or_else(|| content.as_deref().and_then(|content| fallback_snippet(content, query)))
+                .unwrap_or_else(|| title.clone());
+            TaskHit { id, title, list_name, completed, snippet }
+        })
+        .collect())
+}
+
+/// Command for the search box: tasks whose title or content match `query`, best first
+#[tauri::command]
+pub async fn search_tasks(app_handle: AppHandle, query: String, limit: u32) -> Result<Vec<TaskHit>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    if app_handle.state::<AppState>().fts_available.load(Ordering::SeqCst) {
+        Ok(search_fts(&pool, &query, limit).await?)
+    } else {
+        Ok(search_like(&pool, &query, limit).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lists;
+
+    #[test]
+    fn archived_lists_stay_searchable() {
+        tauri::async_runtime::block_on(async {
+            let pool = db::test_pool().await;
+            create_index(&pool).await.unwrap();
+            sqlx::query(r#"INSERT INTO lists (id, name, "order") VALUES ('list-trips', 'Trips', 2)"#)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query(
+                r#"INSERT INTO tasks (id, title, content, list_id, list_name, "order", created_at, updated_at)
+                   VALUES ('task-1', 'Renew passport', 'Photo booth first', 'list-trips', 'Trips', 0, 0, 0)"#,
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            lists::store_archived(&pool, "list-trips", true).await.unwrap();
+            let archived: bool = sqlx::query_scalar("SELECT archived FROM lists WHERE id = 'list-trips'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            assert!(archived);
+
+            let by_index = search_fts(&pool, "passp", 10).await.unwrap();
+            let by_like = search_like(&pool, "booth", 10).await.unwrap();
+            for hits in [by_index, by_like] {
+                let ids: Vec<&str> = hits.iter().map(|hit| hit.id.as_str()).collect();
+                assert_eq!(ids, ["task-1"]);
+                assert_eq!(hits[0].list_name, "Trips");
+            }
+        });
+    }
+}
@@ -0,0 +1,106 @@
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::error::CmdError;
+use crate::{db, tray, AppState};
+
+/// Settings key holding the accelerator string of the show/hide shortcut
+const SHORTCUT_SETTING_KEY: &str = "global_shortcut";
+/// Registered when the user hasn't picked their own shortcut
+const DEFAULT_SHORTCUT: &str = "CommandOrControl+Shift+T";
+
+/// Builds the global shortcut plugin; every registered shortcut toggles the main window
+pub fn plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                toggle_main_window(app);
+            }
+        })
+        .build()
+}
+
+/// Hides the main window when it's visible and focused, otherwise shows and focuses it
+fn toggle_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main")
+        && window.is_visible().unwrap_or(false)
+        && window.is_focused().unwrap_or(false)
+    {
+        let _ = window.hide();
+        return;
+    }
+    tray::show_main_window(app);
+}
+
+fn parse_shortcut(accelerator: &str) -> Result<Shortcut, String> {
+    accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", accelerator, e))
+}
+
+/// Registers the saved shortcut (or the default) at startup; a taken shortcut is only logged
+pub fn register_saved_shortcut(app: &AppHandle) {
+    let saved = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        db::get_setting(&pool, SHORTCUT_SETTING_KEY).await
+    });
+    let accelerator = match saved {
+        Ok(Some(value)) => serde_json::from_str::<String>(&value).unwrap_or_else(|e| {
+            log::warn!("[Shortcut] Malformed saved shortcut, using default: {}", e);
+            DEFAULT_SHORTCUT.to_string()
+        }),
+        Ok(None) => DEFAULT_SHORTCUT.to_string(),
+        Err(e) => {
+            log::warn!("[Shortcut] Failed to load saved shortcut, using default: {}", e);
+            DEFAULT_SHORTCUT.to_string()
+        }
+    };
+
+    let result = parse_shortcut(&accelerator).and_then(|shortcut| {
+        app.global_shortcut()
+            .register(shortcut)
+            .map_err(|e| format!("Shortcut '{}' is unavailable: {}", accelerator, e))
+    });
+    match result {
+        Ok(()) => {
+            log::info!("[Shortcut] Registered global shortcut {}", accelerator);
+            if let Ok(mut current) = app.state::<AppState>().global_shortcut.lock() {
+                *current = Some(accelerator);
+            }
+        }
+        Err(e) => log::warn!("[Shortcut] {}", e),
+    }
+}
+
+/// Command to replace the show/hide shortcut; the old one stays active if the new one is taken
+#[tauri::command]
+pub async fn set_global_shortcut(app_handle: AppHandle, accelerator: String) -> Result<(), CmdError> {
+    let shortcut = parse_shortcut(&accelerator)?;
+    let state = app_handle.state::<AppState>();
+    let previous = state
+        .global_shortcut
+        .lock()
+        .map_err(|e| format!("Failed to read global shortcut: {}", e))?
+        .clone();
+
+    if previous.as_deref() != Some(accelerator.as_str()) {
+        app_handle
+            .global_shortcut()
+            .register(shortcut)
+            .map_err(|e| CmdError::Validation(format!("Shortcut '{}' is unavailable: {}", accelerator, e)))?;
+        if let Some(previous) = previous.as_deref()
+            && let Err(e) = app_handle.global_shortcut().unregister(previous)
+        {
+            log::warn!("[Shortcut] Failed to unregister {}: {}", previous, e);
+        }
+        if let Ok(mut current) = state.global_shortcut.lock() {
+            *current = Some(accelerator.clone());
+        }
+        log::info!("[Shortcut] Global shortcut changed to {}", accelerator);
+    }
+
+    let value = serde_json::to_string(&accelerator)
+        .map_err(|e| format!("Failed to serialize global shortcut: {}", e))?;
+    let pool = db::pool(&app_handle).await?;
+    db::set_setting(&pool, SHORTCUT_SETTING_KEY, &value).await.map_err(CmdError::Db)
+}
@@ -0,0 +1,140 @@
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use crate::db;
+use crate::error::CmdError;
+use crate::tray::local_midnight_millis;
+
+/// Tasks counted in statistics: not in Trash
+const LIVE: &str = "deleted_at IS NULL AND list_name != 'Trash'";
+
+/// Local calendar days, both ends included, as `YYYY-MM-DD`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DateRange {
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayCount {
+    pub date: String,
+    pub completed: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListStats {
+    pub list_id: Option<String>,
+    pub list_name: String,
+    /// Completed within the range
+    pub completed: i64,
+    /// Still open and due within the range
+    pub due_open: i64,
+    pub overdue: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    /// One entry per day of the range, zero days included
+    pub completed_per_day: Vec<DayCount>,
+    pub completed: i64,
+    /// Completed out of the tasks that were either completed within the range or are still
+    /// open and were due within it; `None` when there were none
+    pub completion_rate: Option<f64>,
+    /// Open tasks due before today, whatever the range
+    pub overdue: i64,
+    /// Mean `complete_percentage` of open tasks that track one
+    pub average_complete_percentage: Option<f64>,
+    pub lists: Vec<ListStats>,
+}
+
+type ListStatsRow = (Option<String>, String, i64, i64, i64);
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| format!("Invalid date '{}'; use YYYY-MM-DD", value))
+}
+
+/// Command for the dashboard figures over `range`. Days follow local time, so a task counts
+/// on the day it was completed where the user is
+#[tauri::command]
+pub async fn get_statistics(app_handle: AppHandle, range: DateRange) -> Result<Stats, CmdError> {
+    let first = parse_date(&range.start).map_err(CmdError::Validation)?;
+    let last = parse_date(&range.end).map_err(CmdError::Validation)?;
+    if last < first {
+        return Err(CmdError::Validation(format!("Range ends ({}) before it starts ({})", range.end, range.start)));
+    }
+    let since = local_midnight_millis(first);
+    let until = local_midnight_millis(last.succ_opt().unwrap_or(last));
+    let today = local_midnight_millis(Local::now().date_naive());
+    let pool = db::pool(&app_handle).await?;
+
+    let per_day = sqlx::query_as::<_, (String, i64)>(&format!(
+        "SELECT date(completed_at / 1000, 'unixepoch', 'localtime'), COUNT(*) FROM tasks
+         WHERE {LIVE} AND completed = 1 AND completed_at >= ?1 AND completed_at < ?2
+         GROUP BY 1"
+    ))
+    .bind(since)
+    .bind(until)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to count completed tasks: {}", e)))?
+    .into_iter()
+    .collect::<HashMap<_, _>>();
+    let completed_per_day: Vec<DayCount> = first
+        .iter_days()
+        .take_while(|day| *day <= last)
+        .map(|day| {
+            let date = day.format("%Y-%m-%d").to_string();
+            let completed = per_day.get(&date).copied().unwrap_or(0);
+            DayCount { date, completed }
+        })
+        .collect();
+    let completed: i64 = completed_per_day.iter().map(|day| day.completed).sum();
+
+    let (due_open, overdue, average_complete_percentage) = sqlx::query_as::<_, (i64, i64, Option<f64>)>(&format!(
+        "SELECT COALESCE(SUM(due_date >= ?1 AND due_date < ?2), 0),
+                COALESCE(SUM(due_date < ?3), 0),
+                AVG(complete_percentage)
+         FROM tasks WHERE {LIVE} AND completed = 0"
+    ))
+    .bind(since)
+    .bind(until)
+    .bind(today)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to count open tasks: {}", e)))?;
+    let planned = completed + due_open;
+    let completion_rate = (planned > 0).then(|| completed as f64 / planned as f64);
+
+    let lists = sqlx::query_as::<_, ListStatsRow>(&format!(
+        "SELECT list_id, list_name,
+                COALESCE(SUM(completed = 1 AND completed_at >= ?1 AND completed_at < ?2), 0) AS done,
+                COALESCE(SUM(completed = 0 AND due_date >= ?1 AND due_date < ?2), 0),
+                COALESCE(SUM(completed = 0 AND due_date < ?3), 0)
+         FROM tasks WHERE {LIVE}
+         GROUP BY list_id, list_name
+         ORDER BY done DESC, list_name"
+    ))
+    .bind(since)
+    .bind(until)
+    .bind(today)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to build per-list statistics: {}", e)))?
+    .into_iter()
+    .map(|(list_id, list_name, completed, due_open, overdue)| ListStats {
+        list_id,
+        list_name,
+        completed,
+        due_open,
+        overdue,
+    })
+    .collect();
+
+    Ok(Stats { completed_per_day, completed, completion_rate, overdue, average_complete_percentage, lists })
+}
@@ -0,0 +1,201 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use tauri::AppHandle;
+
+use crate::db;
+use crate::error::CmdError;
+
+/// Deepest nesting allowed below a task; top-level subtasks are level 1
+pub(crate) const MAX_DEPTH: usize = 5;
+
+/// Gap between sibling `order` values, same spacing the frontend uses
+pub(crate) const ORDER_STEP: i64 = 1000;
+
+/// A subtask with its nested children, each level ordered by `order`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtaskNode {
+    pub id: String,
+    pub parent_id: String,
+    pub parent_subtask_id: Option<String>,
+    pub title: String,
+    pub completed: bool,
+    pub completed_at: Option<i64>,
+    pub due_date: Option<i64>,
+    pub order: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub children: Vec<SubtaskNode>,
+}
+
+type SubtaskRow = (String, String, Option<String>, String, bool, Option<i64>, Option<i64>, i64, i64, i64);
+
+fn node((id, parent_id, parent_subtask_id, title, completed, completed_at, due_date, order, created_at, updated_at): SubtaskRow) -> SubtaskNode {
+    SubtaskNode {
+        id,
+        parent_id,
+        parent_subtask_id,
+        title,
+        completed,
+        completed_at,
+        due_date,
+        order,
+        created_at,
+        updated_at,
+        children: Vec::new(),
+    }
+}
+
+/// Depth of a new subtask under `parent_subtask_id`, walking up the chain of parents
+async fn depth_under(pool: &sqlx::SqlitePool, parent_subtask_id: Option<&str>) -> Result<usize, String> {
+    let Some(parent) = parent_subtask_id else {
+        return Ok(1);
+    };
+    sqlx::query_scalar::<_, i64>(
+        "WITH RECURSIVE ancestors(id, parent_subtask_id, depth) AS (
+             SELECT id, parent_subtask_id, 1 FROM subtasks WHERE id = ?
+             UNION
+             SELECT s.id, s.parent_subtask_id, a.depth + 1 FROM subtasks s
+             JOIN ancestors a ON s.id = a.parent_subtask_id
+             WHERE a.depth <= ?
+         )
+         SELECT MAX(depth) FROM ancestors",
+    )
+    .bind(parent)
+    .bind(MAX_DEPTH as i64)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to read subtask depth: {}", e))?
+    .map(|depth| depth as usize + 1)
+    .ok_or_else(|| format!("Subtask '{}' not found", parent))
+}
+
+/// Command to add a subtask under a task, or under another subtask of it; it goes to the
+/// end of its siblings. Refuses to nest deeper than `MAX_DEPTH`
+#[tauri::command]
+pub async fn add_subtask(
+    app_handle: AppHandle,
+    task_id: String,
+    parent_subtask_id: Option<String>,
+    title: String,
+    due_date: Option<i64>,
+) -> Result<SubtaskNode, CmdError> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(CmdError::Validation("Subtask title is empty".to_string()));
+    }
+    let pool = db::pool(&app_handle).await?;
+    if let Some(parent) = &parent_subtask_id {
+        let parent_task = sqlx::query_scalar::<_, String>("SELECT parent_id FROM subtasks WHERE id = ?")
+            .bind(parent)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| CmdError::Db(format!("Failed to look up subtask '{}': {}", parent, e)))?;
+        if parent_task.as_deref() != Some(task_id.as_str()) {
+            return Err(CmdError::Validation(format!("Subtask '{}' doesn't belong to task '{}'", parent, task_id)));
+        }
+    }
+    let depth = depth_under(&pool, parent_subtask_id.as_deref()).await?;
+    if depth > MAX_DEPTH {
+        return Err(CmdError::Validation(format!("Subtasks can be nested at most {} levels deep", MAX_DEPTH)));
+    }
+
+    // Ordering is per parent: siblings share `parent_subtask_id` (NULL at the top level)
+    let last_order = sqlx::query_scalar::<_, Option<i64>>(
+        r#"SELECT MAX("order") FROM subtasks WHERE parent_id = ? AND parent_subtask_id IS ?"#,
+    )
+    .bind(&task_id)
+    .bind(&parent_subtask_id)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read subtask order: {}", e)))?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let subtask = SubtaskNode {
+        id: format!("subtask-{}-{}", now, chrono::Utc::now().timestamp_subsec_nanos()),
+        parent_id: task_id,
+        parent_subtask_id,
+        title: title.to_string(),
+        completed: false,
+        completed_at: None,
+        due_date,
+        order: last_order.map_or(0, |order| order + ORDER_STEP),
+        created_at: now,
+        updated_at: now,
+        children: Vec::new(),
+    };
+    sqlx::query(
+        r#"INSERT INTO subtasks (id, parent_id, parent_subtask_id, title, completed, due_date, "order", created_at, updated_at)
+           VALUES (?, ?, ?, ?, 0, ?, ?, ?, ?)"#,
+    )
+    .bind(&subtask.id)
+    .bind(&subtask.parent_id)
+    .bind(&subtask.parent_subtask_id)
+    .bind(&subtask.title)
+    .bind(subtask.due_date)
+    .bind(subtask.order)
+    .bind(now)
+    .bind(now)
+    .execute(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to add subtask: {}", e)))?;
+    Ok(subtask)
+}
+
+/// Command to delete a subtask together with everything nested below it; returns how many
+/// rows went. Done explicitly since the FK cascade only runs with foreign keys enforced
+#[tauri::command]
+pub async fn delete_subtask(app_handle: AppHandle, subtask_id: String) -> Result<u64, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query(
+        "WITH RECURSIVE descendants(id) AS (
+             SELECT id FROM subtasks WHERE id = ?
+             UNION
+             SELECT s.id FROM subtasks s JOIN descendants d ON s.parent_subtask_id = d.id
+         )
+         DELETE FROM subtasks WHERE id IN (SELECT id FROM descendants)",
+    )
+    .bind(&subtask_id)
+    .execute(&pool)
+    .await
+    .map(|result| result.rows_affected())
+    .map_err(|e| CmdError::Db(format!("Failed to delete subtask '{}': {}", subtask_id, e)))
+}
+
+/// Command returning a task's subtasks as a tree. Subtasks whose parent is gone are listed
+/// at the top level rather than dropped; nothing below `MAX_DEPTH` is followed, which also
+/// stops a corrupted parent cycle
+#[tauri::command]
+pub async fn get_subtask_tree(app_handle: AppHandle, task_id: String) -> Result<Vec<SubtaskNode>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let rows = sqlx::query_as::<_, SubtaskRow>(
+        r#"SELECT id, parent_id, parent_subtask_id, title, completed, completed_at, due_date, "order", created_at, updated_at
+           FROM subtasks WHERE parent_id = ? ORDER BY "order", created_at"#,
+    )
+    .bind(&task_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read subtasks: {}", e)))?;
+
+    let ids: HashSet<String> = rows.iter().map(|row| row.0.clone()).collect();
+    let mut children: HashMap<Option<String>, Vec<SubtaskNode>> = HashMap::new();
+    for row in rows {
+        let node = node(row);
+        let parent = node.parent_subtask_id.clone().filter(|parent| ids.contains(parent));
+        children.entry(parent).or_default().push(node);
+    }
+
+    fn attach(node: &mut SubtaskNode, children: &mut HashMap<Option<String>, Vec<SubtaskNode>>, depth: usize) {
+        if depth >= MAX_DEPTH {
+            return;
+        }
+        node.children = children.remove(&Some(node.id.clone())).unwrap_or_default();
+        for child in &mut node.children {
+            attach(child, children, depth + 1);
+        }
+    }
+    let mut roots = children.remove(&None).unwrap_or_default();
+    for root in &mut roots {
+        attach(root, &mut children, 1);
+    }
+    Ok(roots)
+}
@@ -0,0 +1,112 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::db;
+use crate::error::CmdError;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    pub id: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub period_key: String,
+    pub list_key: String,
+    pub task_ids: Vec<String>,
+    pub summary_text: String,
+}
+
+type SummaryRow = (String, i64, i64, String, String, String, String);
+
+fn summary((id, created_at, updated_at, period_key, list_key, task_ids, summary_text): SummaryRow) -> Summary {
+    Summary {
+        id,
+        created_at,
+        updated_at,
+        period_key,
+        list_key,
+        task_ids: serde_json::from_str(&task_ids).unwrap_or_default(),
+        summary_text,
+    }
+}
+
+/// Command to save the summary of a period and list. There is one per `period_key` and
+/// `list_key`: saving again replaces its text and tasks, keeping its id and `created_at`.
+/// Emits `summary-saved` with the stored row
+#[tauri::command]
+pub async fn create_summary(
+    app_handle: AppHandle,
+    period_key: String,
+    list_key: String,
+    task_ids: Vec<String>,
+    summary_text: String,
+) -> Result<Summary, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let task_ids_json = serde_json::to_string(&task_ids).map_err(|e| format!("Failed to serialize task ids: {}", e))?;
+    let mut tx = pool.begin().await.map_err(|e| CmdError::Db(format!("Failed to start transaction: {}", e)))?;
+
+    // No unique index to upsert on: older builds could store duplicates, so the newest wins
+    let existing = sqlx::query_as::<_, (String, i64)>(
+        "SELECT id, created_at FROM summaries WHERE period_key = ? AND list_key = ? ORDER BY created_at DESC LIMIT 1",
+    )
+    .bind(&period_key)
+    .bind(&list_key)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to look up summary: {}", e)))?;
+    let (id, created_at) = match existing {
+        Some((id, created_at)) => {
+            sqlx::query("UPDATE summaries SET task_ids = ?, summary_text = ?, updated_at = ? WHERE id = ?")
+                .bind(&task_ids_json)
+                .bind(&summary_text)
+                .bind(now)
+                .bind(&id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CmdError::Db(format!("Failed to update summary '{}': {}", id, e)))?;
+            (id, created_at)
+        }
+        None => {
+            let id = format!("summary-{}-{}", now, chrono::Utc::now().timestamp_subsec_nanos());
+            sqlx::query(
+                "INSERT INTO summaries (id, created_at, updated_at, period_key, list_key, task_ids, summary_text)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&id)
+            .bind(now)
+            .bind(now)
+            .bind(&period_key)
+            .bind(&list_key)
+            .bind(&task_ids_json)
+            .bind(&summary_text)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CmdError::Db(format!("Failed to create summary: {}", e)))?;
+            (id, now)
+        }
+    };
+    tx.commit().await.map_err(|e| CmdError::Db(format!("Failed to commit summary: {}", e)))?;
+
+    log::info!("[Summaries] Saved summary {} for {} / {}", id, period_key, list_key);
+    let saved = Summary { id, created_at, updated_at: now, period_key, list_key, task_ids, summary_text };
+    if let Err(e) = app_handle.emit("summary-saved", &saved) {
+        log::error!("[Summaries] Failed to emit summary-saved: {}", e);
+    }
+    Ok(saved)
+}
+
+/// Command for the summaries of a period, newest first
+#[tauri::command]
+pub async fn get_summaries(app_handle: AppHandle, period_key: String) -> Result<Vec<Summary>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let rows = sqlx::query_as::<_, SummaryRow>(
+        "SELECT id, created_at, updated_at, period_key, list_key, task_ids, summary_text
+         FROM summaries WHERE period_key = ? ORDER BY created_at DESC",
+    )
+    .bind(&period_key)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to load summaries: {}", e)))?;
+    Ok(rows.into_iter().map(summary).collect())
+}
@@ -0,0 +1,284 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_http::reqwest;
+
+use crate::error::CmdError;
+use crate::{db, paths};
+use crate::keychain::{self, WEBDAV_PASSWORD_ACCOUNT};
+
+/// Settings key for the WebDAV location; the password is kept in the keychain
+const SYNC_SETTINGS_KEY: &str = "sync";
+/// Name of the database copy in the WebDAV folder
+const REMOTE_FILE: &str = "tada.db";
+const SNAPSHOT_PREFIX: &str = "tada-sync-";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SyncSettings {
+    /// WebDAV folder the database is kept in, e.g. a Nextcloud `remote.php/dav/files/<user>/Tada`
+    pub url: String,
+    pub username: String,
+    /// Whether a password is saved in the keychain; never the password itself
+    pub has_password: bool,
+}
+
+/// Everything needed to reach the remote copy
+#[derive(Debug, Clone)]
+pub struct WebDavConfig {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+}
+
+impl WebDavConfig {
+    fn file_url(&self) -> String {
+        format!("{}/{}", self.url.trim_end_matches('/'), REMOTE_FILE)
+    }
+}
+
+/// What this machine last exchanged with the remote. Kept in a file beside the database, not
+/// in it, since a pull replaces the whole database
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct SyncState {
+    /// SHA-256 of the remote copy as of the last push or pull
+    last_hash: Option<String>,
+    last_synced_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    /// `push` or `pull`
+    pub direction: &'static str,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncResult {
+    /// False when there was nothing to do: the remote already matched
+    pub changed: bool,
+    pub hash: String,
+    pub synced_at: i64,
+}
+
+fn state_path(app: &AppHandle) -> Result<PathBuf, String> {
+    paths::app_config_dir(app).map(|dir| dir.join("sync-state.json"))
+}
+
+fn load_state(app: &AppHandle) -> SyncState {
+    state_path(app)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(app: &AppHandle, state: &SyncState) -> Result<(), String> {
+    let path = state_path(app)?;
+    let json = serde_json::to_string(state).map_err(|e| format!("Failed to serialize sync state: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// `sync` under the app data directory, for snapshots on their way up or down
+fn sync_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    paths::app_data_dir(app).map(|dir| dir.join("sync"))
+}
+
+async fn saved_settings(pool: &sqlx::SqlitePool) -> Result<SyncSettings, String> {
+    Ok(db::get_setting(pool, SYNC_SETTINGS_KEY)
+        .await?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+async fn config(app: &AppHandle) -> Result<WebDavConfig, String> {
+    let pool = db::pool(app).await?;
+    let settings = saved_settings(&pool).await?;
+    if settings.url.trim().is_empty() {
+        return Err("WebDAV sync is not set up".to_string());
+    }
+    Ok(WebDavConfig {
+        url: settings.url,
+        username: settings.username,
+        password: keychain::load_secret(WEBDAV_PASSWORD_ACCOUNT)?,
+    })
+}
+
+/// The remote copy, or `None` when nothing has been pushed yet
+async fn download(client: &reqwest::Client, config: &WebDavConfig) -> Result<Option<Vec<u8>>, String> {
+    let url = config.file_url();
+    let response = client
+        .get(&url)
+        .basic_auth(&config.username, Some(&config.password))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach {}: {}", url, e))?;
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND => Ok(None),
+        status if status.is_success() => response
+            .bytes()
+            .await
+            .map(|bytes| Some(bytes.to_vec()))
+            .map_err(|e| format!("Failed to download {}: {}", url, e)),
+        status => Err(format!("WebDAV server refused the download of {}: {}", url, status)),
+    }
+}
+
+async fn upload(client: &reqwest::Client, config: &WebDavConfig, bytes: Vec<u8>) -> Result<(), String> {
+    let url = config.file_url();
+    let put = || {
+        client
+            .put(&url)
+            .basic_auth(&config.username, Some(&config.password))
+            .body(bytes.clone())
+            .send()
+    };
+    let mut response = put().await.map_err(|e| format!("Failed to reach {}: {}", url, e))?;
+    if response.status() == reqwest::StatusCode::CONFLICT {
+        // 409 means the folder doesn't exist yet
+        let folder = config.url.trim_end_matches('/');
+        client
+            .request(reqwest::Method::from_bytes(b"MKCOL").map_err(|e| e.to_string())?, folder)
+            .basic_auth(&config.username, Some(&config.password))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", folder, e))?;
+        response = put().await.map_err(|e| format!("Failed to reach {}: {}", url, e))?;
+    }
+    if !response.status().is_success() {
+        return Err(format!("WebDAV server refused the upload to {}: {}", url, response.status()));
+    }
+    Ok(())
+}
+
+/// Reports a conflict to the frontend and as the command's error
+fn conflict(app: &AppHandle, direction: &'static str, reason: &str) -> String {
+    log::warn!("[Sync] Conflict on {}: {}", direction, reason);
+    let payload = SyncConflict { direction, reason: reason.to_string() };
+    if let Err(e) = app.emit("sync-conflict", &payload) {
+        log::error!("[Sync] Failed to emit sync-conflict: {}", e);
+    }
+    format!("Sync conflict: {}", reason)
+}
+
+/// Whether tasks or lists changed here after `since`
+async fn changed_locally(pool: &sqlx::SqlitePool, since: i64) -> Result<bool, String> {
+    let latest = sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT MAX(updated_at) FROM (
+             SELECT MAX(updated_at) AS updated_at FROM tasks
+             UNION ALL SELECT MAX(updated_at) FROM lists
+             UNION ALL SELECT MAX(updated_at) FROM subtasks
+         )",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Failed to read local changes: {}", e))?;
+    Ok(latest.is_some_and(|latest| latest > since))
+}
+
+/// Uploads a snapshot of the database. Refuses when the remote copy changed since this machine
+/// last synced, unless `force`
+pub async fn webdav_push(app: &AppHandle, config: &WebDavConfig, force: bool) -> Result<SyncResult, String> {
+    let client = reqwest::Client::new();
+    let state = load_state(app);
+    if !force
+        && let Some(remote) = download(&client, config).await?
+        && state.last_hash.as_deref() != Some(hash(&remote).as_str())
+    {
+        return Err(conflict(app, "push", "the remote copy changed since this device last synced"));
+    }
+
+    let path = crate::backup::snapshot(app, &sync_dir(app)?, SNAPSHOT_PREFIX).await?;
+    let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e));
+    let _ = std::fs::remove_file(&path);
+    let bytes = bytes?;
+    let pushed_hash = hash(&bytes);
+    upload(&client, config, bytes).await?;
+
+    let synced_at = chrono::Utc::now().timestamp_millis();
+    save_state(app, &SyncState { last_hash: Some(pushed_hash.clone()), last_synced_at: synced_at })?;
+    log::info!("[Sync] Pushed the database to {}", config.file_url());
+    Ok(SyncResult { changed: true, hash: pushed_hash, synced_at })
+}
+
+/// Downloads the remote copy and restores it, restarting the app. Refuses when tasks changed
+/// here since the last sync, unless `force`; nothing happens when the remote is unchanged
+pub async fn webdav_pull(app: &AppHandle, config: &WebDavConfig, force: bool) -> Result<SyncResult, String> {
+    let client = reqwest::Client::new();
+    let state = load_state(app);
+    let Some(remote) = download(&client, config).await? else {
+        return Err(format!("Nothing to pull: {} does not exist yet", config.file_url()));
+    };
+    let remote_hash = hash(&remote);
+    if state.last_hash.as_deref() == Some(remote_hash.as_str()) {
+        return Ok(SyncResult { changed: false, hash: remote_hash, synced_at: state.last_synced_at });
+    }
+    let pool = db::pool(app).await?;
+    if !force && state.last_hash.is_some() && changed_locally(&pool, state.last_synced_at).await? {
+        return Err(conflict(app, "pull", "both this device and the remote copy changed since the last sync"));
+    }
+
+    let dir = sync_dir(app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("{}remote.db", SNAPSHOT_PREFIX));
+    std::fs::write(&path, &remote).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    let restored = crate::backup::restore_database(app.clone(), path.to_string_lossy().into_owned()).await;
+    let _ = std::fs::remove_file(&path);
+    restored.map_err(|e| e.to_string())?;
+
+    let synced_at = chrono::Utc::now().timestamp_millis();
+    save_state(app, &SyncState { last_hash: Some(remote_hash.clone()), last_synced_at: synced_at })?;
+    log::info!("[Sync] Pulled the database from {}", config.file_url());
+    Ok(SyncResult { changed: true, hash: remote_hash, synced_at })
+}
+
+#[tauri::command]
+pub async fn get_sync_settings(app_handle: AppHandle) -> Result<SyncSettings, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let mut settings = saved_settings(&pool).await?;
+    settings.has_password = !keychain::load_secret(WEBDAV_PASSWORD_ACCOUNT)?.is_empty();
+    Ok(settings)
+}
+
+/// Command to save the WebDAV location. `password` goes to the keychain; leave it out to keep
+/// the saved one, or pass an empty string to remove it
+#[tauri::command]
+pub async fn set_sync_settings(
+    app_handle: AppHandle,
+    url: String,
+    username: String,
+    password: Option<String>,
+) -> Result<(), CmdError> {
+    if let Some(password) = password {
+        keychain::store_secret(WEBDAV_PASSWORD_ACCOUNT, &password)?;
+    }
+    let has_password = !keychain::load_secret(WEBDAV_PASSWORD_ACCOUNT)?.is_empty();
+    let settings = SyncSettings { url: url.trim().to_string(), username: username.trim().to_string(), has_password };
+    let value = serde_json::to_string(&settings).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let pool = db::pool(&app_handle).await?;
+    db::set_setting(&pool, SYNC_SETTINGS_KEY, &value).await.map_err(CmdError::Db)
+}
+
+/// Command to push this device's tasks to WebDAV; a conflict emits `sync-conflict` instead of
+/// overwriting, and `force` pushes anyway
+#[tauri::command]
+pub async fn sync_push(app_handle: AppHandle, force: Option<bool>) -> Result<SyncResult, CmdError> {
+    let config = config(&app_handle).await?;
+    Ok(webdav_push(&app_handle, &config, force.unwrap_or(false)).await?)
+}
+
+/// Command to replace this device's tasks with the WebDAV copy; a conflict emits
+/// `sync-conflict` instead of overwriting, and `force` pulls anyway
+#[tauri::command]
+pub async fn sync_pull(app_handle: AppHandle, force: Option<bool>) -> Result<SyncResult, CmdError> {
+    let config = config(&app_handle).await?;
+    Ok(webdav_pull(&app_handle, &config, force.unwrap_or(false)).await?)
+}
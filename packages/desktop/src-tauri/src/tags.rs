@@ -0,0 +1,169 @@
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::db;
+use crate::error::CmdError;
+
+/// A tag. The JSON `tasks.tags` column is kept in step with `task_tags` by triggers from the
+/// add_tags migration, so older code reading or writing it keeps working
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    /// Live tasks carrying the tag
+    pub task_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaggedTask {
+    pub id: String,
+    pub title: String,
+    pub list_name: String,
+    pub completed: bool,
+    pub due_date: Option<i64>,
+}
+
+/// Command listing every tag by name, with how many tasks use it
+#[tauri::command]
+pub async fn list_tags(app_handle: AppHandle) -> Result<Vec<Tag>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let rows = sqlx::query_as::<_, (i64, String, Option<String>, i64)>(
+        "SELECT g.id, g.name, g.color, COUNT(t.id)
+         FROM tags g
+         LEFT JOIN task_tags tt ON tt.tag_id = g.id
+         LEFT JOIN tasks t ON t.id = tt.task_id AND t.deleted_at IS NULL
+         GROUP BY g.id
+         ORDER BY g.name COLLATE NOCASE",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read tags: {}", e)))?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, name, color, task_count)| Tag { id, name, color, task_count })
+        .collect())
+}
+
+/// Command to rename a tag on every task in one transaction. Renaming onto an existing tag
+/// merges the two. Returns how many tasks carry the renamed tag
+#[tauri::command]
+pub async fn rename_tag(app_handle: AppHandle, old_name: String, new_name: String) -> Result<u64, CmdError> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err(CmdError::Validation("Tag name is empty".to_string()));
+    }
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| CmdError::Db(format!("Failed to start transaction: {}", e)))?;
+    let old_id = sqlx::query_scalar::<_, i64>("SELECT id FROM tags WHERE name = ?")
+        .bind(&old_name)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to look up tag '{}': {}", old_name, e)))?
+        .ok_or_else(|| CmdError::NotFound(format!("Tag '{}' not found", old_name)))?;
+    let existing_id = sqlx::query_scalar::<_, i64>("SELECT id FROM tags WHERE name = ?")
+        .bind(new_name)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to look up tag '{}': {}", new_name, e)))?;
+
+    let target_id = match existing_id {
+        Some(target_id) if target_id != old_id => {
+            sqlx::query(
+                "INSERT INTO task_tags (task_id, tag_id)
+                 SELECT task_id, ?1 FROM task_tags
+                 WHERE tag_id = ?2 AND task_id NOT IN (SELECT task_id FROM task_tags WHERE tag_id = ?1)",
+            )
+            .bind(target_id)
+            .bind(old_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CmdError::Db(format!("Failed to merge tag '{}': {}", old_name, e)))?;
+            for sql in ["DELETE FROM task_tags WHERE tag_id = ?", "DELETE FROM tags WHERE id = ?"] {
+                sqlx::query(sql)
+                    .bind(old_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| CmdError::Db(format!("Failed to merge tag '{}': {}", old_name, e)))?;
+            }
+            target_id
+        }
+        _ => {
+            sqlx::query("UPDATE tags SET name = ? WHERE id = ?")
+                .bind(new_name)
+                .bind(old_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CmdError::Db(format!("Failed to rename tag '{}': {}", old_name, e)))?;
+            old_id
+        }
+    };
+    let tasks = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM task_tags WHERE tag_id = ?")
+        .bind(target_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to count tagged tasks: {}", e)))?;
+    tx.commit().await.map_err(|e| CmdError::Db(format!("Failed to commit transaction: {}", e)))?;
+    log::info!("[Tags] Renamed '{}' to '{}' on {} tasks", old_name, new_name, tasks);
+    Ok(tasks as u64)
+}
+
+/// Command to set or clear (`None`) a tag's color
+#[tauri::command]
+pub async fn set_tag_color(app_handle: AppHandle, tag_id: i64, color: Option<String>) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query("UPDATE tags SET color = ? WHERE id = ?")
+        .bind(color)
+        .bind(tag_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to set tag color: {}", e)))?;
+    Ok(())
+}
+
+/// Command replacing a task's tags with `tag_ids`, kept in the given order
+#[tauri::command]
+pub async fn set_task_tags(app_handle: AppHandle, task_id: String, tag_ids: Vec<i64>) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| CmdError::Db(format!("Failed to start transaction: {}", e)))?;
+    sqlx::query("DELETE FROM task_tags WHERE task_id = ?")
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to clear tags of task '{}': {}", task_id, e)))?;
+    for tag_id in tag_ids {
+        sqlx::query(
+            "INSERT INTO task_tags (task_id, tag_id)
+             SELECT ?1, id FROM tags
+             WHERE id = ?2 AND NOT EXISTS (SELECT 1 FROM task_tags WHERE task_id = ?1 AND tag_id = ?2)",
+        )
+        .bind(&task_id)
+        .bind(tag_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to tag task '{}': {}", task_id, e)))?;
+    }
+    tx.commit().await.map_err(|e| CmdError::Db(format!("Failed to commit transaction: {}", e)))
+}
+
+/// Command listing the live tasks carrying a tag, in list order
+#[tauri::command]
+pub async fn tasks_by_tag(app_handle: AppHandle, tag_id: i64) -> Result<Vec<TaggedTask>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let rows = sqlx::query_as::<_, (String, String, String, bool, Option<i64>)>(
+        r#"SELECT t.id, t.title, t.list_name, t.completed, t.due_date
+           FROM task_tags tt JOIN tasks t ON t.id = tt.task_id
+           WHERE tt.tag_id = ? AND t.deleted_at IS NULL
+           ORDER BY t.completed, t.pinned DESC, t."order""#,
+    )
+    .bind(tag_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read tagged tasks: {}", e)))?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, title, list_name, completed, due_date)| TaggedTask { id, title, list_name, completed, due_date })
+        .collect())
+}
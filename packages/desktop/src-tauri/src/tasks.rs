@@ -0,0 +1,711 @@
+use chrono::{Days, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::CmdError;
+use crate::priority::Priority;
+use crate::tray::local_midnight_millis;
+use crate::{db, AppState};
+
+/// Values `group_category` can take, as the frontend's `TaskGroupCategory`
+const GROUP_CATEGORIES: &[&str] = &["overdue", "today", "next7days", "later", "nodate"];
+
+/// Days after today, today included, that still count as `next7days`
+const NEXT_DAYS: i64 = 6;
+
+type CategoryRow = (String, bool, String, Option<i64>, String);
+
+/// Just enough of a task for a glance: the focus widget, the tray
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgendaTask {
+    pub id: String,
+    pub title: String,
+    pub due_date: i64,
+    pub list_name: String,
+    pub priority: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Agenda {
+    pub overdue: Vec<AgendaTask>,
+    pub today: Vec<AgendaTask>,
+    /// Due from tomorrow through `days_ahead` days from today
+    pub upcoming: Vec<AgendaTask>,
+}
+
+/// Fields `bulk_update_tasks` sets on every task it is given; the ones left out are kept
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaskPatch {
+    pub list_id: Option<String>,
+    pub completed: Option<bool>,
+    /// Rejected unless 1 to 3; `null` leaves it as is
+    pub priority: Option<Priority>,
+    pub group_category: Option<String>,
+}
+
+async fn set_pinned(app: &AppHandle, id: &str, pinned: bool) -> Result<(), String> {
+    let pool = db::pool(app).await?;
+    let updated = sqlx::query("UPDATE tasks SET pinned = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL")
+        .bind(pinned)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to update task '{}': {}", id, e))?
+        .rows_affected();
+    if updated == 0 {
+        return Err(format!("Task '{}' not found", id));
+    }
+    if let Err(e) = app.emit("task-pinned", serde_json::json!({ "id": id, "pinned": pinned })) {
+        log::error!("[Tasks] Failed to emit task-pinned: {}", e);
+    }
+    Ok(())
+}
+
+/// Command to pin a task: within its group it sorts above unpinned tasks, keeping `order`
+/// among the pinned ones
+#[tauri::command]
+pub async fn pin_task(app_handle: AppHandle, id: String) -> Result<(), CmdError> {
+    Ok(set_pinned(&app_handle, &id, true).await?)
+}
+
+#[tauri::command]
+pub async fn unpin_task(app_handle: AppHandle, id: String) -> Result<(), CmdError> {
+    Ok(set_pinned(&app_handle, &id, false).await?)
+}
+
+/// Command to apply one patch to many tasks in a single transaction, e.g. moving a selection
+/// to another list. Fails as a whole, listing them, when any id isn't a live task. Returns the
+/// number of tasks updated and emits one `tasks-changed`
+#[tauri::command]
+pub async fn bulk_update_tasks(app_handle: AppHandle, ids: Vec<String>, patch: TaskPatch) -> Result<u64, CmdError> {
+    let mut seen = HashSet::new();
+    let ids: Vec<String> = ids.into_iter().filter(|id| seen.insert(id.clone())).collect();
+    if ids.is_empty() {
+        return Ok(0);
+    }
+    if let Some(category) = &patch.group_category
+        && !GROUP_CATEGORIES.contains(&category.as_str())
+    {
+        return Err(CmdError::Validation(format!("Unknown group category '{}'", category)));
+    }
+
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| CmdError::Db(format!("Failed to start transaction: {}", e)))?;
+    let placeholders = vec!["?"; ids.len()].join(", ");
+
+    let select = format!("SELECT id FROM tasks WHERE deleted_at IS NULL AND id IN ({})", placeholders);
+    let mut query = sqlx::query_scalar::<_, String>(&select);
+    for id in &ids {
+        query = query.bind(id);
+    }
+    let found: HashSet<String> = query
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to look up tasks: {}", e)))?
+        .into_iter()
+        .collect();
+    let missing: Vec<&str> = ids.iter().filter(|id| !found.contains(*id)).map(String::as_str).collect();
+    if !missing.is_empty() {
+        return Err(CmdError::NotFound(format!("Tasks not found: {}", missing.join(", "))));
+    }
+
+    let list_name = match &patch.list_id {
+        Some(list_id) => Some(
+            sqlx::query_scalar::<_, String>("SELECT name FROM lists WHERE id = ? AND deleted_at IS NULL")
+                .bind(list_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| CmdError::Db(format!("Failed to look up list '{}': {}", list_id, e)))?
+                .ok_or_else(|| CmdError::NotFound(format!("List '{}' not found", list_id)))?,
+        ),
+        None => None,
+    };
+
+    let mut assignments = Vec::new();
+    if patch.list_id.is_some() {
+        assignments.push("list_id = ?, list_name = ?");
+    }
+    if patch.completed.is_some() {
+        // Same as ticking the checkbox: completing fills the progress, reopening clears it
+        assignments.push(
+            "completed = ?, completed_at = CASE WHEN ? THEN COALESCE(completed_at, ?) END, \
+             complete_percentage = CASE WHEN ? THEN 100 END",
+        );
+    }
+    if patch.priority.is_some() {
+        assignments.push("priority = ?");
+    }
+    if patch.group_category.is_some() {
+        assignments.push("group_category = ?");
+    }
+    if assignments.is_empty() {
+        return Err(CmdError::Validation("Nothing to update: the patch sets no fields".to_string()));
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let update = format!(
+        "UPDATE tasks SET {}, updated_at = ? WHERE id IN ({})",
+        assignments.join(", "),
+        placeholders
+    );
+    let mut query = sqlx::query(&update);
+    if let Some(list_id) = &patch.list_id {
+        query = query.bind(list_id).bind(list_name);
+    }
+    if let Some(completed) = patch.completed {
+        query = query.bind(completed).bind(completed).bind(now).bind(completed);
+    }
+    if let Some(priority) = patch.priority {
+        query = query.bind(priority.to_db());
+    }
+    if let Some(category) = &patch.group_category {
+        query = query.bind(category);
+    }
+    query = query.bind(now);
+    for id in &ids {
+        query = query.bind(id);
+    }
+    let updated = query
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to update tasks: {}", e)))?
+        .rows_affected();
+
+    // Completing a task or moving it to or from the Trash changes its group, unless the patch
+    // names one
+    if patch.group_category.is_none() && (patch.list_id.is_some() || patch.completed.is_some()) {
+        let today = Local::now().date_naive();
+        let select = format!(
+            "SELECT id, completed, list_name, due_date, group_category FROM tasks WHERE id IN ({})",
+            placeholders
+        );
+        let mut query = sqlx::query_as::<_, CategoryRow>(&select);
+        for id in &ids {
+            query = query.bind(id);
+        }
+        let rows = query
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| CmdError::Db(format!("Failed to read tasks: {}", e)))?;
+        for (id, completed, list_name, due_date, current) in rows {
+            let category = group_category(completed, &list_name, due_date, today);
+            if category == current {
+                continue;
+            }
+            sqlx::query("UPDATE tasks SET group_category = ? WHERE id = ?")
+                .bind(category)
+                .bind(&id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CmdError::Db(format!("Failed to update task '{}': {}", id, e)))?;
+        }
+    }
+    tx.commit().await.map_err(|e| CmdError::Db(format!("Failed to commit task updates: {}", e)))?;
+
+    log::info!("[Tasks] Bulk update applied to {} tasks", updated);
+    if let Err(e) = app_handle.emit("tasks-changed", serde_json::json!({ "ids": ids })) {
+        log::error!("[Tasks] Failed to emit tasks-changed: {}", e);
+    }
+    Ok(updated)
+}
+
+/// The "All Tasks" group a task belongs in on local day `today`, by the same rules as the
+/// frontend's `getTaskGroupCategory`: done and trashed tasks have no group, then due day
+/// before today, today, within the next week (tomorrow included), or later
+pub(crate) fn group_category(
+    completed: bool,
+    list_name: &str,
+    due_date: Option<i64>,
+    today: NaiveDate,
+) -> &'static str {
+    if completed || list_name == "Trash" {
+        return "nodate";
+    }
+    let Some(due_day) = due_date.and_then(|due| Local.timestamp_millis_opt(due).single()).map(|due| due.date_naive())
+    else {
+        return "nodate";
+    };
+    match (due_day - today).num_days() {
+        ..0 => "overdue",
+        0 => "today",
+        1..=NEXT_DAYS => "next7days",
+        _ => "later",
+    }
+}
+
+/// Brings every task's `group_category` up to date for today. Returns the ids that moved
+async fn recompute(app: &AppHandle) -> Result<Vec<String>, String> {
+    let pool = db::pool(app).await?;
+    let today = Local::now().date_naive();
+    let rows = sqlx::query_as::<_, CategoryRow>(
+        "SELECT id, completed, list_name, due_date, group_category FROM tasks WHERE deleted_at IS NULL",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to read tasks: {}", e))?;
+
+    let mut tx = pool.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let mut changed = Vec::new();
+    for (id, completed, list_name, due_date, current) in rows {
+        let category = group_category(completed, &list_name, due_date, today);
+        if category == current {
+            continue;
+        }
+        // updated_at is left alone: the task itself didn't change, only the day did
+        sqlx::query("UPDATE tasks SET group_category = ? WHERE id = ?")
+            .bind(category)
+            .bind(&id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to update task '{}': {}", id, e))?;
+        changed.push(id);
+    }
+    tx.commit().await.map_err(|e| format!("Failed to commit group categories: {}", e))?;
+
+    if !changed.is_empty() {
+        log::info!("[Tasks] Recomputed the group of {} tasks", changed.len());
+        if let Err(e) = app.emit("tasks-changed", serde_json::json!({ "ids": changed })) {
+            log::error!("[Tasks] Failed to emit tasks-changed: {}", e);
+        }
+    }
+    Ok(changed)
+}
+
+/// Command to recompute every task's group now. Returns the number of tasks that moved
+#[tauri::command]
+pub async fn recompute_group_categories(app_handle: AppHandle) -> Result<usize, CmdError> {
+    Ok(recompute(&app_handle).await?.len())
+}
+
+/// Recomputes groups once per local day; run from the scheduler thread, whose first pass
+/// at startup covers the days the app was closed
+pub fn run_due_recompute(app: &AppHandle) {
+    let today = Local::now().date_naive();
+    let state = app.state::<AppState>();
+    if state.categories_day.lock().is_ok_and(|day| *day == Some(today)) {
+        return;
+    }
+    match tauri::async_runtime::block_on(recompute(app)) {
+        Ok(_) => {
+            if let Ok(mut day) = state.categories_day.lock() {
+                *day = Some(today);
+            }
+        }
+        Err(e) => log::warn!("[Tasks] Group recompute failed: {}", e),
+    }
+}
+
+/// Command for the open tasks due up to `days_ahead` days from today, split at local day
+/// boundaries and ordered by due date, then `order`
+#[tauri::command]
+pub async fn get_agenda(app_handle: AppHandle, days_ahead: u32) -> Result<Agenda, CmdError> {
+    let today = Local::now().date_naive();
+    let start_of_today = local_midnight_millis(today);
+    let start_of_tomorrow = local_midnight_millis(today + Days::new(1));
+    let end = local_midnight_millis(today + Days::new(u64::from(days_ahead) + 1));
+
+    let pool = db::pool(&app_handle).await?;
+    let rows = sqlx::query_as::<_, (String, String, i64, String, Option<i64>)>(
+        r#"SELECT id, title, due_date, list_name, priority FROM tasks
+           WHERE completed = 0 AND list_name != 'Trash' AND deleted_at IS NULL AND due_date < ?
+           ORDER BY due_date, "order""#,
+    )
+    .bind(end)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to load the agenda: {}", e)))?;
+
+    let mut agenda = Agenda::default();
+    for (id, title, due_date, list_name, priority) in rows {
+        let task = AgendaTask { id, title, due_date, list_name, priority };
+        if due_date < start_of_today {
+            agenda.overdue.push(task);
+        } else if due_date < start_of_tomorrow {
+            agenda.today.push(task);
+        } else {
+            agenda.upcoming.push(task);
+        }
+    }
+    Ok(agenda)
+}
+
+/// Gap between neighbours' `order` below which a reorder renumbers the group instead of taking
+/// the midpoint; every drop between the same pair halves it
+const MIN_ORDER_GAP: f64 = 1e-6;
+
+/// A task's place within its list and group
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskOrder {
+    pub id: String,
+    pub order: f64,
+    pub pinned: bool,
+}
+
+/// The live tasks of one list and group as they are shown: pinned first, then by `order`.
+/// The column has INTEGER affinity, so whole orders come back as integers and are cast to
+/// sit alongside the midpoints `reorder_task` writes
+async fn scope_order(
+    conn: &mut sqlx::SqliteConnection,
+    list_id: Option<&str>,
+    group_category: &str,
+) -> Result<Vec<TaskOrder>, String> {
+    let rows = sqlx::query_as::<_, (String, f64, bool)>(
+        r#"SELECT id, CAST("order" AS REAL), pinned FROM tasks
+           WHERE deleted_at IS NULL AND list_id IS ? AND group_category = ?
+           ORDER BY pinned DESC, "order", created_at"#,
+    )
+    .bind(list_id)
+    .bind(group_category)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to read task order: {}", e))?;
+    Ok(rows.into_iter().map(|(id, order, pinned)| TaskOrder { id, order, pinned }).collect())
+}
+
+/// Rewrites `order` to 0..N in the given sequence. `updated_at` is left alone, since the tasks
+/// keep their places relative to each other
+async fn renumber(conn: &mut sqlx::SqliteConnection, tasks: &mut [TaskOrder]) -> Result<(), String> {
+    for (index, task) in tasks.iter_mut().enumerate() {
+        task.order = index as f64;
+        sqlx::query(r#"UPDATE tasks SET "order" = ? WHERE id = ?"#)
+            .bind(task.order)
+            .bind(&task.id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to update task '{}': {}", task.id, e))?;
+    }
+    Ok(())
+}
+
+/// Command to rewrite `order` to a clean 0..N sequence within each group of the list, or of
+/// every list when `list_id` is left out. Returns the number of tasks renumbered and emits
+/// `tasks-changed`
+#[tauri::command]
+pub async fn normalize_order(app_handle: AppHandle, list_id: Option<String>) -> Result<usize, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| CmdError::Db(format!("Failed to start transaction: {}", e)))?;
+    let scopes = sqlx::query_as::<_, (Option<String>, String)>(
+        "SELECT DISTINCT list_id, group_category FROM tasks WHERE deleted_at IS NULL AND (? IS NULL OR list_id = ?)",
+    )
+    .bind(&list_id)
+    .bind(&list_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read task groups: {}", e)))?;
+    let mut ids = Vec::new();
+    for (scope_list_id, group_category) in scopes {
+        let mut tasks = scope_order(&mut tx, scope_list_id.as_deref(), &group_category).await?;
+        renumber(&mut tx, &mut tasks).await?;
+        ids.extend(tasks.into_iter().map(|task| task.id));
+    }
+    tx.commit().await.map_err(|e| CmdError::Db(format!("Failed to commit task order: {}", e)))?;
+
+    log::info!("[Tasks] Renumbered {} tasks", ids.len());
+    if !ids.is_empty()
+        && let Err(e) = app_handle.emit("tasks-changed", serde_json::json!({ "ids": ids }))
+    {
+        log::error!("[Tasks] Failed to emit tasks-changed: {}", e);
+    }
+    Ok(ids.len())
+}
+
+/// Command to move a task to `new_index` among the tasks of its list and group, as they are
+/// shown. A pinned task stays among the pinned ones and an unpinned one below them, so the
+/// index is clamped to that part. The task gets an `order` between its new neighbours, and
+/// the whole group is renumbered when they are too close for that. Returns the group's new
+/// ordering and emits `tasks-changed`
+#[tauri::command]
+pub async fn reorder_task(app_handle: AppHandle, id: String, new_index: usize) -> Result<Vec<TaskOrder>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| CmdError::Db(format!("Failed to start transaction: {}", e)))?;
+    let (list_id, group_category) = sqlx::query_as::<_, (Option<String>, String)>(
+        "SELECT list_id, group_category FROM tasks WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(&id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to look up task '{}': {}", id, e)))?
+    .ok_or_else(|| CmdError::NotFound(format!("Task '{}' not found", id)))?;
+
+    let mut tasks = scope_order(&mut tx, list_id.as_deref(), &group_category).await?;
+    let from = tasks
+        .iter()
+        .position(|task| task.id == id)
+        .ok_or_else(|| CmdError::NotFound(format!("Task '{}' not found", id)))?;
+    let mut moved = tasks.remove(from);
+    let pinned_count = tasks.iter().filter(|task| task.pinned).count();
+    let index = if moved.pinned { new_index.min(pinned_count) } else { new_index.clamp(pinned_count, tasks.len()) };
+
+    let segment_start = if moved.pinned { 0 } else { pinned_count };
+    let segment_end = if moved.pinned { pinned_count } else { tasks.len() };
+    let prev = (index > segment_start).then(|| tasks[index - 1].order);
+    let next = (index < segment_end).then(|| tasks[index].order);
+    let order = match (prev, next) {
+        (Some(prev), Some(next)) if next - prev >= MIN_ORDER_GAP * 2.0 => Some(prev + (next - prev) / 2.0),
+        (Some(_), Some(_)) => None,
+        (Some(prev), None) => Some(prev + 1.0),
+        (None, Some(next)) => Some(next - 1.0),
+        (None, None) => Some(0.0),
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    sqlx::query(r#"UPDATE tasks SET "order" = ?, updated_at = ? WHERE id = ?"#)
+        .bind(order.unwrap_or(moved.order))
+        .bind(now)
+        .bind(&id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to update task '{}': {}", id, e)))?;
+    moved.order = order.unwrap_or(moved.order);
+    tasks.insert(index, moved);
+    let changed: Vec<String> = if order.is_some() {
+        vec![id]
+    } else {
+        log::info!("[Tasks] Task order in '{}' too dense, renumbering {} tasks", group_category, tasks.len());
+        renumber(&mut tx, &mut tasks).await?;
+        tasks.iter().map(|task| task.id.clone()).collect()
+    };
+    tx.commit().await.map_err(|e| CmdError::Db(format!("Failed to commit task order: {}", e)))?;
+
+    if let Err(e) = app_handle.emit("tasks-changed", serde_json::json!({ "ids": changed })) {
+        log::error!("[Tasks] Failed to emit tasks-changed: {}", e);
+    }
+    Ok(tasks)
+}
+
+/// `order` for a task placed right after `id` in `tasks`, or `None` when the next one is too
+/// close to fit another between
+fn order_after(tasks: &[TaskOrder], id: &str) -> Option<f64> {
+    let position = tasks.iter().position(|task| task.id == id)?;
+    let original = &tasks[position];
+    match tasks.get(position + 1).filter(|next| next.pinned == original.pinned) {
+        Some(next) if next.order - original.order >= MIN_ORDER_GAP * 2.0 => {
+            Some(original.order + (next.order - original.order) / 2.0)
+        }
+        Some(_) => None,
+        None => Some(original.order + 1.0),
+    }
+}
+
+/// Command to copy a task, titled "<title> (copy)" and placed right after it, or last in its
+/// group when that differs from the original's, as for a finished task. The copy is open
+/// whatever the original's state, keeps its tags and priority, and gets the subtasks too when
+/// `include_subtasks`, unchecked. Returns the new id and emits `tasks-changed`
+#[tauri::command]
+pub async fn duplicate_task(app_handle: AppHandle, id: String, include_subtasks: bool) -> Result<String, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| CmdError::Db(format!("Failed to start transaction: {}", e)))?;
+    let (list_id, list_name, due_date, group) = sqlx::query_as::<_, (Option<String>, String, Option<i64>, String)>(
+        "SELECT list_id, list_name, due_date, group_category FROM tasks WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(&id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to look up task '{}': {}", id, e)))?
+    .ok_or_else(|| CmdError::NotFound(format!("Task '{}' not found", id)))?;
+
+    let category = group_category(false, &list_name, due_date, Local::now().date_naive());
+    let mut tasks = scope_order(&mut tx, list_id.as_deref(), category).await?;
+    let mut changed = Vec::new();
+    let order = if category != group {
+        // A done original has no group while its open copy may have one; the copy goes last there
+        tasks.iter().map(|task| task.order).reduce(f64::max).map_or(0.0, |last| last + 1.0)
+    } else {
+        match order_after(&tasks, &id) {
+            Some(order) => order,
+            None => {
+                renumber(&mut tx, &mut tasks).await?;
+                changed.extend(tasks.iter().map(|task| task.id.clone()));
+                order_after(&tasks, &id).unwrap_or_default()
+            }
+        }
+    };
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let copy_id = format!("task-{}-{}", now, chrono::Utc::now().timestamp_subsec_nanos());
+    sqlx::query(
+        r#"INSERT INTO tasks (id, title, completed, completed_at, complete_percentage, due_date, list_id, list_name,
+                              content, "order", created_at, updated_at, tags, priority, group_category, recurrence,
+                              estimated_minutes, pinned)
+           SELECT ?, title || ' (copy)', 0, NULL, NULL, due_date, list_id, list_name,
+                  content, ?, ?, ?, tags, priority, ?, recurrence,
+                  estimated_minutes, pinned
+           FROM tasks WHERE id = ?"#,
+    )
+    .bind(&copy_id)
+    .bind(order)
+    .bind(now)
+    .bind(now)
+    .bind(category)
+    .bind(&id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to copy task '{}': {}", id, e)))?;
+
+    if include_subtasks {
+        let mut pending = sqlx::query_as::<_, (String, Option<String>)>(
+            "SELECT id, parent_subtask_id FROM subtasks WHERE parent_id = ?",
+        )
+        .bind(&id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to read subtasks: {}", e)))?;
+        let originals: HashSet<String> = pending.iter().map(|(subtask_id, _)| subtask_id.clone()).collect();
+        // Parents go in before their children; a parent that is gone puts the child at the top
+        let mut copies = HashMap::new();
+        while !pending.is_empty() {
+            let (ready, waiting): (Vec<_>, Vec<_>) = pending.into_iter().partition(|(_, parent)| {
+                parent.as_ref().is_none_or(|parent| !originals.contains(parent) || copies.contains_key(parent))
+            });
+            if ready.is_empty() {
+                return Err(CmdError::Db(format!("The subtasks of task '{}' are nested in a cycle", id)));
+            }
+            for (subtask_id, parent) in ready {
+                let new_id =
+                    format!("subtask-{}-{}-{}", now, chrono::Utc::now().timestamp_subsec_nanos(), copies.len());
+                let new_parent = parent.and_then(|parent| copies.get(&parent).cloned());
+                sqlx::query(
+                    r#"INSERT INTO subtasks (id, parent_id, parent_subtask_id, title, completed, completed_at, due_date,
+                                             "order", created_at, updated_at)
+                       SELECT ?, ?, ?, title, 0, NULL, due_date, "order", ?, ? FROM subtasks WHERE id = ?"#,
+                )
+                .bind(&new_id)
+                .bind(&copy_id)
+                .bind(&new_parent)
+                .bind(now)
+                .bind(now)
+                .bind(&subtask_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CmdError::Db(format!("Failed to copy subtask '{}': {}", subtask_id, e)))?;
+                copies.insert(subtask_id, new_id);
+            }
+            pending = waiting;
+        }
+    }
+    tx.commit().await.map_err(|e| CmdError::Db(format!("Failed to commit the task copy: {}", e)))?;
+
+    log::info!("[Tasks] Duplicated task {} as {}", id, copy_id);
+    changed.push(copy_id.clone());
+    if let Err(e) = app_handle.emit("tasks-changed", serde_json::json!({ "ids": changed })) {
+        log::error!("[Tasks] Failed to emit tasks-changed: {}", e);
+    }
+    Ok(copy_id)
+}
+
+/// Command to move a task to another list, taking the list's current name along with its id.
+/// Its group is recomputed, since leaving the trash changes it. Emits `tasks-changed`
+#[tauri::command]
+pub async fn move_task(app_handle: AppHandle, task_id: String, list_id: String) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| CmdError::Db(format!("Failed to start transaction: {}", e)))?;
+    let list_name = sqlx::query_scalar::<_, String>("SELECT name FROM lists WHERE id = ? AND deleted_at IS NULL")
+        .bind(&list_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to look up list '{}': {}", list_id, e)))?
+        .ok_or_else(|| CmdError::NotFound(format!("List '{}' not found", list_id)))?;
+    let (completed, due_date) = sqlx::query_as::<_, (bool, Option<i64>)>(
+        "SELECT completed, due_date FROM tasks WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(&task_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to look up task '{}': {}", task_id, e)))?
+    .ok_or_else(|| CmdError::NotFound(format!("Task '{}' not found", task_id)))?;
+
+    let category = group_category(completed, &list_name, due_date, Local::now().date_naive());
+    sqlx::query("UPDATE tasks SET list_id = ?, list_name = ?, group_category = ?, updated_at = ? WHERE id = ?")
+        .bind(&list_id)
+        .bind(&list_name)
+        .bind(category)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to move task '{}': {}", task_id, e)))?;
+    tx.commit().await.map_err(|e| CmdError::Db(format!("Failed to commit the move: {}", e)))?;
+
+    log::info!("[Tasks] Moved task {} to list '{}'", task_id, list_name);
+    if let Err(e) = app_handle.emit("tasks-changed", serde_json::json!({ "ids": [task_id] })) {
+        log::error!("[Tasks] Failed to emit tasks-changed: {}", e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Noon local time on `day`, clear of DST transitions
+    fn due_on(day: NaiveDate) -> Option<i64> {
+        let noon = day.and_hms_opt(12, 0, 0).unwrap();
+        Some(Local.from_local_datetime(&noon).earliest().unwrap().timestamp_millis())
+    }
+
+    #[test]
+    fn groups_by_due_day() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 12).unwrap();
+        let category = |days: i64| {
+            let day = today.checked_add_signed(chrono::Duration::days(days)).unwrap();
+            group_category(false, "Inbox", due_on(day), today)
+        };
+        assert_eq!(category(-30), "overdue");
+        assert_eq!(category(-1), "overdue");
+        assert_eq!(category(0), "today");
+        assert_eq!(category(1), "next7days");
+        assert_eq!(category(NEXT_DAYS), "next7days");
+        assert_eq!(category(NEXT_DAYS + 1), "later");
+        assert_eq!(category(365), "later");
+    }
+
+    #[test]
+    fn pinned_tasks_sort_first() {
+        tauri::async_runtime::block_on(async {
+            let pool = db::test_pool().await;
+            let rows =
+                [("low-order", 0.0, false), ("pinned", 5.0, true), ("midpoint", 4.5, false), ("high-order", 9.0, false)];
+            for (id, order, pinned) in rows {
+                sqlx::query(
+                    r#"INSERT INTO tasks (id, title, list_id, list_name, "order", created_at, updated_at, pinned)
+                       VALUES (?, 'Task', 'inbox-default', 'Inbox', ?, 0, 0, ?)"#,
+                )
+                .bind(id)
+                .bind(order)
+                .bind(pinned)
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+            let mut conn = pool.acquire().await.unwrap();
+            let tasks = scope_order(&mut conn, Some("inbox-default"), "nodate").await.unwrap();
+            let ids: Vec<&str> = tasks.iter().map(|task| task.id.as_str()).collect();
+            assert_eq!(ids, ["pinned", "low-order", "midpoint", "high-order"]);
+            assert!(tasks[0].pinned);
+        });
+    }
+
+    #[test]
+    fn undated_done_and_trashed_tasks_have_no_group() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 12).unwrap();
+        assert_eq!(group_category(false, "Inbox", None, today), "nodate");
+        assert_eq!(group_category(true, "Inbox", due_on(today), today), "nodate");
+        assert_eq!(group_category(false, "Trash", due_on(today), today), "nodate");
+    }
+
+    #[test]
+    fn groups_by_local_day_not_time() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 12).unwrap();
+        let late = today.and_hms_opt(23, 59, 0).unwrap();
+        let late = Local.from_local_datetime(&late).earliest().unwrap().timestamp_millis();
+        assert_eq!(group_category(false, "Inbox", Some(late), today), "today");
+        let early = today.succ_opt().unwrap().and_hms_opt(0, 1, 0).unwrap();
+        let early = Local.from_local_datetime(&early).earliest().unwrap().timestamp_millis();
+        assert_eq!(group_category(false, "Inbox", Some(early), today), "next7days");
+    }
+}
@@ -0,0 +1,136 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::db;
+use crate::error::CmdError;
+
+const MINUTE_MILLIS: i64 = 60 * 1000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeEntry {
+    pub id: i64,
+    pub task_id: String,
+    pub started_at: i64,
+    /// `None` while the timer is running
+    pub ended_at: Option<i64>,
+}
+
+/// Tracked time for one list over a report's period
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListTimeReport {
+    pub list_name: String,
+    pub tracked_minutes: i64,
+    /// Summed estimates of the tasks time was tracked on
+    pub estimated_minutes: i64,
+    pub task_count: i64,
+}
+
+/// Command to start a timer on a task. Refused while one is already running on it
+#[tauri::command]
+pub async fn start_timer(app_handle: AppHandle, task_id: String) -> Result<TimeEntry, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let now = chrono::Utc::now().timestamp_millis();
+    // The partial unique index allows one open entry per task, so two racing starts can't
+    // both get in
+    let id = sqlx::query("INSERT INTO time_entries (task_id, started_at) VALUES (?, ?)")
+        .bind(&task_id)
+        .bind(now)
+        .execute(&pool)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db_error) if db_error.is_unique_violation() => {
+                CmdError::Validation(format!("A timer is already running on task '{}'", task_id))
+            }
+            _ => CmdError::Db(format!("Failed to start timer: {}", e)),
+        })?
+        .last_insert_rowid();
+    log::info!("[TimeTracking] Timer {} started on task {}", id, task_id);
+    Ok(TimeEntry { id, task_id, started_at: now, ended_at: None })
+}
+
+/// Command to stop a task's running timer: closes its entry and adds the elapsed minutes,
+/// rounded, to the task's `actual_minutes`. Emits `task-time-tracked` with the new total
+#[tauri::command]
+pub async fn stop_timer(app_handle: AppHandle, task_id: String) -> Result<TimeEntry, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut tx = pool.begin().await.map_err(|e| CmdError::Db(format!("Failed to start transaction: {}", e)))?;
+    let (id, started_at) = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT id, started_at FROM time_entries WHERE task_id = ? AND ended_at IS NULL",
+    )
+    .bind(&task_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read timer: {}", e)))?
+    .ok_or_else(|| CmdError::NotFound(format!("No timer is running on task '{}'", task_id)))?;
+    let ended_at = now.max(started_at);
+    let minutes = (ended_at - started_at + MINUTE_MILLIS / 2) / MINUTE_MILLIS;
+
+    sqlx::query("UPDATE time_entries SET ended_at = ? WHERE id = ?")
+        .bind(ended_at)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to stop timer: {}", e)))?;
+    let actual_minutes = sqlx::query_scalar::<_, i64>(
+        "UPDATE tasks SET actual_minutes = COALESCE(actual_minutes, 0) + ?, updated_at = ? WHERE id = ?
+         RETURNING actual_minutes",
+    )
+    .bind(minutes)
+    .bind(now)
+    .bind(&task_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to update tracked time: {}", e)))?;
+    tx.commit().await.map_err(|e| CmdError::Db(format!("Failed to commit transaction: {}", e)))?;
+
+    log::info!("[TimeTracking] Timer {} stopped on task {} after {} min", id, task_id, minutes);
+    if let Some(actual_minutes) = actual_minutes
+        && let Err(e) = app_handle.emit(
+            "task-time-tracked",
+            serde_json::json!({ "taskId": task_id, "actualMinutes": actual_minutes }),
+        )
+    {
+        log::error!("[TimeTracking] Failed to emit task-time-tracked: {}", e);
+    }
+    Ok(TimeEntry { id, task_id, started_at, ended_at: Some(ended_at) })
+}
+
+/// Command totalling the time tracked per list on entries started in `since..until` (epoch
+/// millis). Running timers aren't counted yet
+#[tauri::command]
+pub async fn get_time_report(app_handle: AppHandle, since: i64, until: i64) -> Result<Vec<ListTimeReport>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let rows = sqlx::query_as::<_, (String, i64, i64, i64)>(
+        "SELECT t.list_name,
+                SUM(e.tracked) / ?3,
+                SUM(COALESCE(t.estimated_minutes, 0)),
+                COUNT(*)
+         FROM (
+             SELECT task_id, SUM(ended_at - started_at) AS tracked FROM time_entries
+             WHERE ended_at IS NOT NULL AND started_at >= ?1 AND started_at < ?2
+             GROUP BY task_id
+         ) e
+         JOIN tasks t ON t.id = e.task_id
+         WHERE t.deleted_at IS NULL
+         GROUP BY t.list_name
+         ORDER BY SUM(e.tracked) DESC",
+    )
+    .bind(since)
+    .bind(until)
+    .bind(MINUTE_MILLIS)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to build time report: {}", e)))?;
+    Ok(rows
+        .into_iter()
+        .map(|(list_name, tracked_minutes, estimated_minutes, task_count)| ListTimeReport {
+            list_name,
+            tracked_minutes,
+            estimated_minutes,
+            task_count,
+        })
+        .collect())
+}
@@ -0,0 +1,259 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::db;
+use crate::error::CmdError;
+
+/// Settings key for how many days soft-deleted tasks and lists are kept
+const RETENTION_DAYS_KEY: &str = "trash_retention_days";
+
+const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// Settings key for how many days completed tasks are kept before being purged; 0 (the
+/// default) keeps them
+const COMPLETED_RETENTION_DAYS_KEY: &str = "completed_retention_days";
+
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+/// A soft-deleted task or list
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItem {
+    /// `"task"` or `"list"`
+    pub kind: &'static str,
+    pub id: String,
+    /// Task title or list name
+    pub title: String,
+    pub list_name: Option<String>,
+    pub deleted_at: i64,
+}
+
+/// Command for the trash view: every soft-deleted task and list, most recently deleted first
+#[tauri::command]
+pub async fn list_trash(app_handle: AppHandle) -> Result<Vec<TrashItem>, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let tasks = sqlx::query_as::<_, (String, String, String, i64)>(
+        "SELECT id, title, list_name, deleted_at FROM tasks WHERE deleted_at IS NOT NULL",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read deleted tasks: {}", e)))?;
+    let lists = sqlx::query_as::<_, (String, String, i64)>(
+        "SELECT id, name, deleted_at FROM lists WHERE deleted_at IS NOT NULL",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to read deleted lists: {}", e)))?;
+
+    let mut items: Vec<TrashItem> = tasks
+        .into_iter()
+        .map(|(id, title, list_name, deleted_at)| TrashItem {
+            kind: "task",
+            id,
+            title,
+            list_name: Some(list_name),
+            deleted_at,
+        })
+        .chain(lists.into_iter().map(|(id, name, deleted_at)| TrashItem {
+            kind: "list",
+            id,
+            title: name,
+            list_name: None,
+            deleted_at,
+        }))
+        .collect();
+    items.sort_by_key(|item| std::cmp::Reverse(item.deleted_at));
+    Ok(items)
+}
+
+/// Command to bring a soft-deleted task back; its list comes back with it if that was deleted too
+#[tauri::command]
+pub async fn restore_task(app_handle: AppHandle, id: String) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut tx = pool.begin().await.map_err(|e| CmdError::Db(format!("Failed to start transaction: {}", e)))?;
+    let restored = sqlx::query("UPDATE tasks SET deleted_at = NULL, updated_at = ? WHERE id = ? AND deleted_at IS NOT NULL")
+        .bind(now)
+        .bind(&id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CmdError::Db(format!("Failed to restore task '{}': {}", id, e)))?
+        .rows_affected();
+    if restored == 0 {
+        return Err(CmdError::NotFound(format!("Task '{}' is not in the trash", id)));
+    }
+    sqlx::query(
+        "UPDATE lists SET deleted_at = NULL, updated_at = ?
+         WHERE deleted_at IS NOT NULL AND id = (SELECT list_id FROM tasks WHERE id = ?)",
+    )
+    .bind(now)
+    .bind(&id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| CmdError::Db(format!("Failed to restore the list of task '{}': {}", id, e)))?;
+    tx.commit().await.map_err(|e| CmdError::Db(format!("Failed to commit transaction: {}", e)))?;
+    log::info!("[Trash] Restored task {}", id);
+    Ok(())
+}
+
+/// Permanently removes the tasks `selection` picks (a `SELECT id FROM tasks` with `cutoff` as
+/// its one parameter) along with their subtasks, reminders, tags, time entries and
+/// dependencies; returns the attachment files to remove once the transaction commits
+async fn delete_tasks(
+    tx: &mut sqlx::SqliteConnection,
+    selection: &str,
+    cutoff: i64,
+) -> Result<Vec<String>, String> {
+    let attachment_files =
+        sqlx::query_scalar::<_, String>(&format!("SELECT file_path FROM attachments WHERE task_id IN ({})", selection))
+            .bind(cutoff)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to read attachments: {}", e))?;
+    for table in ["subtasks", "reminders", "task_tags", "time_entries", "attachments"] {
+        let column = if table == "subtasks" { "parent_id" } else { "task_id" };
+        sqlx::query(&format!("DELETE FROM {} WHERE {} IN ({})", table, column, selection))
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to delete from {}: {}", table, e))?;
+    }
+    sqlx::query(&format!(
+        "DELETE FROM task_dependencies WHERE task_id IN ({0}) OR depends_on_id IN ({0})",
+        selection
+    ))
+    .bind(cutoff)
+    .bind(cutoff)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to delete from task_dependencies: {}", e))?;
+    Ok(attachment_files)
+}
+
+/// Permanently removes trashed tasks (with their subtasks, reminders, tags, time entries,
+/// dependencies and attachment files) and lists deleted at or before `cutoff`; returns how
+/// many rows went
+async fn purge(pool: &sqlx::SqlitePool, cutoff: i64) -> Result<u64, String> {
+    let mut tx = pool.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let attachment_files = delete_tasks(&mut tx, "SELECT id FROM tasks WHERE deleted_at <= ?", cutoff)
+        .await
+        .map_err(|e| format!("Failed to empty trash: {}", e))?;
+    let mut removed = 0;
+    for sql in ["DELETE FROM tasks WHERE deleted_at <= ?", "DELETE FROM lists WHERE deleted_at <= ?"] {
+        removed += sqlx::query(sql)
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to empty trash: {}", e))?
+            .rows_affected();
+    }
+    tx.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    crate::attachments::remove_files(attachment_files);
+    Ok(removed)
+}
+
+/// Command to permanently delete what's in the trash; with `older_than` (epoch millis) only
+/// items deleted at or before then. Returns how many tasks and lists were removed
+#[tauri::command]
+pub async fn empty_trash(app_handle: AppHandle, older_than: Option<i64>) -> Result<u64, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let removed = purge(&pool, older_than.unwrap_or(i64::MAX)).await?;
+    log::info!("[Trash] Emptied {} items", removed);
+    Ok(removed)
+}
+
+async fn retention_days(pool: &sqlx::SqlitePool) -> Result<u32, String> {
+    Ok(db::get_setting(pool, RETENTION_DAYS_KEY)
+        .await?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS))
+}
+
+/// Command for the retention setting; 0 keeps trashed items until the trash is emptied
+#[tauri::command]
+pub async fn set_trash_retention_days(app_handle: AppHandle, days: u32) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    db::set_setting(&pool, RETENTION_DAYS_KEY, &days.to_string()).await.map_err(CmdError::Db)
+}
+
+/// Hard-deletes trashed items past the retention period; run from the scheduler thread
+pub fn sweep_expired(app: &AppHandle) {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        let days = retention_days(&pool).await?;
+        if days == 0 {
+            return Ok(0);
+        }
+        let cutoff = chrono::Utc::now().timestamp_millis() - i64::from(days) * DAY_MILLIS;
+        purge(&pool, cutoff).await
+    });
+    match result {
+        Ok(0) => {}
+        Ok(removed) => log::info!("[Trash] Removed {} items past the retention period", removed),
+        Err(e) => log::warn!("[Trash] Retention sweep failed: {}", e),
+    }
+}
+
+/// Permanently removes tasks completed before `cutoff` with everything hanging off them, and
+/// tells the frontend which went; returns how many
+async fn purge_completed_before(app: &AppHandle, cutoff: i64) -> Result<u64, String> {
+    const SELECTION: &str = "SELECT id FROM tasks WHERE completed = 1 AND completed_at < ?";
+    let pool = db::pool(app).await?;
+    let mut tx = pool.begin().await.map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let attachment_files = delete_tasks(&mut tx, SELECTION, cutoff)
+        .await
+        .map_err(|e| format!("Failed to purge completed tasks: {}", e))?;
+    let ids = sqlx::query_scalar::<_, String>("DELETE FROM tasks WHERE completed = 1 AND completed_at < ? RETURNING id")
+        .bind(cutoff)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to purge completed tasks: {}", e))?;
+    tx.commit().await.map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    crate::attachments::remove_files(attachment_files);
+    if !ids.is_empty() {
+        log::info!("[Trash] Purged {} completed tasks", ids.len());
+        if let Err(e) = app.emit("tasks-changed", serde_json::json!({ "ids": ids })) {
+            log::error!("[Trash] Failed to emit tasks-changed: {}", e);
+        }
+    }
+    Ok(ids.len() as u64)
+}
+
+/// Command to permanently delete tasks completed more than `older_than_days` ago, with their
+/// subtasks, reminders, tags, time entries, dependencies and attachment files; returns how many
+/// tasks went. Unlike deleting a task, which moves it to the trash where `restore_task` can
+/// bring it back, this skips the trash: a purged task is gone, so run `backup_database` first
+/// to keep a copy. Completed tasks already in the trash are purged too
+#[tauri::command]
+pub async fn purge_completed(app_handle: AppHandle, older_than_days: u32) -> Result<u64, CmdError> {
+    let cutoff = chrono::Utc::now().timestamp_millis() - i64::from(older_than_days) * DAY_MILLIS;
+    Ok(purge_completed_before(&app_handle, cutoff).await?)
+}
+
+/// Command for how many days completed tasks are kept before the scheduler purges them;
+/// 0 turns the automatic purge off
+#[tauri::command]
+pub async fn set_completed_retention_days(app_handle: AppHandle, days: u32) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    db::set_setting(&pool, COMPLETED_RETENTION_DAYS_KEY, &days.to_string()).await.map_err(CmdError::Db)
+}
+
+/// Purges completed tasks past the retention period when one is set; run from the scheduler
+/// thread
+pub fn sweep_completed(app: &AppHandle) {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        let days = db::get_setting(&pool, COMPLETED_RETENTION_DAYS_KEY)
+            .await?
+            .and_then(|value| value.parse::<u32>().ok())
+            .unwrap_or(0);
+        if days == 0 {
+            return Ok(());
+        }
+        let cutoff = chrono::Utc::now().timestamp_millis() - i64::from(days) * DAY_MILLIS;
+        purge_completed_before(app, cutoff).await.map(|_| ())
+    });
+    if let Err(e) = result {
+        log::warn!("[Trash] Completed task sweep failed: {}", e);
+    }
+}
@@ -0,0 +1,609 @@
+use chrono::{Local, NaiveDate, Utc};
+use image::{Rgba, RgbaImage};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use serde::{Deserialize, Serialize};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::error::CmdError;
+use crate::{db, paths, scheduler, window, AppState};
+
+/// Id of the single tray icon, used to look it up again via `tray_by_id`
+pub const TRAY_ID: &str = "main-tray";
+/// How often the tray tooltip and badge are refreshed in the background
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+const TRAY_ICON: &[u8] = include_bytes!("../icons/tray-icon.png");
+/// Shown while a report is being generated
+const TRAY_ICON_BUSY: &[u8] = include_bytes!("../icons/tray-icon-busy.png");
+
+const BADGE_COLOR: Rgba<u8> = Rgba([229, 57, 53, 255]);
+const BADGE_TEXT_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// 3x5 bitmap glyphs for the badge, one bit per pixel, most significant bit on the left
+const DIGIT_GLYPHS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+const PLUS_GLYPH: [u8; 5] = [0b000, 0b010, 0b111, 0b010, 0b000];
+
+/// Settings key holding the `TrayLeftClick` preference
+const LEFT_CLICK_SETTING_KEY: &str = "tray_left_click";
+
+/// What a left click on the tray icon does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrayLeftClick {
+    #[default]
+    Show,
+    // Hide the window when it's already visible
+    Toggle,
+}
+
+/// Number of tasks listed in the tray "Recent" submenu
+const RECENT_TASK_LIMIT: i64 = 5;
+/// Longest task title shown in the "Recent" submenu before it is cut with an ellipsis
+const RECENT_TITLE_MAX_CHARS: usize = 40;
+/// Menu id prefix for "Recent" entries; the task id follows the colon
+const OPEN_TASK_PREFIX: &str = "open_task:";
+
+/// Builds the tray menu; `recent` holds `(id, title)` pairs for the "Recent" submenu
+fn build_menu(app: &AppHandle, recent: &[(String, String)]) -> tauri::Result<Menu<Wry>> {
+    let show = MenuItem::with_id(app, "show", "Show Tada", true, None::<&str>)?;
+    let quick_add_item = MenuItem::with_id(app, "quick_add", "Quick Add", true, None::<&str>)?;
+
+    let recent_menu = Submenu::with_id(app, "recent", "Recent", true)?;
+    if recent.is_empty() {
+        recent_menu.append(&MenuItem::with_id(app, "recent_empty", "No recent tasks", false, None::<&str>)?)?;
+    }
+    for (id, title) in recent {
+        let label = if title.chars().count() > RECENT_TITLE_MAX_CHARS {
+            format!("{}…", title.chars().take(RECENT_TITLE_MAX_CHARS).collect::<String>())
+        } else {
+            title.clone()
+        };
+        recent_menu.append(&MenuItem::with_id(
+            app,
+            format!("{}{}", OPEN_TASK_PREFIX, id),
+            label,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+
+    let schedule_enabled = app
+        .state::<AppState>()
+        .schedule_settings
+        .lock()
+        .map(|settings| settings.enabled)
+        .unwrap_or(false);
+    let schedule_item = CheckMenuItem::with_id(
+        app,
+        "toggle_schedule",
+        "Enable Scheduled Reports",
+        true,
+        schedule_enabled,
+        None::<&str>,
+    )?;
+    let always_on_top = app
+        .get_webview_window("main")
+        .and_then(|window| window.is_always_on_top().ok())
+        .unwrap_or(false);
+    let always_on_top_item = CheckMenuItem::with_id(
+        app,
+        "toggle_always_on_top",
+        "Always on Top",
+        true,
+        always_on_top,
+        None::<&str>,
+    )?;
+    let open_logs_item = MenuItem::with_id(app, "open_logs", "Open Logs", true, None::<&str>)?;
+    let copy_log_path_item = MenuItem::with_id(app, "copy_log_path", "Copy Log Path", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &show,
+            &quick_add_item,
+            &recent_menu,
+            &PredefinedMenuItem::separator(app)?,
+            &schedule_item,
+            &always_on_top_item,
+            &open_logs_item,
+            &copy_log_path_item,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )?;
+    if let Ok(mut item) = app.state::<AppState>().tray_schedule_item.lock() {
+        *item = Some(schedule_item);
+    }
+    if let Ok(mut item) = app.state::<AppState>().tray_always_on_top_item.lock() {
+        *item = Some(always_on_top_item);
+    }
+
+    Ok(menu)
+}
+
+/// Builds the tray icon and its menu
+pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
+    let recent = tauri::async_runtime::block_on(recent_tasks(app)).unwrap_or_else(|e| {
+        log::warn!("[Tray] Failed to load recent tasks: {}", e);
+        Vec::new()
+    });
+    let menu = build_menu(app, &recent)?;
+
+    let left_click = tauri::async_runtime::block_on(load_left_click(app)).unwrap_or_else(|e| {
+        log::warn!("[Tray] Failed to load left-click preference: {}", e);
+        TrayLeftClick::default()
+    });
+    if let Ok(mut current) = app.state::<AppState>().tray_left_click.lock() {
+        *current = left_click;
+    }
+
+    TrayIconBuilder::with_id(TRAY_ID)
+        .icon(Image::from_bytes(TRAY_ICON)?)
+        .icon_as_template(cfg!(target_os = "macos"))
+        .tooltip("Tada")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            "show" => show_main_window(app),
+            "quick_add" => quick_add(app),
+            "toggle_schedule" => toggle_schedule(app),
+            "toggle_always_on_top" => toggle_always_on_top(app),
+            "open_logs" => {
+                if let Err(e) = open_logs(app) {
+                    log::error!("[Tray] Failed to open logs: {}", e);
+                }
+            }
+            "copy_log_path" => {
+                if let Err(e) = copy_log_path(app) {
+                    log::error!("[Tray] Failed to copy log path: {}", e);
+                }
+            }
+            "quit" => request_quit(app),
+            id => {
+                if let Some(task_id) = id.strip_prefix(OPEN_TASK_PREFIX) {
+                    open_task(app, task_id);
+                }
+            }
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                handle_left_click(tray.app_handle());
+            }
+        })
+        .build(app)?;
+
+    Ok(())
+}
+
+/// Shows the main window, or hides it when visible and the preference is `Toggle`
+fn handle_left_click(app: &AppHandle) {
+    let behavior = app
+        .state::<AppState>()
+        .tray_left_click
+        .lock()
+        .map(|current| *current)
+        .unwrap_or_default();
+    if behavior == TrayLeftClick::Toggle
+        && let Some(window) = app.get_webview_window("main")
+        && window.is_visible().unwrap_or(false)
+    {
+        let _ = window.hide();
+        return;
+    }
+    show_main_window(app);
+}
+
+async fn load_left_click(app: &AppHandle) -> Result<TrayLeftClick, String> {
+    let pool = db::pool(app).await?;
+    match db::get_setting(&pool, LEFT_CLICK_SETTING_KEY).await? {
+        Some(value) => serde_json::from_str(&value)
+            .map_err(|e| format!("Malformed {} setting: {}", LEFT_CLICK_SETTING_KEY, e)),
+        None => Ok(TrayLeftClick::default()),
+    }
+}
+
+/// Shows, unminimizes and focuses the main window
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Quits, unless a report is generating: then the frontend is asked to confirm first and
+/// calls `confirm_quit` to go ahead
+fn request_quit(app: &AppHandle) {
+    if !app.state::<AppState>().report_in_progress.load(Ordering::SeqCst) {
+        app.exit(0);
+        return;
+    }
+    log::info!("[Tray] Quit requested while a report is generating, asking for confirmation");
+    show_main_window(app);
+    if let Err(e) = app.emit("confirm-quit", ()) {
+        // Without a listener there is nobody to confirm, so don't trap the user
+        log::error!("[Tray] Failed to emit confirm-quit, quitting: {}", e);
+        app.exit(0);
+    }
+}
+
+/// Command for the frontend to quit after the user confirmed in response to `confirm-quit`
+#[tauri::command]
+pub fn confirm_quit(app_handle: AppHandle) {
+    app_handle.exit(0);
+}
+
+/// Brings the main window forward and asks the frontend to focus its new-task input
+pub fn quick_add(app: &AppHandle) {
+    show_main_window(app);
+    if let Err(e) = app.emit("tray-quick-add", ()) {
+        log::error!("[Tray] Failed to emit tray-quick-add: {}", e);
+    }
+}
+
+/// Brings the main window forward focused on the given task
+fn open_task(app: &AppHandle, task_id: &str) {
+    show_main_window(app);
+    if let Err(e) = app.emit("open-task", task_id) {
+        log::error!("[Tray] Failed to emit open-task: {}", e);
+    }
+}
+
+fn log_dir(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    paths::app_log_dir(app)
+}
+
+/// Opens the log directory in the platform file manager, creating it on a fresh install
+fn open_logs(app: &AppHandle) -> Result<(), String> {
+    let log_dir = log_dir(app)?;
+    std::fs::create_dir_all(&log_dir)
+        .map_err(|e| format!("Failed to create log directory {}: {}", log_dir.display(), e))?;
+    app.opener()
+        .open_path(log_dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| format!("Failed to open {}: {}", log_dir.display(), e))
+}
+
+/// Copies the log directory path to the clipboard, for setups without a GUI file manager
+fn copy_log_path(app: &AppHandle) -> Result<(), String> {
+    let log_dir = log_dir(app)?.to_string_lossy().into_owned();
+    app.clipboard()
+        .write_text(log_dir.clone())
+        .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
+    log::info!("[Tray] Copied log path to clipboard: {}", log_dir);
+
+    // The copy already succeeded, so a missing notification daemon is only worth a warning
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("Log path copied")
+        .body(&log_dir)
+        .show()
+    {
+        log::warn!("[Tray] Failed to show notification: {}", e);
+    }
+    Ok(())
+}
+
+/// Flips scheduled reports on or off from the tray check item
+fn toggle_schedule(app: &AppHandle) {
+    let enabled = app
+        .state::<AppState>()
+        .schedule_settings
+        .lock()
+        .map(|settings| !settings.enabled)
+        .unwrap_or(false);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = scheduler::set_schedule_enabled(&app, enabled).await {
+            log::error!("[Tray] Failed to toggle scheduled reports: {}", e);
+        }
+        sync_schedule_toggle(&app);
+    });
+}
+
+/// Makes the tray check item match `ScheduleSettings.enabled`
+pub fn sync_schedule_toggle(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let enabled = state.schedule_settings.lock().map(|settings| settings.enabled).unwrap_or(false);
+    if let Ok(item) = state.tray_schedule_item.lock()
+        && let Some(item) = item.as_ref()
+        && let Err(e) = item.set_checked(enabled)
+    {
+        log::warn!("[Tray] Failed to update schedule check item: {}", e);
+    }
+}
+
+/// Refreshes the tray periodically so day rollovers show up without a task edit. Stops with
+/// the scheduler: it waits on the same condvar, which `stop_background_scheduler` signals
+pub fn start_refresh_timer(app: AppHandle) {
+    std::thread::spawn(move || {
+        let state = app.state::<AppState>();
+        while !state.scheduler_stop.load(Ordering::SeqCst) {
+            if let Err(e) = tauri::async_runtime::block_on(update_tooltip(&app)) {
+                log::warn!("[Tray] Failed to refresh tooltip: {}", e);
+            }
+            if let Err(e) = tauri::async_runtime::block_on(update_badge(&app)) {
+                log::warn!("[Tray] Failed to refresh badge: {}", e);
+            }
+            // Settings changes wake the condvar too, so only the stop flag ends the wait early
+            let Ok(settings) = state.schedule_settings.lock() else {
+                log::error!("[Tray] Schedule settings lock poisoned, stopping tray refresh");
+                return;
+            };
+            let running = |_: &mut _| !state.scheduler_stop.load(Ordering::SeqCst);
+            if state.schedule_changed.wait_timeout_while(settings, REFRESH_INTERVAL, running).is_err() {
+                log::error!("[Tray] Schedule settings lock poisoned, stopping tray refresh");
+                return;
+            }
+        }
+        log::debug!("[Tray] Refresh timer stopped");
+    });
+}
+
+/// Millis of local midnight at the start of `date`
+pub(crate) fn local_midnight_millis(date: NaiveDate) -> i64 {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    midnight
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|dt| dt.timestamp_millis())
+        .unwrap_or_else(|| midnight.and_utc().timestamp_millis())
+}
+
+/// Number of incomplete tasks (outside Trash) due today in local time
+async fn count_due_today(app: &AppHandle) -> Result<i64, String> {
+    let pool = db::pool(app).await?;
+    let today = Local::now().date_naive();
+    let start = local_midnight_millis(today);
+    let end = local_midnight_millis(today.succ_opt().unwrap_or(today));
+
+    sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM tasks WHERE completed = 0 AND list_name != 'Trash' AND deleted_at IS NULL AND due_date >= ? AND due_date < ?",
+    )
+    .bind(start)
+    .bind(end)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| format!("Failed to count today's tasks: {}", e))
+}
+
+/// Number of incomplete tasks (outside Trash) whose due date has already passed
+async fn count_overdue(app: &AppHandle) -> Result<i64, String> {
+    let pool = db::pool(app).await?;
+    sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM tasks WHERE completed = 0 AND list_name != 'Trash' AND deleted_at IS NULL AND due_date < ?",
+    )
+    .bind(Utc::now().timestamp_millis())
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| format!("Failed to count overdue tasks: {}", e))
+}
+
+/// The most recently created or edited tasks (outside Trash) as `(id, title)` pairs
+async fn recent_tasks(app: &AppHandle) -> Result<Vec<(String, String)>, String> {
+    let pool = db::pool(app).await?;
+    sqlx::query_as::<_, (String, String)>(
+        "SELECT id, title FROM tasks WHERE list_name != 'Trash' AND deleted_at IS NULL ORDER BY updated_at DESC LIMIT ?",
+    )
+    .bind(RECENT_TASK_LIMIT)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to load recent tasks: {}", e))
+}
+
+fn tooltip_text(due_today: i64) -> String {
+    match due_today {
+        0 => "Tada · All clear today".to_string(),
+        1 => "Tada · 1 task due today".to_string(),
+        n => format!("Tada · {} tasks due today", n),
+    }
+}
+
+async fn update_tooltip(app: &AppHandle) -> Result<(), String> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    let due_today = count_due_today(app).await?;
+    tray.set_tooltip(Some(tooltip_text(due_today)))
+        .map_err(|e| format!("Failed to set tray tooltip: {}", e))
+}
+
+/// Composites a red count bubble onto the top-right corner of the base tray image
+fn render_badge(count: i64) -> Result<RgbaImage, String> {
+    let mut icon = image::load_from_memory(TRAY_ICON)
+        .map_err(|e| format!("Failed to decode tray icon: {}", e))?
+        .to_rgba8();
+    let (width, height) = icon.dimensions();
+
+    let radius = (width.min(height) / 4).max(4) as i64;
+    let (cx, cy) = (width as i64 - radius - 1, radius);
+    for y in 0..=(2 * radius) {
+        for x in (cx - radius)..=(cx + radius) {
+            let (dx, dy) = (x - cx, y - cy);
+            let inside = x >= 0 && x < width as i64 && y < height as i64;
+            if inside && dx * dx + dy * dy <= radius * radius {
+                icon.put_pixel(x as u32, y as u32, BADGE_COLOR);
+            }
+        }
+    }
+
+    let glyphs: Vec<[u8; 5]> = if count > 9 {
+        vec![DIGIT_GLYPHS[9], PLUS_GLYPH]
+    } else {
+        vec![DIGIT_GLYPHS[count.clamp(0, 9) as usize]]
+    };
+    // Scale the 3x5 glyphs so the text fills a little over half the bubble height
+    let scale = (radius / 4).max(1);
+    let glyph_width = 3 * scale;
+    let text_width = glyphs.len() as i64 * glyph_width + (glyphs.len() as i64 - 1) * scale;
+    let left = cx - text_width / 2;
+    let top = cy - (5 * scale) / 2;
+    for (i, glyph) in glyphs.iter().enumerate() {
+        let glyph_left = left + i as i64 * (glyph_width + scale);
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                for sy in 0..scale {
+                    for sx in 0..scale {
+                        let x = glyph_left + col * scale + sx;
+                        let y = top + row as i64 * scale + sy;
+                        if x >= 0 && y >= 0 && x < width as i64 && y < height as i64 {
+                            icon.put_pixel(x as u32, y as u32, BADGE_TEXT_COLOR);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(icon)
+}
+
+async fn update_badge(app: &AppHandle) -> Result<(), String> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    // The busy icon wins until `set_tray_status("idle")` restores the badge
+    if app.state::<AppState>().tray_busy.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+    let overdue = count_overdue(app).await?;
+
+    let icon = if overdue > 0 {
+        let badged = render_badge(overdue)?;
+        let (width, height) = badged.dimensions();
+        Image::new_owned(badged.into_raw(), width, height)
+    } else {
+        Image::from_bytes(TRAY_ICON).map_err(|e| format!("Failed to load tray icon: {}", e))?
+    };
+    tray.set_icon(Some(icon))
+        .map_err(|e| format!("Failed to set tray icon: {}", e))?;
+    // A template image is drawn as a monochrome mask on macOS, which would hide the red
+    // badge, so only the clean icon stays a template
+    if cfg!(target_os = "macos") {
+        tray.set_icon_as_template(overdue == 0)
+            .map_err(|e| format!("Failed to set tray icon template: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Flips always-on-top for the main window from the tray check item
+fn toggle_always_on_top(app: &AppHandle) {
+    let enabled = !app
+        .get_webview_window("main")
+        .and_then(|window| window.is_always_on_top().ok())
+        .unwrap_or(false);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = window::apply_always_on_top(&app, enabled).await {
+            log::error!("[Tray] Failed to toggle always on top: {}", e);
+            sync_always_on_top_toggle(&app, !enabled);
+        }
+    });
+}
+
+/// Makes the tray check item match the main window's always-on-top state
+pub fn sync_always_on_top_toggle(app: &AppHandle, enabled: bool) {
+    if let Ok(item) = app.state::<AppState>().tray_always_on_top_item.lock()
+        && let Some(item) = item.as_ref()
+        && let Err(e) = item.set_checked(enabled)
+    {
+        log::warn!("[Tray] Failed to update always-on-top check item: {}", e);
+    }
+}
+
+/// Command to change the tray left-click behavior (`"show"` | `"toggle"`) without a restart
+#[tauri::command]
+pub async fn set_tray_left_click(app_handle: AppHandle, behavior: TrayLeftClick) -> Result<(), CmdError> {
+    match app_handle.state::<AppState>().tray_left_click.lock() {
+        Ok(mut current) => *current = behavior,
+        Err(e) => return Err(CmdError::Internal(format!("Failed to update tray left-click behavior: {}", e))),
+    }
+    let value = serde_json::to_string(&behavior)
+        .map_err(|e| format!("Failed to serialize tray left-click behavior: {}", e))?;
+    let pool = db::pool(&app_handle).await?;
+    db::set_setting(&pool, LEFT_CLICK_SETTING_KEY, &value).await.map_err(CmdError::Db)
+}
+
+/// Command for the frontend to mark report generation as running (`"busy"`) or done (`"idle"`)
+#[tauri::command]
+pub async fn set_tray_status(app_handle: AppHandle, state: String) -> Result<(), CmdError> {
+    let busy = match state.as_str() {
+        "busy" => true,
+        "idle" => false,
+        other => {
+            return Err(CmdError::Validation(format!("Unknown tray status '{}', expected 'busy' or 'idle'", other)));
+        }
+    };
+    app_handle.state::<AppState>().tray_busy.store(busy, Ordering::SeqCst);
+    if !busy {
+        return Ok(update_badge(&app_handle).await?);
+    }
+
+    let Some(tray) = app_handle.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    match Image::from_bytes(TRAY_ICON_BUSY) {
+        Ok(icon) => {
+            tray.set_icon(Some(icon))
+                .map_err(|e| format!("Failed to set tray icon: {}", e))?;
+            if cfg!(target_os = "macos") {
+                tray.set_icon_as_template(true)
+                    .map_err(|e| format!("Failed to set tray icon template: {}", e))?;
+            }
+        }
+        // Not worth failing the report over; keep whatever icon is showing
+        Err(e) => log::warn!("[Tray] Busy icon unavailable, keeping current icon: {}", e),
+    }
+    Ok(())
+}
+
+/// Command to rebuild the tray menu after tasks change; menus can't be patched per item,
+/// so the whole menu is replaced
+#[tauri::command]
+pub async fn refresh_tray_menu(app_handle: AppHandle) -> Result<(), CmdError> {
+    let Some(tray) = app_handle.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    let recent = recent_tasks(&app_handle).await?;
+    let menu = build_menu(&app_handle, &recent).map_err(|e| format!("Failed to build tray menu: {}", e))?;
+    tray.set_menu(Some(menu))
+        .map_err(|e| CmdError::Internal(format!("Failed to set tray menu: {}", e)))
+}
+
+/// Command for the frontend to refresh the overdue badge after a task edit
+#[tauri::command]
+pub async fn refresh_tray_badge(app_handle: AppHandle) -> Result<(), CmdError> {
+    Ok(update_badge(&app_handle).await?)
+}
+
+/// Command for the frontend to push a tooltip update right after a task edit
+#[tauri::command]
+pub async fn refresh_tray_tooltip(app_handle: AppHandle) -> Result<(), CmdError> {
+    Ok(update_tooltip(&app_handle).await?)
+}
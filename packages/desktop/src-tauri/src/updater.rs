@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_updater::UpdaterExt;
+
+use crate::error::CmdError;
+use crate::{db, AppState};
+
+/// Settings key for the update channel, `"stable"` or `"beta"` as JSON
+const UPDATE_CHANNEL_KEY: &str = "update_channel";
+
+/// Update manifests per channel: stable follows the latest release, beta a rolling `beta` tag
+const STABLE_ENDPOINT: &str = "https://github.com/LoadShine/tada/releases/latest/download/latest.json";
+const BETA_ENDPOINT: &str = "https://github.com/LoadShine/tada/releases/download/beta/latest.json";
+
+/// A check gives up after this, so a dead connection never holds anything up for long
+const CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Delay before the launch check, so it never competes with startup
+const STARTUP_CHECK_DELAY: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    /// Release notes from the manifest
+    pub notes: Option<String>,
+    pub date: Option<String>,
+    /// Where the update package is downloaded from
+    pub url: String,
+}
+
+async fn channel(app: &AppHandle) -> Result<UpdateChannel, String> {
+    let pool = db::pool(app).await?;
+    Ok(db::get_setting(&pool, UPDATE_CHANNEL_KEY)
+        .await?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+/// The key update packages are signed with: `TADA_UPDATER_PUBKEY` at build time, else the
+/// one in `tauri.conf.json`. `None` when neither is set, as in local builds
+fn pubkey(app: &AppHandle) -> Option<String> {
+    let not_blank = |key: &String| !key.trim().is_empty();
+    option_env!("TADA_UPDATER_PUBKEY")
+        .map(str::to_string)
+        .filter(not_blank)
+        .or_else(|| app.config().plugins.0.get("updater")?.get("pubkey")?.as_str().map(str::to_string))
+        .filter(not_blank)
+}
+
+/// An updater pointed at the saved channel. Fails up front when the plugin didn't load or the
+/// build has no signing key, since every download would then fail its signature check
+async fn updater(app: &AppHandle) -> Result<tauri_plugin_updater::Updater, String> {
+    if !app.state::<AppState>().updater_available.load(Ordering::SeqCst) {
+        return Err("Updates are unavailable: the updater failed to start".to_string());
+    }
+    let pubkey = pubkey(app).ok_or("Updates aren't configured in this build: it has no signing key")?;
+    let endpoint = match channel(app).await? {
+        UpdateChannel::Stable => STABLE_ENDPOINT,
+        UpdateChannel::Beta => BETA_ENDPOINT,
+    };
+    let url = endpoint.parse().map_err(|e| format!("Invalid update endpoint {}: {}", endpoint, e))?;
+    app.updater_builder()
+        .endpoints(vec![url])
+        .map_err(|e| format!("Invalid update endpoint {}: {}", endpoint, e))?
+        .timeout(CHECK_TIMEOUT)
+        .pubkey(pubkey)
+        .build()
+        .map_err(|e| format!("Failed to set up the updater: {}", e))
+}
+
+/// Network failures read as "offline" rather than as an updater error
+fn friendly_error(error: tauri_plugin_updater::Error) -> String {
+    match error {
+        tauri_plugin_updater::Error::Reqwest(e) if e.is_connect() || e.is_timeout() => {
+            "Could not reach the update server; check your connection".to_string()
+        }
+        e => format!("Failed to check for updates: {}", e),
+    }
+}
+
+fn update_info(update: &tauri_plugin_updater::Update) -> UpdateInfo {
+    UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        notes: update.body.clone(),
+        date: update.date.map(|date| date.to_string()),
+        url: update.download_url.to_string(),
+    }
+}
+
+/// Command to look for a newer version on the saved channel; `None` when up to date
+#[tauri::command]
+pub async fn check_for_update(app_handle: AppHandle) -> Result<Option<UpdateInfo>, CmdError> {
+    let update = updater(&app_handle).await?.check().await.map_err(friendly_error)?;
+    Ok(update.as_ref().map(update_info))
+}
+
+/// Command to download and install the available update, then restart into it. Progress goes
+/// out as `update-download-progress` with the bytes so far and the total when known
+#[tauri::command]
+pub async fn install_update(app_handle: AppHandle) -> Result<(), CmdError> {
+    let Some(update) = updater(&app_handle).await?.check().await.map_err(friendly_error)? else {
+        return Err(CmdError::NotFound("Tada is already up to date".to_string()));
+    };
+    log::info!("[Updater] Installing {} (from {})", update.version, update.current_version);
+    let mut downloaded: u64 = 0;
+    let progress_app = app_handle.clone();
+    update
+        .download_and_install(
+            move |chunk, total| {
+                downloaded += chunk as u64;
+                let payload = serde_json::json!({ "downloaded": downloaded, "total": total });
+                if let Err(e) = progress_app.emit("update-download-progress", payload) {
+                    log::error!("[Updater] Failed to emit update-download-progress: {}", e);
+                }
+            },
+            || log::info!("[Updater] Download finished"),
+        )
+        .await
+        .map_err(|e| CmdError::Io(format!("Failed to install the update: {}", e)))?;
+    app_handle.restart()
+}
+
+#[tauri::command]
+pub async fn get_update_channel(app_handle: AppHandle) -> Result<UpdateChannel, CmdError> {
+    Ok(channel(&app_handle).await?)
+}
+
+#[tauri::command]
+pub async fn set_update_channel(app_handle: AppHandle, channel: UpdateChannel) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let value = serde_json::to_string(&channel).map_err(|e| format!("Failed to serialize channel: {}", e))?;
+    db::set_setting(&pool, UPDATE_CHANNEL_KEY, &value).await.map_err(CmdError::Db)
+}
+
+/// Registers the updater plugin, then checks for an update in the background. A plugin that
+/// fails to load turns updates off for the session instead of keeping Tada from starting
+pub fn init(app: &AppHandle) {
+    if let Err(e) = app.plugin(tauri_plugin_updater::Builder::new().build()) {
+        log::warn!("[Updater] Updates are off, the plugin failed to load: {}", e);
+        return;
+    }
+    app.state::<AppState>().updater_available.store(true, Ordering::SeqCst);
+    if pubkey(app).is_none() {
+        log::info!("[Updater] No update signing key in this build, skipping update checks");
+        return;
+    }
+    check_in_background(app);
+}
+
+/// Checks for an update off the startup path and emits `update-available` when there is one.
+/// Being offline only gets a log line
+fn check_in_background(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_CHECK_DELAY).await;
+        let result = async { updater(&app).await?.check().await.map_err(friendly_error) }.await;
+        match result {
+            Ok(Some(update)) => {
+                log::info!("[Updater] Update available: {}", update.version);
+                if let Err(e) = app.emit("update-available", update_info(&update)) {
+                    log::error!("[Updater] Failed to emit update-available: {}", e);
+                }
+            }
+            Ok(None) => log::debug!("[Updater] Up to date"),
+            Err(e) => log::info!("[Updater] Skipped the update check: {}", e),
+        }
+    });
+}
@@ -0,0 +1,253 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use tauri::AppHandle;
+
+use crate::db;
+use crate::error::CmdError;
+
+/// The single profile row
+const PROFILE_ID: &str = "default";
+
+/// Settings row earlier builds kept the whole profile in, as the frontend's `UserProfile` JSON
+const LEGACY_PROFILE_KEY: &str = "userProfile";
+
+const PERSONAS: &[&str] = &["dev", "product", "marketing", "sales", "ops", "admin", "research", "freelance"];
+const TASK_VIEWS: &[&str] = &["process", "outcome"];
+const UNCERTAINTY_TOLERANCES: &[&str] = &["low", "high"];
+const INCOMPLETION_STYLES: &[&str] = &["narrative", "explicit"];
+
+/// Confidence given to each axis until user behavior adjusts it
+const DEFAULT_CONFIDENCE: f64 = 0.7;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkRealityConfidence {
+    pub task_view: f64,
+    pub uncertainty_tolerance: f64,
+    pub incompletion_style: f64,
+}
+
+impl Default for WorkRealityConfidence {
+    fn default() -> Self {
+        Self {
+            task_view: DEFAULT_CONFIDENCE,
+            uncertainty_tolerance: DEFAULT_CONFIDENCE,
+            incompletion_style: DEFAULT_CONFIDENCE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkRealityModel {
+    pub task_view: Option<String>,
+    pub uncertainty_tolerance: Option<String>,
+    pub incompletion_style: Option<String>,
+    #[serde(default)]
+    pub confidence: WorkRealityConfidence,
+}
+
+/// Same shape as the frontend's `UserProfile`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfile {
+    /// `None` when onboarding skipped the question
+    pub persona: Option<Vec<String>>,
+    #[serde(default)]
+    pub work_reality_model: WorkRealityModel,
+    pub user_note: Option<String>,
+    #[serde(default)]
+    pub onboarding_completed: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Fields to change; the ones left out are kept, and `null` clears the nullable ones
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UserProfilePatch {
+    #[serde(deserialize_with = "present")]
+    pub persona: Option<Option<Vec<String>>>,
+    #[serde(deserialize_with = "present")]
+    pub task_view: Option<Option<String>>,
+    #[serde(deserialize_with = "present")]
+    pub uncertainty_tolerance: Option<Option<String>>,
+    #[serde(deserialize_with = "present")]
+    pub incompletion_style: Option<Option<String>>,
+    pub confidence: Option<WorkRealityConfidence>,
+    #[serde(deserialize_with = "present")]
+    pub user_note: Option<Option<String>>,
+    pub onboarding_completed: Option<bool>,
+}
+
+/// Tells a field set to `null` (`Some(None)`) from one left out (`None`, via `default`)
+fn present<'de, D: Deserializer<'de>, T: Deserialize<'de>>(deserializer: D) -> Result<Option<Option<T>>, D::Error> {
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// persona, task_view, uncertainty_tolerance, incompletion_style, wrm_confidence, user_note,
+/// onboarding_completed, created_at, updated_at
+type ProfileRow =
+    (Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, bool, i64, i64);
+
+fn check(field: &str, value: Option<&str>, allowed: &[&str]) -> Result<(), String> {
+    match value {
+        Some(value) if !allowed.contains(&value) => {
+            Err(format!("Invalid {} '{}'; use {}", field, value, allowed.join(", ")))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate(profile: &UserProfile) -> Result<(), String> {
+    for persona in profile.persona.iter().flatten() {
+        check("persona", Some(persona), PERSONAS)?;
+    }
+    let model = &profile.work_reality_model;
+    check("task view", model.task_view.as_deref(), TASK_VIEWS)?;
+    check("uncertainty tolerance", model.uncertainty_tolerance.as_deref(), UNCERTAINTY_TOLERANCES)?;
+    check("incompletion style", model.incompletion_style.as_deref(), INCOMPLETION_STYLES)?;
+    let confidence = model.confidence;
+    for value in [confidence.task_view, confidence.uncertainty_tolerance, confidence.incompletion_style] {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(format!("Invalid confidence {}; use a value from 0 to 1", value));
+        }
+    }
+    Ok(())
+}
+
+async fn save(pool: &sqlx::SqlitePool, profile: &UserProfile) -> Result<(), String> {
+    let persona = profile
+        .persona
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| format!("Failed to serialize persona: {}", e))?;
+    let model = &profile.work_reality_model;
+    let confidence =
+        serde_json::to_string(&model.confidence).map_err(|e| format!("Failed to serialize confidence: {}", e))?;
+    sqlx::query(
+        "INSERT OR REPLACE INTO user_profile
+             (id, persona, task_view, uncertainty_tolerance, incompletion_style, wrm_confidence, user_note,
+              onboarding_completed, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(PROFILE_ID)
+    .bind(persona)
+    .bind(&model.task_view)
+    .bind(&model.uncertainty_tolerance)
+    .bind(&model.incompletion_style)
+    .bind(confidence)
+    .bind(&profile.user_note)
+    .bind(profile.onboarding_completed)
+    .bind(profile.created_at)
+    .bind(profile.updated_at)
+    .execute(pool)
+    .await
+    .map(|_| ())
+    .map_err(|e| format!("Failed to save user profile: {}", e))
+}
+
+/// The stored profile. A row never written since the migration created it takes over the
+/// profile earlier builds kept in settings, if there is one
+async fn load(pool: &sqlx::SqlitePool) -> Result<UserProfile, String> {
+    let row = sqlx::query_as::<_, ProfileRow>(
+        "SELECT persona, task_view, uncertainty_tolerance, incompletion_style, wrm_confidence, user_note,
+                onboarding_completed, created_at, updated_at
+         FROM user_profile WHERE id = ?",
+    )
+    .bind(PROFILE_ID)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to read user profile: {}", e))?;
+
+    let untouched = row.as_ref().is_none_or(|row| !row.6 && row.7 == row.8);
+    if untouched
+        && let Some(mut legacy) = db::get_setting(pool, LEGACY_PROFILE_KEY)
+            .await?
+            .and_then(|value| serde_json::from_str::<UserProfile>(&value).ok())
+            .filter(|legacy| validate(legacy).is_ok())
+    {
+        log::info!("[Profile] Moved the user profile out of settings");
+        legacy.updated_at = legacy.updated_at.max(legacy.created_at + 1);
+        save(pool, &legacy).await?;
+        return Ok(legacy);
+    }
+
+    let Some(row) = row else {
+        let now = chrono::Utc::now().timestamp_millis();
+        let profile = UserProfile {
+            persona: None,
+            work_reality_model: WorkRealityModel::default(),
+            user_note: None,
+            onboarding_completed: false,
+            created_at: now,
+            updated_at: now,
+        };
+        save(pool, &profile).await?;
+        return Ok(profile);
+    };
+    let (
+        persona,
+        task_view,
+        uncertainty_tolerance,
+        incompletion_style,
+        confidence,
+        user_note,
+        onboarding_completed,
+        created_at,
+        updated_at,
+    ) = row;
+    Ok(UserProfile {
+        persona: persona.and_then(|persona| serde_json::from_str(&persona).ok()),
+        work_reality_model: WorkRealityModel {
+            task_view,
+            uncertainty_tolerance,
+            incompletion_style,
+            confidence: confidence.and_then(|confidence| serde_json::from_str(&confidence).ok()).unwrap_or_default(),
+        },
+        user_note,
+        onboarding_completed,
+        created_at,
+        updated_at,
+    })
+}
+
+#[tauri::command]
+pub async fn get_user_profile(app_handle: AppHandle) -> Result<UserProfile, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    Ok(load(&pool).await?)
+}
+
+/// Command to change onboarding answers; rejects values outside the ones the UI offers
+#[tauri::command]
+pub async fn update_user_profile(app_handle: AppHandle, patch: UserProfilePatch) -> Result<UserProfile, CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    let mut profile = load(&pool).await?;
+    let model = &mut profile.work_reality_model;
+    if let Some(persona) = patch.persona {
+        profile.persona = persona;
+    }
+    if let Some(task_view) = patch.task_view {
+        model.task_view = task_view;
+    }
+    if let Some(uncertainty_tolerance) = patch.uncertainty_tolerance {
+        model.uncertainty_tolerance = uncertainty_tolerance;
+    }
+    if let Some(incompletion_style) = patch.incompletion_style {
+        model.incompletion_style = incompletion_style;
+    }
+    if let Some(confidence) = patch.confidence {
+        model.confidence = confidence;
+    }
+    if let Some(user_note) = patch.user_note {
+        profile.user_note = user_note.filter(|note| !note.trim().is_empty());
+    }
+    if let Some(onboarding_completed) = patch.onboarding_completed {
+        profile.onboarding_completed = onboarding_completed;
+    }
+    validate(&profile).map_err(CmdError::Validation)?;
+    // Kept past `created_at`, which is how `load` tells a written row from the migration's
+    profile.updated_at = chrono::Utc::now().timestamp_millis().max(profile.created_at + 1);
+    save(&pool, &profile).await?;
+    Ok(profile)
+}
@@ -0,0 +1,96 @@
+use serde::Serialize;
+use std::path::Path;
+use tauri::AppHandle;
+
+use crate::db;
+use crate::error::CmdError;
+
+/// Settings key for when the database was last vacuumed automatically (epoch millis)
+const LAST_AUTO_VACUUM_KEY: &str = "auto_vacuum_last";
+
+/// Automatic vacuums run at most this often
+const AUTO_VACUUM_INTERVAL_MILLIS: i64 = 7 * 24 * 60 * 60 * 1000;
+
+/// Share of free pages in the file above which an automatic vacuum is worth rewriting it
+const AUTO_VACUUM_FREE_RATIO: f64 = 0.25;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VacuumResult {
+    /// Size of `tada.db` and its WAL together
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+fn disk_size(db_path: &Path) -> u64 {
+    ["db", "db-wal"]
+        .iter()
+        .filter_map(|extension| std::fs::metadata(db_path.with_extension(extension)).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+async fn vacuum(app: &AppHandle) -> Result<VacuumResult, String> {
+    let pool = db::pool(app).await?;
+    let db_path = db::db_path(app)?;
+    let bytes_before = disk_size(&db_path);
+    sqlx::query("VACUUM")
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to vacuum the database: {}", e))?;
+    // In WAL mode the rewritten pages land in the WAL, so the space only comes back once it is
+    // checkpointed and truncated
+    let (busy, _, _) = sqlx::query_as::<_, (i64, i64, i64)>("PRAGMA wal_checkpoint(TRUNCATE)")
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| format!("Failed to checkpoint the WAL: {}", e))?;
+    if busy != 0 {
+        log::info!("[Vacuum] A reader kept the WAL from being truncated; it shrinks at a later checkpoint");
+    }
+    let result = VacuumResult { bytes_before, bytes_after: disk_size(&db_path) };
+    log::info!("[Vacuum] Database vacuumed from {} to {} bytes", result.bytes_before, result.bytes_after);
+    Ok(result)
+}
+
+/// Command to rewrite the database without its free pages, after large deletes or imports.
+/// Returns the size on disk before and after; `bytesAfter` can stay high while a reader holds
+/// the WAL open. Vacuuming needs free disk space about the size of the database, and holds the
+/// write lock the whole time, so writes made meanwhile wait. It fails with "database is locked"
+/// when another write is already underway, and can simply be run again
+#[tauri::command]
+pub async fn vacuum_database(app_handle: AppHandle) -> Result<VacuumResult, CmdError> {
+    Ok(vacuum(&app_handle).await?)
+}
+
+/// Vacuums at most weekly, and only once enough of the file is free pages that rewriting it
+/// pays off. SQLite's own `auto_vacuum` isn't used: it needs a full `VACUUM` to switch on for
+/// an existing database, and moves pages around on every commit; run from the scheduler thread
+pub fn run_due_vacuum(app: &AppHandle) {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let last = db::get_setting(&pool, LAST_AUTO_VACUUM_KEY)
+            .await?
+            .and_then(|value| value.parse::<i64>().ok())
+            .unwrap_or(0);
+        if now - last < AUTO_VACUUM_INTERVAL_MILLIS {
+            return Ok(());
+        }
+        let free = sqlx::query_scalar::<_, i64>("PRAGMA freelist_count")
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| format!("Failed to read the free page count: {}", e))?;
+        let pages = sqlx::query_scalar::<_, i64>("PRAGMA page_count")
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| format!("Failed to read the page count: {}", e))?;
+        if pages == 0 || (free as f64) < pages as f64 * AUTO_VACUUM_FREE_RATIO {
+            return Ok(());
+        }
+        vacuum(app).await?;
+        db::set_setting(&pool, LAST_AUTO_VACUUM_KEY, &now.to_string()).await
+    });
+    if let Err(e) = result {
+        log::warn!("[Vacuum] Automatic vacuum failed: {}", e);
+    }
+}
@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize, UserAttentionType, WebviewUrl,
+    WebviewWindow, WebviewWindowBuilder,
+};
+
+use crate::db;
+use crate::error::CmdError;
+
+/// Settings key holding the persisted `WindowState` JSON of the main window
+const WINDOW_STATE_KEY: &str = "window_state";
+/// Settings key holding the persisted `WindowState` JSON of the focus widget
+const WIDGET_STATE_KEY: &str = "widget_window_state";
+/// Label of the small always-on-top "today" window
+pub const WIDGET_LABEL: &str = "widget";
+/// Settings key for keeping the main window hidden (tray only) at launch
+const START_MINIMIZED_KEY: &str = "start_minimized";
+/// Settings key for bringing the hidden main window up when a scheduled report fires
+const SHOW_ON_TRIGGER_KEY: &str = "show_on_trigger";
+/// Settings key for keeping the main window above other apps
+const ALWAYS_ON_TOP_KEY: &str = "always_on_top";
+/// Smallest part of the window (in physical px) that must stay on a monitor to count as visible
+const MIN_VISIBLE: i32 = 100;
+
+/// Geometry of the main window in physical pixels: outer position, inner size
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+/// Monitor work area in physical pixels: x, y, width, height
+type MonitorRect = (i32, i32, u32, u32);
+
+/// Fits a saved geometry onto the current monitors: keeps it on the monitor it overlaps
+/// most, shrinking and shifting it inside that work area; falls back to the first monitor
+/// when it overlaps none (e.g. an unplugged external display)
+fn clamp_to_monitors(state: WindowState, monitors: &[MonitorRect]) -> Option<WindowState> {
+    let overlap = |&(mx, my, mw, mh): &MonitorRect| {
+        let width = (state.x + state.width as i32).min(mx + mw as i32) - state.x.max(mx);
+        let height = (state.y + state.height as i32).min(my + mh as i32) - state.y.max(my);
+        if width >= MIN_VISIBLE.min(state.width as i32) && height >= MIN_VISIBLE.min(state.height as i32) {
+            width as i64 * height as i64
+        } else {
+            0
+        }
+    };
+    let monitor = monitors
+        .iter()
+        .filter(|monitor| overlap(monitor) > 0)
+        .max_by_key(|monitor| overlap(monitor))
+        .or_else(|| monitors.first())?;
+
+    let (mx, my, mw, mh) = *monitor;
+    let width = state.width.min(mw);
+    let height = state.height.min(mh);
+    let x = state.x.clamp(mx, mx + (mw - width) as i32);
+    let y = state.y.clamp(my, my + (mh - height) as i32);
+    Some(WindowState { x, y, width, height, maximized: state.maximized })
+}
+
+fn state_key(window: &WebviewWindow) -> &'static str {
+    if window.label() == WIDGET_LABEL {
+        WIDGET_STATE_KEY
+    } else {
+        WINDOW_STATE_KEY
+    }
+}
+
+/// Persists a window's geometry; minimized windows are skipped since their position
+/// is meaningless (Windows reports -32000)
+pub fn save_window_state(window: &WebviewWindow) {
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+    let state = WindowState {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: window.is_maximized().unwrap_or(false),
+    };
+
+    let app = window.app_handle();
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        let value = serde_json::to_string(&state).map_err(|e| e.to_string())?;
+        db::set_setting(&pool, state_key(window), &value).await
+    });
+    if let Err(e) = result {
+        log::warn!("[Window] Failed to save window state: {}", e);
+    }
+}
+
+/// Applies a window's saved geometry, clamped to the monitors present now
+pub fn restore_window_state(window: &WebviewWindow) {
+    let app = window.app_handle();
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        db::get_setting(&pool, state_key(window)).await
+    });
+    let state = match result {
+        Ok(Some(value)) => match serde_json::from_str::<WindowState>(&value) {
+            Ok(state) => state,
+            Err(e) => {
+                log::warn!("[Window] Malformed saved window state, using defaults: {}", e);
+                return;
+            }
+        },
+        Ok(None) => return,
+        Err(e) => {
+            log::warn!("[Window] Failed to load window state: {}", e);
+            return;
+        }
+    };
+
+    let monitors: Vec<MonitorRect> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            let area = monitor.work_area();
+            (area.position.x, area.position.y, area.size.width, area.size.height)
+        })
+        .collect();
+    let Some(state) = clamp_to_monitors(state, &monitors) else {
+        return;
+    };
+
+    let _ = window.set_size(PhysicalSize::new(state.width, state.height));
+    let _ = window.set_position(PhysicalPosition::new(state.x, state.y));
+    if state.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Opens (or brings forward) the focus widget showing today's top tasks; it lives
+/// independently of the main window and closes with the app
+#[tauri::command]
+pub async fn open_focus_widget(app_handle: AppHandle) -> Result<(), CmdError> {
+    if let Some(widget) = app_handle.get_webview_window(WIDGET_LABEL) {
+        let _ = widget.show();
+        let _ = widget.set_focus();
+        return Ok(());
+    }
+
+    // The frontend picks the widget view by window label
+    let widget = WebviewWindowBuilder::new(&app_handle, WIDGET_LABEL, WebviewUrl::App("index.html".into()))
+        .title("Tada Focus")
+        .inner_size(300.0, 220.0)
+        .min_inner_size(220.0, 140.0)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .build()
+        .map_err(|e| format!("Failed to open focus widget: {}", e))?;
+    restore_window_state(&widget);
+    widget
+        .show()
+        .map_err(|e| CmdError::Internal(format!("Failed to show focus widget: {}", e)))
+}
+
+/// Re-applies the saved always-on-top choice to the main window at launch
+pub fn restore_always_on_top(app: &AppHandle) {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        db::get_setting(&pool, ALWAYS_ON_TOP_KEY).await
+    });
+    let enabled = match result {
+        Ok(Some(value)) => serde_json::from_str(&value).unwrap_or(false),
+        Ok(None) => false,
+        Err(e) => {
+            log::warn!("[Window] Failed to load always_on_top: {}", e);
+            false
+        }
+    };
+    if enabled
+        && let Some(window) = app.get_webview_window("main")
+        && let Err(e) = window.set_always_on_top(true)
+    {
+        log::warn!("[Window] Failed to restore always on top: {}", e);
+    }
+}
+
+/// Sets always-on-top on the main window, persists it and mirrors it in the tray
+pub async fn apply_always_on_top(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    window
+        .set_always_on_top(enabled)
+        .map_err(|e| format!("Failed to set always on top: {}", e))?;
+    crate::tray::sync_always_on_top_toggle(app, enabled);
+
+    let pool = db::pool(app).await?;
+    db::set_setting(&pool, ALWAYS_ON_TOP_KEY, &enabled.to_string()).await
+}
+
+#[tauri::command]
+pub async fn set_always_on_top(app_handle: AppHandle, enabled: bool) -> Result<(), CmdError> {
+    Ok(apply_always_on_top(&app_handle, enabled).await?)
+}
+
+/// Whether the main window should stay hidden in the tray at launch; defaults to `false`
+pub fn load_start_minimized(app: &AppHandle) -> bool {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        db::get_setting(&pool, START_MINIMIZED_KEY).await
+    });
+    match result {
+        Ok(Some(value)) => serde_json::from_str(&value).unwrap_or(false),
+        Ok(None) => false,
+        Err(e) => {
+            log::warn!("[Window] Failed to load start_minimized: {}", e);
+            false
+        }
+    }
+}
+
+/// Command to keep the main window hidden at launch; the tray and scheduler start either way
+#[tauri::command]
+pub async fn set_start_minimized(app_handle: AppHandle, enabled: bool) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    db::set_setting(&pool, START_MINIMIZED_KEY, &enabled.to_string()).await.map_err(CmdError::Db)
+}
+
+/// Whether a scheduled trigger should show the main window rather than only flash it
+fn load_show_on_trigger(app: &AppHandle) -> bool {
+    let result = tauri::async_runtime::block_on(async {
+        let pool = db::pool(app).await?;
+        db::get_setting(&pool, SHOW_ON_TRIGGER_KEY).await
+    });
+    match result {
+        Ok(Some(value)) => serde_json::from_str(&value).unwrap_or(false),
+        Ok(None) => false,
+        Err(e) => {
+            log::warn!("[Window] Failed to load show_on_trigger: {}", e);
+            false
+        }
+    }
+}
+
+/// Command to show the main window when a scheduled report fires while it's hidden
+#[tauri::command]
+pub async fn set_show_on_trigger(app_handle: AppHandle, enabled: bool) -> Result<(), CmdError> {
+    let pool = db::pool(&app_handle).await?;
+    db::set_setting(&pool, SHOW_ON_TRIGGER_KEY, &enabled.to_string()).await.map_err(CmdError::Db)
+}
+
+/// Draws attention to a hidden main window after a scheduled trigger: requests user
+/// attention (dock bounce / taskbar flash), and shows it when `show_on_trigger` is set
+pub fn attract_attention(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if window.is_visible().unwrap_or(true) {
+        return;
+    }
+    if load_show_on_trigger(app) {
+        crate::tray::show_main_window(app);
+    }
+    if let Err(e) = window.request_user_attention(Some(UserAttentionType::Informational)) {
+        log::warn!("[Window] Failed to request user attention: {}", e);
+    }
+}
+
+/// Arguments of a relaunch forwarded to the running instance
+#[derive(Debug, Clone, Serialize)]
+pub struct SecondInstancePayload {
+    pub args: Vec<String>,
+    pub cwd: String,
+}
+
+/// Called by the single-instance plugin when Tada is launched again while already running
+pub fn on_second_instance(app: &AppHandle, args: Vec<String>, cwd: String) {
+    log::info!("[Window] Second launch forwarded to the running instance: {:?}", args);
+    // Scripted capture (`tada --add ...`) shouldn't pull the window up
+    if !crate::cli::handle_forwarded_args(app, &args) {
+        crate::tray::show_main_window(app);
+    }
+    if let Err(e) = app.emit("second-instance", SecondInstancePayload { args, cwd }) {
+        log::error!("[Window] Failed to emit second-instance: {}", e);
+    }
+}
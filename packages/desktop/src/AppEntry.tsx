@@ -1,6 +1,7 @@
 import React, { useEffect, useState } from 'react';
 import ReactDOM from 'react-dom/client';
 import { HashRouter } from 'react-router-dom';
+import { getCurrentWindow } from '@tauri-apps/api/window';
 import { Provider as JotaiProvider } from 'jotai';
 import { App } from '@tada/core';
 import * as Tooltip from '@radix-ui/react-tooltip';
@@ -8,12 +9,13 @@ import storageManager from '@tada/core/services/storageManager';
 import { error as logError } from '@tauri-apps/plugin-log';
 import { SqliteStorageService } from './services/sqliteStorageService';
 import { GlobalErrorBoundary } from './components/GlobalErrorBoundary';
+import { FocusWidget } from './components/FocusWidget';
 
 import '@tada/core/locales';
 import '@tada/core/styles/index.css';
 
 const AppEntry = () => {
-    const [isReady, setIsReady] = useState(false);
+    const [storageService, setStorageService] = useState<SqliteStorageService | null>(null);
     const [error, setError] = useState<string | null>(null);
 
     useEffect(() => {
@@ -26,7 +28,7 @@ const AppEntry = () => {
 
                 await storageService.preloadData();
 
-                setIsReady(true);
+                setStorageService(storageService);
             } catch (err: any) {
                 const errorMessage = err.message || String(err);
                 console.error('Desktop initialization failed:', err);
@@ -49,7 +51,7 @@ const AppEntry = () => {
         );
     }
 
-    if (!isReady) {
+    if (!storageService) {
         return (
             <div className="h-screen w-screen flex items-center justify-center bg-white dark:bg-[#1D2530]">
                 <div className="animate-pulse flex flex-col items-center">
@@ -60,6 +62,17 @@ const AppEntry = () => {
         );
     }
 
+    // The focus widget window only renders the compact "today" view, none of the app shell
+    if (getCurrentWindow().label === 'widget') {
+        return (
+            <GlobalErrorBoundary>
+                <JotaiProvider>
+                    <FocusWidget storageService={storageService} />
+                </JotaiProvider>
+            </GlobalErrorBoundary>
+        );
+    }
+
     return (
         <GlobalErrorBoundary>
             <JotaiProvider>
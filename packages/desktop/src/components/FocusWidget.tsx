@@ -0,0 +1,68 @@
+import React, { useEffect, useMemo } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { getCurrentWindow } from '@tauri-apps/api/window';
+import { useAtom } from 'jotai';
+import { RESET } from 'jotai/utils';
+import { tasksAtom } from '@tada/core/store/jotai';
+import { Task } from '@tada/core/types';
+import { SqliteStorageService } from '../services/sqliteStorageService';
+
+const MAX_TASKS = 3;
+
+const byPriorityThenOrder = (a: Task, b: Task) =>
+    (a.priority ?? Number.MAX_SAFE_INTEGER) - (b.priority ?? Number.MAX_SAFE_INTEGER) || (a.order ?? 0) - (b.order ?? 0);
+
+/**
+ * Compact always-on-top view rendered in the `widget` window, showing the top
+ * incomplete tasks due today. It is read-only and reloads whenever another window
+ * broadcasts `tasks-changed` after writing to the database.
+ */
+export const FocusWidget: React.FC<{ storageService: SqliteStorageService }> = ({ storageService }) => {
+    const [tasks, setTasks] = useAtom(tasksAtom);
+
+    useEffect(() => {
+        let unlisten: (() => void) | undefined;
+        listen('tasks-changed', async () => {
+            await storageService.fetchTasksAsync();
+            setTasks(RESET);
+        }).then(cleanup => {
+            unlisten = cleanup;
+        }).catch(error => {
+            console.error('[FocusWidget] Failed to listen for task changes:', error);
+        });
+
+        return () => {
+            unlisten?.();
+        };
+    }, [storageService, setTasks]);
+
+    const topTasks = useMemo(() => (tasks ?? [])
+        .filter(task => !task.completed && task.listName !== 'Trash' && task.groupCategory === 'today')
+        .sort(byPriorityThenOrder)
+        .slice(0, MAX_TASKS), [tasks]);
+
+    return (
+        <div className="h-screen w-screen flex flex-col bg-white dark:bg-[#1D2530] text-gray-800 dark:text-gray-100 select-none">
+            <div className="flex items-center justify-between px-3 py-2" data-tauri-drag-region="true">
+                <span className="text-sm font-semibold" data-tauri-drag-region="true">Today</span>
+                <button
+                    className="text-gray-400 hover:text-gray-600 dark:hover:text-gray-200 text-sm leading-none"
+                    onClick={() => getCurrentWindow().close()}
+                    aria-label="Close"
+                >
+                    ×
+                </button>
+            </div>
+            <ul className="flex-1 overflow-hidden px-3 pb-3 space-y-1.5">
+                {topTasks.length === 0 && (
+                    <li className="text-sm text-gray-400">All clear today</li>
+                )}
+                {topTasks.map(task => (
+                    <li key={task.id} className="text-sm truncate" title={task.title}>
+                        {task.title}
+                    </li>
+                ))}
+            </ul>
+        </div>
+    );
+};
@@ -1,4 +1,6 @@
 import Database from '@tauri-apps/plugin-sql';
+import { invoke } from '@tauri-apps/api/core';
+import { emit, listen } from '@tauri-apps/api/event';
 import { IStorageService } from '@tada/core/services/storageInterface';
 import {
     AISettings,
@@ -42,11 +44,16 @@ interface DbTask {
     tags: string | null;
     priority: number | null;
     group_category: string;
+    recurrence: string | null;
+    estimated_minutes: number | null;
+    actual_minutes: number;
+    pinned: number;
 }
 
 interface DbSubtask {
     id: string;
     parent_id: string;
+    parent_subtask_id: string | null;
     title: string;
     completed: number;
     completed_at: number | null;
@@ -62,6 +69,7 @@ interface DbList {
     icon: string | null;
     color: string | null;
     order: number | null;
+    archived: number;
     created_at: number;
     updated_at: number;
 }
@@ -85,6 +93,34 @@ interface DbEchoReport {
     user_input: string | null;
 }
 
+/** Stored as `apiKey` in the `ai` settings row; the backend keeps the real key in the OS keychain. */
+const AI_API_KEY_PLACEHOLDER = '__keychain__';
+
+// Upserts rather than `INSERT OR REPLACE`: with foreign keys enforced, REPLACE deletes the old
+// row first, which cascades to the task's subtasks, reminders and tags (and unlinks a list's
+// tasks). Rows written back are live again, as REPLACE left them
+const TASK_UPSERT = `
+    INSERT INTO tasks (
+        id, title, completed, completed_at, complete_percentage, due_date,
+        list_id, list_name, content, "order", created_at, updated_at,
+        tags, priority, group_category, recurrence, estimated_minutes, actual_minutes, pinned
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+    ON CONFLICT(id) DO UPDATE SET
+        title = excluded.title, completed = excluded.completed, completed_at = excluded.completed_at,
+        complete_percentage = excluded.complete_percentage, due_date = excluded.due_date,
+        list_id = excluded.list_id, list_name = excluded.list_name, content = excluded.content,
+        "order" = excluded."order", created_at = excluded.created_at, updated_at = excluded.updated_at,
+        tags = excluded.tags, priority = excluded.priority, group_category = excluded.group_category,
+        recurrence = excluded.recurrence, estimated_minutes = excluded.estimated_minutes,
+        actual_minutes = excluded.actual_minutes, pinned = excluded.pinned, deleted_at = NULL
+`;
+const LIST_UPSERT = `
+    INSERT INTO lists (id, name, icon, color, "order", archived, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+    ON CONFLICT(id) DO UPDATE SET
+        name = excluded.name, icon = excluded.icon, color = excluded.color, "order" = excluded."order",
+        archived = excluded.archived, updated_at = excluded.updated_at, deleted_at = NULL
+`;
+
 interface DbSetting {
     key: string;
     value: string;
@@ -97,6 +133,14 @@ interface BatchOperation {
     data: any;
 }
 
+/** What `app_ready` reports about the backend's boot */
+interface ReadyState {
+    ready: boolean;
+    migrationsComplete: boolean;
+    schemaVersion: number;
+    scheduleSettingsLoaded: boolean;
+}
+
 export class SqliteStorageService implements IStorageService {
     private db: Database | null = null;
     private listsCache: List[] = [];
@@ -122,12 +166,56 @@ export class SqliteStorageService implements IStorageService {
 
     async initialize(): Promise<void> {
         try {
-            this.db = await Database.load('sqlite:tada.db');
+            await this.waitForBackend();
+            // Preloaded by the backend, which reopens it in WAL mode; `load` would open it again
+            // with the plugin's defaults
+            this.db = Database.get('sqlite:tada.db');
             console.log('Database connected successfully');
 
             await this.ensureIndexes();
 
-            const lists = await this.db.select<DbList[]>('SELECT * FROM lists');
+            // Backend commands (timers, archiving, pinning) change rows behind the cache; keep it in step
+            // so later rewrites don't undo them
+            await listen<{ taskId: string; actualMinutes: number }>('task-time-tracked', ({ payload }) => {
+                const task = this.tasksCache.find(t => t.id === payload.taskId);
+                if (task) task.actualMinutes = payload.actualMinutes;
+            });
+            await listen<{ id: string; archived: boolean }>('list-archived', ({ payload }) => {
+                const list = this.listsCache.find(l => l.id === payload.id);
+                if (list) list.archived = payload.archived;
+            });
+            await listen<{ id: string; name: string }>('list-renamed', ({ payload }) => {
+                const list = this.listsCache.find(l => l.id === payload.id);
+                if (list) list.name = payload.name;
+            });
+            await listen<{ id: string; pinned: boolean }>('task-pinned', ({ payload }) => {
+                const task = this.tasksCache.find(t => t.id === payload.id);
+                if (task) task.pinned = payload.pinned;
+            });
+            await listen<StoredSummary>('summary-saved', ({ payload }) => {
+                const index = this.summariesCache.findIndex(s => s.id === payload.id);
+                if (index === -1) this.summariesCache.unshift(payload);
+                else this.summariesCache[index] = payload;
+            });
+            await listen<EchoReport>('echo-report-saved', ({ payload }) => {
+                if (!this.echoReportsCache.some(r => r.id === payload.id)) this.echoReportsCache.unshift(payload);
+            });
+            await listen<string>('echo-report-deleted', ({ payload }) => {
+                this.echoReportsCache = this.echoReportsCache.filter(r => r.id !== payload);
+            });
+            // Bulk updates come from the backend with the ids; this window's own broadcasts carry none
+            await listen<{ ids?: string[] } | null>('tasks-changed', async ({ payload }) => {
+                if (payload?.ids) this.tasksCache = await this.fetchTasksAsync();
+            });
+            await listen('tasks-rolled-over', async () => {
+                this.tasksCache = await this.fetchTasksAsync();
+            });
+            // An import rewrites whole tables, so start over from the database rather than patching
+            await listen('data-imported', () => {
+                window.location.reload();
+            });
+
+            const lists = await this.db.select<DbList[]>('SELECT * FROM lists WHERE deleted_at IS NULL');
             if (lists.length === 0) {
                 console.warn('No lists found! Creating default Inbox...');
                 const now = Date.now();
@@ -142,6 +230,29 @@ export class SqliteStorageService implements IStorageService {
         }
     }
 
+    /**
+     * Resolves once the backend has finished setup, so nothing reads the database before its migrations
+     * have run and the pool has been reopened. Listens first and then asks, in case setup finished before
+     * the listener was registered
+     */
+    private async waitForBackend(): Promise<void> {
+        let markReady: (state: ReadyState) => void = () => {};
+        const announced = new Promise<ReadyState>(resolve => { markReady = resolve; });
+        const unlisten = await listen<ReadyState>('app-ready', ({ payload }) => markReady(payload));
+        try {
+            const current = await invoke<ReadyState>('app_ready');
+            const state = current.ready ? current : await announced;
+            if (!state.migrationsComplete) {
+                throw new Error(`Database migrations did not complete (schema version ${state.schemaVersion})`);
+            }
+            if (!state.scheduleSettingsLoaded) {
+                console.warn('Schedule settings could not be loaded; the scheduler is using defaults');
+            }
+        } finally {
+            unlisten();
+        }
+    }
+
     private async ensureIndexes(): Promise<void> {
         const db = this.getDb();
         try {
@@ -227,7 +338,12 @@ export class SqliteStorageService implements IStorageService {
                 else if (op.table === 'lists') await this.updateListInDb(op.data.id, op.data.updates);
                 break;
             case 'delete':
-                await db.execute(`DELETE FROM ${op.table} WHERE id = ?`, [op.data.id]);
+                // Tasks and lists are soft-deleted and can be restored from the trash
+                if (op.table === 'tasks' || op.table === 'lists') {
+                    await db.execute(`UPDATE ${op.table} SET deleted_at = ? WHERE id = ?`, [Date.now(), op.data.id]);
+                } else {
+                    await db.execute(`DELETE FROM ${op.table} WHERE id = ?`, [op.data.id]);
+                }
                 break;
         }
     }
@@ -252,6 +368,12 @@ export class SqliteStorageService implements IStorageService {
             }
         }
         this.isProcessingQueue = false;
+        // Keep the tray's tooltip, overdue badge and "Recent" submenu in step with the data just written
+        invoke('refresh_tray_tooltip').catch(error => console.error('Failed to refresh tray tooltip:', error));
+        invoke('refresh_tray_badge').catch(error => console.error('Failed to refresh tray badge:', error));
+        invoke('refresh_tray_menu').catch(error => console.error('Failed to refresh tray menu:', error));
+        // Other windows (the focus widget) keep their own caches and reload on this
+        emit('tasks-changed').catch(error => console.error('Failed to broadcast tasks-changed:', error));
     }
 
     // Settings
@@ -287,6 +409,14 @@ export class SqliteStorageService implements IStorageService {
                 console.error(`Failed to parse setting ${setting.key}:`, error);
             }
         });
+        if (result.ai.apiKey === AI_API_KEY_PLACEHOLDER) {
+            try {
+                result.ai.apiKey = await invoke<string>('get_ai_api_key');
+            } catch (error) {
+                console.error('Failed to read the AI API key from the keychain:', error);
+                result.ai.apiKey = '';
+            }
+        }
         this.settingsCache = result;
         return result;
     }
@@ -316,7 +446,10 @@ export class SqliteStorageService implements IStorageService {
         this.queueWrite(async () => {
             const db = this.getDb();
             const now = Date.now();
-            await db.execute('INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES (?, ?, ?)', ['ai', JSON.stringify(settings), now]);
+            // The key itself goes to the OS keychain; the row only keeps a placeholder
+            await invoke('set_ai_api_key', { key: settings.apiKey });
+            const stored = { ...settings, apiKey: settings.apiKey ? AI_API_KEY_PLACEHOLDER : '' };
+            await db.execute('INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES (?, ?, ?)', ['ai', JSON.stringify(stored), now]);
         });
         return settings;
     }
@@ -337,16 +470,12 @@ export class SqliteStorageService implements IStorageService {
     }
 
     async fetchUserProfileAsync(): Promise<UserProfile | null> {
-        const db = this.getDb();
-        const settings = await db.select<DbSetting[]>('SELECT * FROM settings WHERE key = ?', ['userProfile']);
-        if (settings.length > 0) {
-            try {
-                const parsed = JSON.parse(settings[0].value) as UserProfile;
-                this.userProfileCache = parsed;
-                return parsed;
-            } catch (error) {
-                console.error('Failed to parse user profile:', error);
-            }
+        try {
+            const profile = await invoke<UserProfile>('get_user_profile');
+            this.userProfileCache = profile;
+            return profile;
+        } catch (error) {
+            console.error('Failed to load user profile:', error);
         }
         return null;
     }
@@ -355,9 +484,18 @@ export class SqliteStorageService implements IStorageService {
         const updated = { ...profile, updatedAt: Date.now() };
         this.userProfileCache = updated;
         this.queueWrite(async () => {
-            const db = this.getDb();
-            const now = Date.now();
-            await db.execute('INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES (?, ?, ?)', ['userProfile', JSON.stringify(updated), now]);
+            const { workRealityModel } = updated;
+            await invoke('update_user_profile', {
+                patch: {
+                    persona: updated.persona,
+                    taskView: workRealityModel.taskView,
+                    uncertaintyTolerance: workRealityModel.uncertaintyTolerance,
+                    incompletionStyle: workRealityModel.incompletionStyle,
+                    confidence: workRealityModel.confidence,
+                    userNote: updated.userNote,
+                    onboardingCompleted: updated.onboardingCompleted
+                }
+            });
         });
         return updated;
     }
@@ -369,7 +507,7 @@ export class SqliteStorageService implements IStorageService {
 
     async fetchListsAsync(): Promise<List[]> {
         const db = this.getDb();
-        const dbLists = await db.select<DbList[]>('SELECT * FROM lists ORDER BY "order", name');
+        const dbLists = await db.select<DbList[]>('SELECT * FROM lists WHERE deleted_at IS NULL ORDER BY "order", name');
         const lists = dbLists.map(this.mapDbListToList);
         this.listsCache = lists;
         return lists;
@@ -394,8 +532,8 @@ export class SqliteStorageService implements IStorageService {
         const db = this.getDb();
         const now = Date.now();
         await db.execute(
-            'INSERT INTO lists (id, name, icon, color, "order", created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)',
-            [list.id, list.name, list.icon, list.color, list.order, now, now]
+            'INSERT INTO lists (id, name, icon, color, "order", archived, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)',
+            [list.id, list.name, list.icon, list.color, list.order, list.archived ? 1 : 0, now, now]
         );
     }
 
@@ -430,6 +568,7 @@ export class SqliteStorageService implements IStorageService {
         if (updates.icon !== undefined) { updateFields.push('icon = ?'); values.push(updates.icon); }
         if (updates.color !== undefined) { updateFields.push('color = ?'); values.push(updates.color); }
         if (updates.order !== undefined) { updateFields.push('"order" = ?'); values.push(updates.order); }
+        if (updates.archived !== undefined) { updateFields.push('archived = ?'); values.push(updates.archived ? 1 : 0); }
         if (updateFields.length === 0) return;
         updateFields.push('updated_at = ?');
         values.push(now, listId);
@@ -458,7 +597,7 @@ export class SqliteStorageService implements IStorageService {
             await db.execute('BEGIN TRANSACTION');
             try {
                 await db.execute(`UPDATE tasks SET list_id = CASE WHEN list_name = 'Trash' THEN NULL ELSE ? END, list_name = CASE WHEN list_name = 'Trash' THEN list_name ELSE ? END, updated_at = ? WHERE list_id = ?`, [inbox.id, inbox.name, now, listId]);
-                await db.execute('DELETE FROM lists WHERE id = ?', [listId]);
+                await db.execute('UPDATE lists SET deleted_at = ? WHERE id = ?', [now, listId]);
                 await db.execute('COMMIT');
             } catch (error) {
                 await db.execute('ROLLBACK');
@@ -475,9 +614,10 @@ export class SqliteStorageService implements IStorageService {
             const now = Date.now();
             await db.execute('BEGIN TRANSACTION');
             try {
-                await db.execute('DELETE FROM lists');
+                // Lists missing from `lists` stay behind as deleted; the rest are written back live
+                await db.execute('UPDATE lists SET deleted_at = ? WHERE deleted_at IS NULL', [now]);
                 for (const list of lists) {
-                    await db.execute('INSERT INTO lists (id, name, icon, color, "order", created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)', [list.id, list.name, list.icon || null, list.color || null, list.order || 0, now, now]);
+                    await db.execute(LIST_UPSERT, [list.id, list.name, list.icon || null, list.color || null, list.order || 0, list.archived ? 1 : 0, now, now]);
                 }
                 await db.execute('COMMIT');
             } catch (error) {
@@ -496,7 +636,7 @@ export class SqliteStorageService implements IStorageService {
     async fetchTasksAsync(): Promise<Task[]> {
         const db = this.getDb();
         const [dbTasks, dbSubtasks] = await Promise.all([
-            db.select<DbTask[]>('SELECT * FROM tasks ORDER BY "order", created_at'),
+            db.select<DbTask[]>('SELECT * FROM tasks WHERE deleted_at IS NULL ORDER BY pinned DESC, "order", created_at'),
             db.select<DbSubtask[]>('SELECT * FROM subtasks ORDER BY parent_id, "order"')
         ]);
         const subtasksByParent: Record<string, Subtask[]> = {};
@@ -525,11 +665,8 @@ export class SqliteStorageService implements IStorageService {
 
     private async insertTask(task: Task): Promise<void> {
         const db = this.getDb();
-        await db.execute(`
-            INSERT INTO tasks (id, title, completed, completed_at, complete_percentage, due_date, list_id, list_name, content, "order", created_at, updated_at, tags, priority, group_category)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-        `, [
-            task.id, task.title, task.completed ? 1 : 0, task.completedAt, task.completePercentage, task.dueDate || null, task.listId, task.listName, task.content || null, task.order, task.createdAt, task.updatedAt, task.tags ? JSON.stringify(task.tags) : null, task.priority, task.groupCategory
+        await db.execute(TASK_UPSERT, [
+            task.id, task.title, task.completed ? 1 : 0, task.completedAt, task.completePercentage, task.dueDate || null, task.listId, task.listName, task.content || null, task.order, task.createdAt, task.updatedAt, task.tags ? JSON.stringify(task.tags) : null, task.priority, task.groupCategory, task.recurrence || null, task.estimatedMinutes ?? null, task.actualMinutes ?? 0, task.pinned ? 1 : 0
         ]);
     }
 
@@ -538,10 +675,31 @@ export class SqliteStorageService implements IStorageService {
         if (index === -1) throw new Error("Task not found");
         const now = Date.now();
         this.tasksCache[index] = { ...this.tasksCache[index], ...updates, updatedAt: now };
-        this.queueWrite(async () => { await this.updateTaskInDb(taskId, updates); });
+        const repeats = updates.completed === true && Boolean(this.tasksCache[index].recurrence);
+        this.queueWrite(async () => {
+            await this.updateTaskInDb(taskId, updates);
+            if (repeats) await this.materializeRecurringTasks();
+            // Emits 'dependencies-unblocked' for tasks that were only waiting on this one
+            if (updates.completed === true) await invoke('check_unblocked_tasks', { taskId });
+        });
         return this.tasksCache[index];
     }
 
+    /**
+     * Has the backend create the next instance of each completed recurring task, then adds
+     * them to the cache and tells the task store to reload.
+     */
+    private async materializeRecurringTasks(): Promise<void> {
+        const created = await invoke<Task[]>('materialize_recurring_tasks');
+        if (created.length === 0) return;
+        const createdIds = new Set(created.map(t => t.id));
+        this.tasksCache = this.tasksCache
+            .map(t => t.recurrence && t.completed && t.listName !== 'Trash' ? { ...t, recurrence: null } : t)
+            .filter(t => !createdIds.has(t.id))
+            .concat(created);
+        await emit('recurring-tasks-created', created.map(t => t.id));
+    }
+
     private async updateTaskInDb(taskId: string, updates: Partial<Task>): Promise<void> {
         const db = this.getDb();
         const now = Date.now();
@@ -561,6 +719,9 @@ export class SqliteStorageService implements IStorageService {
                 case 'tags': updateFields.push('tags = ?'); values.push(value ? JSON.stringify(value) : null); break;
                 case 'priority': updateFields.push('priority = ?'); values.push(value); break;
                 case 'groupCategory': updateFields.push('group_category = ?'); values.push(value); break;
+                case 'recurrence': updateFields.push('recurrence = ?'); values.push(value || null); break;
+                case 'estimatedMinutes': updateFields.push('estimated_minutes = ?'); values.push(value ?? null); break;
+                case 'pinned': updateFields.push('pinned = ?'); values.push(value ? 1 : 0); break;
             }
         });
         if (updateFields.length === 0) return;
@@ -573,8 +734,8 @@ export class SqliteStorageService implements IStorageService {
         this.tasksCache = this.tasksCache.filter(t => t.id !== taskId);
         this.queueWrite(async () => {
             const db = this.getDb();
-            await db.execute('DELETE FROM subtasks WHERE parent_id = ?', [taskId]);
-            await db.execute('DELETE FROM tasks WHERE id = ?', [taskId]);
+            // Subtasks stay with the task so restoring it from the trash brings them back
+            await db.execute('UPDATE tasks SET deleted_at = ? WHERE id = ?', [Date.now(), taskId]);
         });
     }
 
@@ -585,14 +746,15 @@ export class SqliteStorageService implements IStorageService {
             const now = Date.now();
             await db.execute('BEGIN TRANSACTION');
             try {
-                await db.execute('DELETE FROM subtasks');
-                await db.execute('DELETE FROM tasks');
+                // Tasks missing from `tasks` stay behind as deleted; the rest are written back live
+                await db.execute('DELETE FROM subtasks WHERE parent_id IN (SELECT id FROM tasks WHERE deleted_at IS NULL)');
+                await db.execute('UPDATE tasks SET deleted_at = ? WHERE deleted_at IS NULL', [now]);
                 for (const task of tasks) {
                     await this.insertTask(task);
                     if (task.subtasks) {
                         for (const subtask of task.subtasks) {
-                            await db.execute(`INSERT INTO subtasks (id, parent_id, title, completed, completed_at, due_date, "order", created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)`,
-                                [subtask.id, subtask.parentId, subtask.title, subtask.completed ? 1 : 0, subtask.completedAt, subtask.dueDate || null, subtask.order, subtask.createdAt, subtask.updatedAt]);
+                            await db.execute(`INSERT INTO subtasks (id, parent_id, parent_subtask_id, title, completed, completed_at, due_date, "order", created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
+                                [subtask.id, subtask.parentId, subtask.parentSubtaskId || null, subtask.title, subtask.completed ? 1 : 0, subtask.completedAt, subtask.dueDate || null, subtask.order, subtask.createdAt, subtask.updatedAt]);
                         }
                     }
                 }
@@ -617,14 +779,8 @@ export class SqliteStorageService implements IStorageService {
             await db.execute('BEGIN TRANSACTION');
             try {
                 for (const task of tasks) {
-                    await db.execute(`
-                        INSERT OR REPLACE INTO tasks (
-                            id, title, completed, completed_at, complete_percentage, due_date, 
-                            list_id, list_name, content, "order", created_at, updated_at, 
-                            tags, priority, group_category
-                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                    `, [
-                        task.id, task.title, task.completed ? 1 : 0, task.completedAt, task.completePercentage, task.dueDate || null, task.listId, task.listName, task.content || null, task.order, task.createdAt, task.updatedAt, task.tags ? JSON.stringify(task.tags) : null, task.priority, task.groupCategory
+                    await db.execute(TASK_UPSERT, [
+                        task.id, task.title, task.completed ? 1 : 0, task.completedAt, task.completePercentage, task.dueDate || null, task.listId, task.listName, task.content || null, task.order, task.createdAt, task.updatedAt, task.tags ? JSON.stringify(task.tags) : null, task.priority, task.groupCategory, task.recurrence || null, task.estimatedMinutes ?? null, task.actualMinutes ?? 0, task.pinned ? 1 : 0
                     ]);
                 }
                 await db.execute('COMMIT');
@@ -643,7 +799,7 @@ export class SqliteStorageService implements IStorageService {
             await db.execute('BEGIN TRANSACTION');
             try {
                 for (const list of lists) {
-                    await db.execute('INSERT OR REPLACE INTO lists (id, name, icon, color, "order", created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)', [list.id, list.name, list.icon || null, list.color || null, list.order || 0, now, now]);
+                    await db.execute(LIST_UPSERT, [list.id, list.name, list.icon || null, list.color || null, list.order || 0, list.archived ? 1 : 0, now, now]);
                 }
                 await db.execute('COMMIT');
             } catch (error) {
@@ -661,10 +817,10 @@ export class SqliteStorageService implements IStorageService {
     }
 
     // Subtasks
-    createSubtask(taskId: string, subtaskData: { title: string; order: number; dueDate: number | null }): Subtask {
+    createSubtask(taskId: string, subtaskData: { title: string; order: number; dueDate: number | null; parentSubtaskId?: string | null }): Subtask {
         const now = Date.now();
         const id = `subtask-${now}-${Math.random()}`;
-        const newSubtask: Subtask = { id, parentId: taskId, title: subtaskData.title, completed: false, completedAt: null, order: subtaskData.order, dueDate: subtaskData.dueDate, createdAt: now, updatedAt: now };
+        const newSubtask: Subtask = { id, parentId: taskId, parentSubtaskId: subtaskData.parentSubtaskId || null, title: subtaskData.title, completed: false, completedAt: null, order: subtaskData.order, dueDate: subtaskData.dueDate, createdAt: now, updatedAt: now };
 
         const taskIndex = this.tasksCache.findIndex(t => t.id === taskId);
         if (taskIndex !== -1) {
@@ -674,8 +830,8 @@ export class SqliteStorageService implements IStorageService {
 
         this.queueWrite(async () => {
             const db = this.getDb();
-            await db.execute(`INSERT INTO subtasks (id, parent_id, title, completed, completed_at, due_date, "order", created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)`,
-                [newSubtask.id, newSubtask.parentId, newSubtask.title, newSubtask.completed ? 1 : 0, newSubtask.completedAt, newSubtask.dueDate, newSubtask.order, newSubtask.createdAt, newSubtask.updatedAt]);
+            await db.execute(`INSERT INTO subtasks (id, parent_id, parent_subtask_id, title, completed, completed_at, due_date, "order", created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
+                [newSubtask.id, newSubtask.parentId, newSubtask.parentSubtaskId, newSubtask.title, newSubtask.completed ? 1 : 0, newSubtask.completedAt, newSubtask.dueDate, newSubtask.order, newSubtask.createdAt, newSubtask.updatedAt]);
         });
         return newSubtask;
     }
@@ -718,14 +874,30 @@ export class SqliteStorageService implements IStorageService {
     }
 
     deleteSubtask(subtaskId: string): void {
+        // Nested subtasks go with their parent
         for (const task of this.tasksCache) {
             if (task.subtasks) {
-                task.subtasks = task.subtasks.filter(s => s.id !== subtaskId);
+                const removed = new Set([subtaskId]);
+                let grew = true;
+                while (grew) {
+                    grew = false;
+                    for (const s of task.subtasks) {
+                        if (s.parentSubtaskId && removed.has(s.parentSubtaskId) && !removed.has(s.id)) {
+                            removed.add(s.id);
+                            grew = true;
+                        }
+                    }
+                }
+                task.subtasks = task.subtasks.filter(s => !removed.has(s.id));
             }
         }
         this.queueWrite(async () => {
             const db = this.getDb();
-            await db.execute('DELETE FROM subtasks WHERE id = ?', [subtaskId]);
+            await db.execute(`WITH RECURSIVE descendants(id) AS (
+                SELECT id FROM subtasks WHERE id = ?
+                UNION ALL
+                SELECT s.id FROM subtasks s JOIN descendants d ON s.parent_subtask_id = d.id
+            ) DELETE FROM subtasks WHERE id IN (SELECT id FROM descendants)`, [subtaskId]);
         });
     }
 
@@ -1040,7 +1212,7 @@ export class SqliteStorageService implements IStorageService {
     }
 
     private mapDbListToList(dbList: DbList): List {
-        return { id: dbList.id, name: dbList.name, icon: dbList.icon, color: dbList.color, order: dbList.order };
+        return { id: dbList.id, name: dbList.name, icon: dbList.icon, color: dbList.color, order: dbList.order, archived: Boolean(dbList.archived) };
     }
 
     private mapDbTaskToTask(dbTask: DbTask): Task {
@@ -1066,13 +1238,15 @@ export class SqliteStorageService implements IStorageService {
             completePercentage: dbTask.complete_percentage, dueDate: dbTask.due_date, listId: dbTask.list_id,
             listName: dbTask.list_name, content: dbTask.content || undefined, order: dbTask.order,
             createdAt: dbTask.created_at, updatedAt: dbTask.updated_at, tags: parsedTags,
-            priority: dbTask.priority, groupCategory: dbTask.group_category as any, subtasks: []
+            priority: dbTask.priority, groupCategory: dbTask.group_category as any, recurrence: dbTask.recurrence,
+            estimatedMinutes: dbTask.estimated_minutes, actualMinutes: dbTask.actual_minutes ?? 0,
+            pinned: Boolean(dbTask.pinned), subtasks: []
         };
     }
 
     private mapDbSubtaskToSubtask(dbSubtask: DbSubtask): Subtask {
         return {
-            id: dbSubtask.id, parentId: dbSubtask.parent_id, title: dbSubtask.title, completed: Boolean(dbSubtask.completed),
+            id: dbSubtask.id, parentId: dbSubtask.parent_id, parentSubtaskId: dbSubtask.parent_subtask_id, title: dbSubtask.title, completed: Boolean(dbSubtask.completed),
             completedAt: dbSubtask.completed_at, dueDate: dbSubtask.due_date, order: dbSubtask.order,
             createdAt: dbSubtask.created_at, updatedAt: dbSubtask.updated_at
         };